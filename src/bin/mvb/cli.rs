@@ -1,8 +1,13 @@
+use std::path::{Path, PathBuf};
+
 use clap::{crate_version, AppSettings, Parser};
 use common::color::{parse_color, COLOR_CHOICES};
 use common::help::highlight_static;
 use common::run::Options;
-use common::transfer::TransferOptions;
+use common::transfer::{
+    parse_overwrite_policy, parse_symlink_mode, BackupMode, OverwritePolicy, SymlinkMode,
+    TransferOptions, OVERWRITE_POLICIES, SYMLINK_MODES,
+};
 use indoc::indoc;
 use termcolor::ColorChoice;
 
@@ -27,15 +32,66 @@ use termcolor::ColorChoice;
 
             $> find -name '*.jpeg' | rew -d '{B}.jpg' | mvb # Rename all *.jpeg files to *.jpg
 
-        Each pair of source and destination path must be either both files or both directories. Mixing these types will result in error.
+        Each pair of source and destination path must be either both files or both directories. Mixing these types will result in error. Symlink (unless followed) can only replace a file or another symlink.
 
         Source path must exist. Using non-existent source path will result in error.
 
-        Destination path may exist. Existing destination file will be overwritten. Existing destination directory will have its contents merged with contents of source directory.
+        Destination path may exist. By default, existing destination file will be overwritten and existing destination directory will have its contents merged with contents of source directory. This can be changed using `-o, --overwrite` option:
+
+            - `always` - Overwrite destination path (default).
+            - `skip`   - Skip transfer of source path.
+            - `fail`   - Fail with an error.
+            - `newer`  - Overwrite destination path only when source path was modified later.
+            - `backup` - Rename destination path before the transfer. Numbered backup `<name>.~<N>~` is made, unless `-B, --backup-suffix` option is used (then `<name><suffix>` backup is made).
+            - `prompt` - Ask user whether to overwrite destination path (answer is read from terminal).
 
         Missing parent directories in destination path will be created as needed.
 
+        Path which cannot be renamed (e.g. when moved to a different file system) is copied and then deleted. Use `-m, --preserve` flag to keep its permissions, ownership (when permitted), timestamps and extended attributes (Linux only).
+
+        Symlinks are handled according to `-y, --symlinks` option:
+
+            - `keep`     - Move symlink itself, its target stays the same (default).
+            - `retarget` - Move symlink itself, its relative target is changed to point to the same path from the new location. This applies also to symlinks inside moved directory, unless their target is inside the directory too.
+            - `follow`   - Replace symlink with a copy of its target.
+            - `skip`     - Do not move symlinks.
+
+        Symlinks inside moved directories are never followed nor skipped. Existing destination symlink is always replaced, it is never followed.
+
         Nothing will be done if source and destination paths point to the same file or directory.
+
+        Instructions are not processed in their input order. Moves are reordered so no path is overwritten before being moved itself:
+
+            <a        <b
+            >b        >c
+            <b   =>   <a
+            >c        >b
+
+        Cycles are broken using a temporary path in the same directory:
+
+            <a        <a
+            >b        >a.mvb-tmp
+            <b   =>   <b
+            >a        >a
+                      <a.mvb-tmp
+                      >b
+
+        Use `-k, --check` flag to validate all instructions before any path is transferred. The following problems are reported:
+
+            - Source path does not exist.
+            - Source and destination path types (file/directory/symlink) do not match.
+            - Multiple source paths have the same destination path.
+            - Existing destination path is overwritten before being used as a source path of some later instruction.
+
+        Nothing is transferred when any problem is found. Use `-n, --dry-run` flag to only run the validation.
+
+        Use `-A, --transaction` flag to make the whole batch atomic. When any move fails, all previously completed moves are reverted (in reverse order), overwritten files are restored and created parent directories are removed. Overwritten files are kept aside until the batch succeeds. Directories are merged with existing ones, so their overwritten contents cannot be restored.
+
+        Use `-P, --progress` flag to report progress. Total count and size of paths is computed before any path is moved. Progress bar (including progress of the file currently being copied) is shown when standard output is a terminal, otherwise a progress line is printed every 5 seconds and once at the end. Moves within the same file system are instant, so their size is counted only when the move finishes.
+
+        Use `-j, --json-lines` flag to print a JSON object for each processed instruction instead of human-readable output. It contains `src`, `dst`, `action` (`move`/`copy`), `status` (`ok`/`failed`/`skipped`), `error` message (or `null`), number of copied `bytes` (zero for paths just renamed) and `duration_ms`. The final summary object contains `total`, `ok`, `failed` and `skipped` counts, total `bytes`, `duration_ms` and whether the transfers were `rolled_back`.
+
+        Use `-W, --journal` option to record all performed transfers (and created parent directories) in a file. The journal can be later passed to `-u, --undo` option which moves back destination paths to their source paths and removes created parent directories (if they are empty). Paths are recorded as absolute, so the journal can be undone from any working directory. Besides `<src`/`>dst` pairs, the journal contains `+dir` lines for created directories, `~path` lines followed by `>backup` for backups and `!path` lines for overwritten paths, so it cannot be passed to standard input as plain instructions.
     "}),
     after_help = highlight_static("Use `-h` for short descriptions and `--help` for more details."),
     setting(AppSettings::DeriveDisplayOrder),
@@ -51,10 +107,74 @@ pub struct Cli {
     #[clap(short = 'F', long)]
     pub fail_at_end: bool,
 
+    /// Revert all completed moves after a failure
+    #[clap(short = 'A', long, conflicts_with = "fail-at-end")]
+    pub transaction: bool,
+
+    /// What to do with existing destination paths
+    #[clap(
+        short = 'o',
+        long,
+        value_name = "policy",
+        possible_values = OVERWRITE_POLICIES,
+        parse(try_from_str = parse_overwrite_policy),
+        default_value = "always",
+    )]
+    pub overwrite: OverwritePolicy,
+
+    /// Suffix for backups of existing destination paths
+    #[clap(short = 'B', long, value_name = "suffix")]
+    pub backup_suffix: Option<String>,
+
+    /// Preserve permissions, ownership, timestamps and extended attributes
+    #[clap(short = 'm', long)]
+    pub preserve: bool,
+
+    /// How to handle symlinks
+    #[clap(
+        short = 'y',
+        long,
+        value_name = "mode",
+        possible_values = SYMLINK_MODES,
+        parse(try_from_str = parse_symlink_mode),
+        default_value = "keep",
+    )]
+    pub symlinks: SymlinkMode,
+
+    /// Check all instructions before transferring any path
+    #[clap(short = 'k', long)]
+    pub check: bool,
+
+    /// Only check instructions, do not transfer anything
+    #[clap(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// Write journal of performed transfers to a file
+    #[clap(short = 'W', long, value_name = "path", parse(from_os_str))]
+    pub journal: Option<PathBuf>,
+
+    /// Undo transfers recorded in a journal file
+    #[clap(
+        short = 'u',
+        long,
+        value_name = "path",
+        parse(from_os_str),
+        conflicts_with_all = &["check", "dry-run", "journal", "transaction"],
+    )]
+    pub undo: Option<PathBuf>,
+
     /// Explain what is being done
     #[clap(short = 'v', long)]
     pub verbose: bool,
 
+    /// Report progress with byte counts and ETA
+    #[clap(short = 'P', long)]
+    pub progress: bool,
+
+    /// Print results as JSON lines
+    #[clap(short = 'j', long, conflicts_with_all = &["verbose", "progress", "undo"])]
+    pub json_lines: bool,
+
     /// When to use colors
     #[clap(
         long,
@@ -88,9 +208,54 @@ impl TransferOptions for Cli {
         self.verbose
     }
 
+    fn progress(&self) -> bool {
+        self.progress
+    }
+
+    fn json_lines(&self) -> bool {
+        self.json_lines
+    }
+
     fn fail_at_end(&self) -> bool {
         self.fail_at_end
     }
+
+    fn preserve(&self) -> bool {
+        self.preserve
+    }
+
+    fn symlink_mode(&self) -> SymlinkMode {
+        self.symlinks
+    }
+
+    fn check(&self) -> bool {
+        self.check
+    }
+
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    fn journal(&self) -> Option<&Path> {
+        self.journal.as_deref()
+    }
+
+    fn undo(&self) -> Option<&Path> {
+        self.undo.as_deref()
+    }
+
+    fn overwrite_policy(&self) -> OverwritePolicy {
+        match (&self.overwrite, &self.backup_suffix) {
+            (OverwritePolicy::Backup(_), Some(suffix)) => {
+                OverwritePolicy::Backup(BackupMode::Suffix(suffix.clone()))
+            }
+            (policy, _) => policy.clone(),
+        }
+    }
+
+    fn transaction(&self) -> bool {
+        self.transaction
+    }
 }
 
 #[cfg(test)]
@@ -117,13 +282,102 @@ mod tests {
         assert_eq!(run(args).verbose(), result);
     }
 
+    #[test_case(&[],             false ; "off")]
+    #[test_case(&["--progress"], true  ; "on")]
+    fn progress(args: &[&str], result: bool) {
+        assert_eq!(run(args).progress(), result);
+    }
+
+    #[test_case(&[],               false ; "off")]
+    #[test_case(&["--json-lines"], true  ; "on")]
+    fn json_lines(args: &[&str], result: bool) {
+        assert_eq!(run(args).json_lines(), result);
+    }
+
     #[test_case(&[],                false ; "off")]
     #[test_case(&["--fail-at-end"], true  ; "on")]
     fn fail_at_end(args: &[&str], result: bool) {
         assert_eq!(run(args).fail_at_end(), result);
     }
 
+    #[test_case(&[],                false ; "off")]
+    #[test_case(&["--transaction"], true  ; "on")]
+    fn transaction(args: &[&str], result: bool) {
+        assert_eq!(run(args).transaction(), result);
+    }
+
+    #[test]
+    fn transaction_conflict() {
+        assert!(Cli::try_parse_from(["mvb", "--transaction", "--fail-at-end"]).is_err());
+    }
+
+    #[test_case(&[],                                          OverwritePolicy::Always                                        ; "default")]
+    #[test_case(&["--overwrite=skip"],                        OverwritePolicy::Skip                                          ; "skip")]
+    #[test_case(&["--overwrite=fail"],                        OverwritePolicy::Fail                                          ; "fail")]
+    #[test_case(&["--overwrite=newer"],                       OverwritePolicy::Newer                                         ; "newer")]
+    #[test_case(&["--overwrite=backup"],                      OverwritePolicy::Backup(BackupMode::Numbered)                  ; "backup numbered")]
+    #[test_case(&["--overwrite=backup", "--backup-suffix=~"], OverwritePolicy::Backup(BackupMode::Suffix(String::from("~"))) ; "backup suffix")]
+    #[test_case(&["--overwrite=prompt"],                      OverwritePolicy::Prompt                                        ; "prompt")]
+    #[test_case(&["--backup-suffix=~"],                       OverwritePolicy::Always                                        ; "suffix only")]
+    fn overwrite_policy(args: &[&str], result: OverwritePolicy) {
+        assert_eq!(run(args).overwrite_policy(), result);
+    }
+
+    #[test_case(&[],             false ; "off")]
+    #[test_case(&["--preserve"], true  ; "on")]
+    fn preserve(args: &[&str], result: bool) {
+        assert_eq!(run(args).preserve(), result);
+    }
+
+    #[test_case(&[],                      SymlinkMode::Keep     ; "default")]
+    #[test_case(&["--symlinks=keep"],     SymlinkMode::Keep     ; "keep")]
+    #[test_case(&["--symlinks=retarget"], SymlinkMode::Retarget ; "retarget")]
+    #[test_case(&["--symlinks=follow"],   SymlinkMode::Follow   ; "follow")]
+    #[test_case(&["--symlinks=skip"],     SymlinkMode::Skip     ; "skip")]
+    fn symlink_mode(args: &[&str], result: SymlinkMode) {
+        assert_eq!(run(args).symlink_mode(), result);
+    }
+
+    #[test_case(&[],          false ; "off")]
+    #[test_case(&["--check"], true  ; "on")]
+    fn check(args: &[&str], result: bool) {
+        assert_eq!(run(args).check(), result);
+    }
+
+    #[test_case(&[],            false ; "off")]
+    #[test_case(&["--dry-run"], true  ; "on")]
+    fn dry_run(args: &[&str], result: bool) {
+        assert_eq!(run(args).dry_run(), result);
+    }
+
+    #[test_case(&[],                None        ; "default")]
+    #[test_case(&["--journal=a/b"], Some("a/b") ; "some")]
+    fn journal(args: &[&str], result: Option<&str>) {
+        assert_eq!(run(args).journal(), result.map(Path::new));
+    }
+
+    #[test_case(&[],             None        ; "default")]
+    #[test_case(&["--undo=a/b"], Some("a/b") ; "some")]
+    fn undo(args: &[&str], result: Option<&str>) {
+        assert_eq!(run(args).undo(), result.map(Path::new));
+    }
+
+    #[test_case(&["--undo=a", "--check"]       ; "check")]
+    #[test_case(&["--undo=a", "--dry-run"]     ; "dry run")]
+    #[test_case(&["--undo=a", "--journal=b"]   ; "journal")]
+    #[test_case(&["--undo=a", "--transaction"] ; "transaction")]
+    fn undo_conflict(args: &[&str]) {
+        assert!(Cli::try_parse_from([&["mvb"], args].concat()).is_err());
+    }
+
+    #[test_case(&["--json-lines", "--verbose"]  ; "verbose")]
+    #[test_case(&["--json-lines", "--progress"] ; "progress")]
+    #[test_case(&["--json-lines", "--undo=a"]   ; "undo")]
+    fn json_lines_conflict(args: &[&str]) {
+        assert!(Cli::try_parse_from([&["mvb"], args].concat()).is_err());
+    }
+
     fn run(args: &[&str]) -> Cli {
-        Cli::try_parse_from(&[&["mvb"], args].concat()).unwrap()
+        Cli::try_parse_from([&["mvb"], args].concat()).unwrap()
     }
 }
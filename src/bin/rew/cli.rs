@@ -10,6 +10,8 @@ use termcolor::ColorChoice;
 
 use crate::counter;
 use crate::pattern::eval;
+use crate::pattern::glob::GlobHolder;
+use crate::pattern::normalize::{parse_normalization, Normalization, NORMALIZATION_FORMS};
 
 const INPUT_HEADING: Option<&str> = Some("INPUT OPTIONS");
 const OUTPUT_HEADING: Option<&str> = Some("OUTPUT OPTIONS");
@@ -91,6 +93,20 @@ pub struct Cli {
     )]
     pub no_stdin: bool,
 
+    /// Normalize input values to a Unicode normalization form
+    ///
+    /// Pattern is evaluated using the normalized value.
+    /// Filters reading a file (metadata, EXIF, audio, file date/hash, path conditions) still use the original input value as its path.
+    /// Diff, pretty and JSON lines output modes still print the original input value.
+    #[clap(
+        long,
+        value_name = "form",
+        possible_values = NORMALIZATION_FORMS,
+        parse(try_from_str = parse_normalization),
+        help_heading = INPUT_HEADING
+    )]
+    pub normalize_input: Option<Normalization>,
+
     /// Print results terminated by a specific string, not newline
     #[clap(
         short = 'T',
@@ -221,6 +237,32 @@ pub struct Cli {
     )]
     pub regex_filename: Option<Regex>,
 
+    /// Glob matched against each input value
+    ///
+    /// Every wildcard is captured, captures are available as '$1', '$2', ...
+    #[clap(
+        short = 'g',
+        long,
+        value_name = "glob",
+        conflicts_with_all = &["regex", "regex-filename", "glob-filename"],
+        verbatim_doc_comment,
+        help_heading = PROCESSING_HEADING,
+    )]
+    pub glob: Option<GlobHolder>,
+
+    /// Glob matched against 'filename component' of each input value
+    ///
+    /// Every wildcard is captured, captures are available as '$1', '$2', ...
+    #[clap(
+        short = 'G',
+        long,
+        value_name = "glob",
+        conflicts_with_all = &["regex", "regex-filename", "glob"],
+        verbatim_doc_comment,
+        help_heading = PROCESSING_HEADING,
+    )]
+    pub glob_filename: Option<GlobHolder>,
+
     /// Local counter configuration
     ///
     /// init - Initial value.
@@ -351,7 +393,7 @@ mod tests {
     }
 
     fn run(args: &[&str]) -> Cli {
-        Cli::try_parse_from(&[&["rew"], args].concat()).unwrap()
+        Cli::try_parse_from([&["rew"], args].concat()).unwrap()
     }
 
     mod parse_single_byte_char {
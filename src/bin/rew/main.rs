@@ -82,16 +82,27 @@ fn run(cli: &Cli, io: &Io) -> Result {
         let separator = if let Some(separator) = &cli.separator {
             Separator::String(separator.clone())
         } else if let Some(separator) = &cli.separator_regex {
-            Separator::Regex(RegexHolder(separator.clone()))
+            Separator::Regex(RegexHolder::from(separator.clone()))
         } else {
-            Separator::Regex(RegexHolder(
+            Separator::Regex(RegexHolder::from(
                 Regex::new("\\s+").expect("Failed to create default separator from regex"),
             ))
         };
 
+        let working_dir = if let Some(working_dir) = &cli.working_directory {
+            if working_dir.is_relative() {
+                env::current_dir()?.join(working_dir)
+            } else {
+                working_dir.clone()
+            }
+        } else {
+            env::current_dir()?
+        };
+
         let parse_config = parse::Config {
             escape: cli.escape.unwrap_or('%'),
             separator,
+            working_dir,
         };
 
         let pattern = match Pattern::parse(raw_pattern, &parse_config) {
@@ -104,6 +115,7 @@ fn run(cli: &Cli, io: &Io) -> Result {
                     writeln!(stderr)?;
                     let message = match hint {
                         parse::ErrorHint::RegexSyntax => help::REGEX_HINT,
+                        parse::ErrorHint::DateFormatSyntax => help::DATE_FORMAT_HINT,
                         parse::ErrorHint::PatternSyntax => help::PATTERN_HINT,
                         parse::ErrorHint::FilterUsage => help::FILTERS_HINT,
                     };
@@ -133,20 +145,14 @@ fn run(cli: &Cli, io: &Io) -> Result {
             regex::Solver::Value(regex)
         } else if let Some(regex) = &cli.regex_filename {
             regex::Solver::FileName(regex)
+        } else if let Some(glob) = &cli.glob {
+            regex::Solver::Value(&glob.regex)
+        } else if let Some(glob) = &cli.glob_filename {
+            regex::Solver::FileName(&glob.regex)
         } else {
             regex::Solver::None
         };
 
-        let working_dir = if let Some(working_dir) = &cli.working_directory {
-            if working_dir.is_relative() {
-                env::current_dir()?.join(working_dir)
-            } else {
-                working_dir.clone()
-            }
-        } else {
-            env::current_dir()?
-        };
-
         let expression_quotes = match cli.quote {
             0 => None,
             1 => Some('\''),
@@ -154,6 +160,9 @@ fn run(cli: &Cli, io: &Io) -> Result {
         };
 
         while let Some(input_value) = input_values.next()? {
+            let normalized_value = cli.normalize_input.map(|form| form.apply(input_value));
+            let value = normalized_value.as_deref().unwrap_or(input_value);
+
             let global_counter = if global_counter_used {
                 global_counter_generator.next()
             } else {
@@ -161,26 +170,34 @@ fn run(cli: &Cli, io: &Io) -> Result {
             };
 
             let local_counter = if local_counter_used {
-                local_counter_generator.next(input_value)
+                local_counter_generator.next(value)
             } else {
                 0
             };
 
             let regex_captures = if regex_capture_used {
-                regex_solver.eval(input_value)
+                regex_solver.eval(value)
             } else {
                 None
             };
 
             let context = eval::Context {
-                working_dir: &working_dir,
+                working_dir: &parse_config.working_dir,
                 global_counter,
                 local_counter,
                 regex_captures,
                 expression_quotes,
+                exif_cache: Default::default(),
+                audio_cache: Default::default(),
+                normalized_input: normalized_value.as_deref().map(|normalized| {
+                    eval::NormalizedInput {
+                        original: input_value,
+                        normalized,
+                    }
+                }),
             };
 
-            let output_value = match pattern.eval(input_value, &context) {
+            let output_value = match pattern.eval(value, &context) {
                 Ok(value) => value,
                 Err(error) => {
                     write_pattern_error(&mut io.stderr(), &error, raw_pattern)?;
@@ -197,7 +214,11 @@ fn run(cli: &Cli, io: &Io) -> Result {
         }
     } else {
         while let Some(value) = input_values.next()? {
-            output_values.write(value, value)?;
+            if let Some(form) = cli.normalize_input {
+                output_values.write(value, &form.apply(value))?;
+            } else {
+                output_values.write(value, value)?;
+            }
         }
     };
 
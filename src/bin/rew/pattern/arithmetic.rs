@@ -0,0 +1,366 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::eval::{BaseResult, Context, ErrorKind};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Numeric {
+    Integer(i64),
+    Decimal(f64),
+}
+
+impl Numeric {
+    // Integers are preferred, so they do not lose precision
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Ok(integer) = value.parse() {
+            Some(Self::Integer(integer))
+        } else {
+            match value.parse::<f64>() {
+                Ok(decimal) if decimal.is_finite() => Some(Self::Decimal(decimal)),
+                _ => None,
+            }
+        }
+    }
+
+    pub fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Integer(left), Self::Integer(right)) => left.cmp(right),
+            (left, right) => left
+                .to_f64()
+                .partial_cmp(&right.to_f64())
+                .unwrap_or(Ordering::Equal),
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        match self {
+            Self::Integer(integer) => integer as f64,
+            Self::Decimal(decimal) => decimal,
+        }
+    }
+}
+
+impl fmt::Display for Numeric {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Integer(integer) => write!(formatter, "{}", integer),
+            Self::Decimal(decimal) => write!(formatter, "{}", decimal),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Operand {
+    Number(Numeric),
+    LocalCounter,
+    GlobalCounter,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Arithmetic {
+    pub operator: Operator,
+    pub operand: Operand,
+}
+
+impl Arithmetic {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        let operator = match char.map(Char::as_char) {
+            Some('+') => Operator::Add,
+            Some('-') => Operator::Subtract,
+            Some('*') => Operator::Multiply,
+            Some('/') => Operator::Divide,
+            Some('%') => Operator::Modulo,
+            _ => {
+                return Err(parse::Error {
+                    kind: parse::ErrorKind::ExpectedOperator(char.cloned()),
+                    range: position..reader.position(),
+                })
+            }
+        };
+
+        let position = reader.position();
+        let operand = reader.read_to_end().to_string();
+
+        let operand = match operand.as_str() {
+            "" => {
+                return Err(parse::Error {
+                    kind: parse::ErrorKind::ExpectedOperand,
+                    range: position..reader.end(),
+                })
+            }
+            "c" => Operand::LocalCounter,
+            "C" => Operand::GlobalCounter,
+            number => match Numeric::parse(number) {
+                Some(number) => Operand::Number(number),
+                None => {
+                    return Err(parse::Error {
+                        kind: parse::ErrorKind::NumberInvalid(operand),
+                        range: position..reader.end(),
+                    })
+                }
+            },
+        };
+
+        Ok(Self { operator, operand })
+    }
+
+    pub fn eval(&self, value: &str, context: &Context) -> BaseResult<String> {
+        let value = value.trim();
+        let left = Numeric::parse(value).ok_or(ErrorKind::InputNotNumber)?;
+        let right = match &self.operand {
+            Operand::Number(number) => *number,
+            Operand::LocalCounter => Numeric::Integer(context.local_counter.into()),
+            Operand::GlobalCounter => Numeric::Integer(context.global_counter.into()),
+        };
+
+        match (left, right) {
+            (Numeric::Integer(left), Numeric::Integer(right)) => {
+                let result = self.apply_integer(left, right)?;
+                Ok(format_integer(result, zero_padded_width(value)))
+            }
+            (left, right) => {
+                let result = self.apply_decimal(left.to_f64(), right.to_f64())?;
+                Ok(format_decimal(result, self.precision(left, right)))
+            }
+        }
+    }
+
+    // Decimal places of the exact result, so it does not show floating-point errors
+    fn precision(&self, left: Numeric, right: Numeric) -> usize {
+        let left = decimal_places(left);
+        let right = decimal_places(right);
+
+        match self.operator {
+            Operator::Add | Operator::Subtract | Operator::Modulo => left.max(right),
+            Operator::Multiply => left + right,
+            Operator::Divide => DIVISION_PRECISION,
+        }
+    }
+
+    fn apply_integer(&self, left: i64, right: i64) -> BaseResult<i64> {
+        if right == 0 && matches!(self.operator, Operator::Divide | Operator::Modulo) {
+            return Err(ErrorKind::DivisionByZero);
+        }
+
+        match self.operator {
+            Operator::Add => left.checked_add(right),
+            Operator::Subtract => left.checked_sub(right),
+            Operator::Multiply => left.checked_mul(right),
+            Operator::Divide => left.checked_div(right),
+            Operator::Modulo => left.checked_rem(right),
+        }
+        .ok_or(ErrorKind::ArithmeticOverflow)
+    }
+
+    fn apply_decimal(&self, left: f64, right: f64) -> BaseResult<f64> {
+        if right == 0.0 && matches!(self.operator, Operator::Divide | Operator::Modulo) {
+            return Err(ErrorKind::DivisionByZero);
+        }
+
+        let result = match self.operator {
+            Operator::Add => left + right,
+            Operator::Subtract => left - right,
+            Operator::Multiply => left * right,
+            Operator::Divide => left / right,
+            Operator::Modulo => left % right,
+        };
+
+        if result.is_finite() {
+            Ok(result)
+        } else {
+            Err(ErrorKind::ArithmeticOverflow)
+        }
+    }
+}
+
+const DIVISION_PRECISION: usize = 10;
+
+fn decimal_places(number: Numeric) -> usize {
+    // Display of f64 is the shortest representation which parses back to the same value
+    match number.to_string().split_once('.') {
+        Some((_, fraction)) => fraction.len(),
+        None => 0,
+    }
+}
+
+// Integer with leading zeros (e.g. '0042') is expected to keep its width
+fn zero_padded_width(value: &str) -> usize {
+    let digits = value.trim_start_matches(['-', '+']);
+    if digits.len() > 1 && digits.starts_with('0') {
+        digits.len()
+    } else {
+        0
+    }
+}
+
+fn format_integer(value: i64, width: usize) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    format!("{}{:0width$}", sign, value.unsigned_abs(), width = width)
+}
+
+fn format_decimal(value: f64, precision: usize) -> String {
+    let result = format!("{:.*}", precision, value);
+    let result = if result.contains('.') {
+        result.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &result
+    };
+
+    if result == "-0" {
+        "0".into()
+    } else {
+        result.into()
+    }
+}
+
+impl fmt::Display for Arithmetic {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let operand = match &self.operand {
+            Operand::Number(number) => number.to_string(),
+            Operand::LocalCounter => "local counter".into(),
+            Operand::GlobalCounter => "global counter".into(),
+        };
+
+        match self.operator {
+            Operator::Add => write!(formatter, "Add {}", operand),
+            Operator::Subtract => write!(formatter, "Subtract {}", operand),
+            Operator::Multiply => write!(formatter, "Multiply by {}", operand),
+            Operator::Divide => write!(formatter, "Divide by {}", operand),
+            Operator::Modulo => write!(formatter, "Remainder after division by {}", operand),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("",      None                            ; "empty")]
+    #[test_case("abc",   None                            ; "text")]
+    #[test_case("1a",    None                            ; "suffix")]
+    #[test_case("inf",   None                            ; "infinity")]
+    #[test_case("NaN",   None                            ; "nan")]
+    #[test_case("42",    Some(Numeric::Integer(42))      ; "integer")]
+    #[test_case("0042",  Some(Numeric::Integer(42))      ; "leading zeros")]
+    #[test_case("-42",   Some(Numeric::Integer(-42))     ; "negative integer")]
+    #[test_case("1.5",   Some(Numeric::Decimal(1.5))     ; "decimal")]
+    #[test_case("-.5",   Some(Numeric::Decimal(-0.5))    ; "negative decimal")]
+    #[test_case("1e3",   Some(Numeric::Decimal(1000.0))  ; "exponent")]
+    fn numeric_parse(input: &str, result: Option<Numeric>) {
+        assert_eq!(Numeric::parse(input), result);
+    }
+
+    #[test_case(Numeric::Integer(1),   Numeric::Integer(2),   Ordering::Less    ; "integer less")]
+    #[test_case(Numeric::Integer(2),   Numeric::Integer(2),   Ordering::Equal   ; "integer equal")]
+    #[test_case(Numeric::Integer(2),   Numeric::Decimal(1.5), Ordering::Greater ; "mixed greater")]
+    #[test_case(Numeric::Decimal(2.0), Numeric::Integer(2),   Ordering::Equal   ; "mixed equal")]
+    fn numeric_compare(left: Numeric, right: Numeric, result: Ordering) {
+        assert_eq!(left.compare(&right), result);
+    }
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",    0..0, ErrorKind::ExpectedOperator(None)             ; "no operator")]
+        #[test_case("x1",  0..1, ErrorKind::ExpectedOperator(Some('x'.into())) ; "invalid operator")]
+        #[test_case("+",   1..1, ErrorKind::ExpectedOperand                    ; "no operand")]
+        #[test_case("+1x", 1..3, ErrorKind::NumberInvalid("1x".into())         ; "invalid operand")]
+        #[test_case("*cc", 1..3, ErrorKind::NumberInvalid("cc".into())         ; "invalid counter")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                Arithmetic::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("+1",   Operator::Add,      Operand::Number(Numeric::Integer(1))    ; "add")]
+        #[test_case("--1",  Operator::Subtract, Operand::Number(Numeric::Integer(-1))   ; "subtract")]
+        #[test_case("*1.5", Operator::Multiply, Operand::Number(Numeric::Decimal(1.5))  ; "multiply")]
+        #[test_case("/c",   Operator::Divide,   Operand::LocalCounter                   ; "divide")]
+        #[test_case("%C",   Operator::Modulo,   Operand::GlobalCounter                  ; "modulo")]
+        fn ok(input: &str, operator: Operator, operand: Operand) {
+            let mut reader = Reader::from(input);
+            assert_eq!(
+                Arithmetic::parse(&mut reader),
+                Ok(Arithmetic { operator, operand })
+            );
+            assert_eq!(reader.position(), input.len());
+        }
+    }
+
+    mod eval {
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case("42",   Operator::Add,      Operand::Number(Numeric::Integer(100)), "142"          ; "add")]
+        #[test_case("0042", Operator::Add,      Operand::Number(Numeric::Integer(100)), "0142"         ; "add leading zeros")]
+        #[test_case("0999", Operator::Add,      Operand::Number(Numeric::Integer(1)),   "1000"         ; "add leading zeros overflow width")]
+        #[test_case("-05",  Operator::Add,      Operand::Number(Numeric::Integer(1)),   "-04"          ; "add leading zeros negative")]
+        #[test_case("0",    Operator::Add,      Operand::Number(Numeric::Integer(1)),   "1"            ; "add zero")]
+        #[test_case(" 42 ", Operator::Add,      Operand::Number(Numeric::Integer(1)),   "43"           ; "add trimmed")]
+        #[test_case("5",    Operator::Subtract, Operand::Number(Numeric::Integer(7)),   "-2"           ; "subtract")]
+        #[test_case("5",    Operator::Multiply, Operand::Number(Numeric::Integer(3)),   "15"           ; "multiply")]
+        #[test_case("7",    Operator::Divide,   Operand::Number(Numeric::Integer(2)),   "3"            ; "divide integer")]
+        #[test_case("7",    Operator::Modulo,   Operand::Number(Numeric::Integer(3)),   "1"            ; "modulo integer")]
+        #[test_case("7",    Operator::Divide,   Operand::Number(Numeric::Decimal(2.0)), "3.5"          ; "divide decimal")]
+        #[test_case("7.5",  Operator::Modulo,   Operand::Number(Numeric::Integer(2)),   "1.5"          ; "modulo decimal")]
+        #[test_case("1.5",  Operator::Add,      Operand::Number(Numeric::Decimal(1.5)), "3"            ; "decimal whole result")]
+        #[test_case("0.1",  Operator::Add,      Operand::Number(Numeric::Decimal(0.2)), "0.3"          ; "decimal add rounded")]
+        #[test_case("0.3",  Operator::Subtract, Operand::Number(Numeric::Decimal(0.1)), "0.2"          ; "decimal subtract rounded")]
+        #[test_case("-0.1", Operator::Add,      Operand::Number(Numeric::Decimal(0.1)), "0"            ; "decimal zero result")]
+        #[test_case("1.1",  Operator::Multiply, Operand::Number(Numeric::Decimal(1.1)), "1.21"         ; "decimal multiply rounded")]
+        #[test_case("1",    Operator::Divide,   Operand::Number(Numeric::Decimal(3.0)), "0.3333333333" ; "decimal divide rounded")]
+        #[test_case("10",   Operator::Add,      Operand::LocalCounter,                  "11"           ; "local counter")]
+        #[test_case("10",   Operator::Multiply, Operand::GlobalCounter,                 "20"           ; "global counter")]
+        fn ok(input: &str, operator: Operator, operand: Operand, result: &str) {
+            let arithmetic = Arithmetic { operator, operand };
+            assert_eq!(
+                arithmetic.eval(input, &Context::fixture()),
+                Ok(result.into())
+            );
+        }
+
+        #[test_case("",                    Operator::Add,      Operand::Number(Numeric::Integer(1)),   ErrorKind::InputNotNumber     ; "empty")]
+        #[test_case("abc",                 Operator::Add,      Operand::Number(Numeric::Integer(1)),   ErrorKind::InputNotNumber     ; "not number")]
+        #[test_case("1",                   Operator::Divide,   Operand::Number(Numeric::Integer(0)),   ErrorKind::DivisionByZero     ; "divide integer by zero")]
+        #[test_case("1",                   Operator::Modulo,   Operand::Number(Numeric::Integer(0)),   ErrorKind::DivisionByZero     ; "modulo integer by zero")]
+        #[test_case("1.5",                 Operator::Divide,   Operand::Number(Numeric::Decimal(0.0)), ErrorKind::DivisionByZero     ; "divide decimal by zero")]
+        #[test_case("9223372036854775807", Operator::Add,      Operand::Number(Numeric::Integer(1)),   ErrorKind::ArithmeticOverflow ; "integer overflow")]
+        #[test_case("1e308",               Operator::Multiply, Operand::Number(Numeric::Integer(10)),  ErrorKind::ArithmeticOverflow ; "decimal overflow")]
+        fn err(input: &str, operator: Operator, operand: Operand, kind: ErrorKind) {
+            let arithmetic = Arithmetic { operator, operand };
+            assert_eq!(arithmetic.eval(input, &Context::fixture()), Err(kind));
+        }
+    }
+
+    #[test_case(Operator::Add,      Operand::Number(Numeric::Integer(1)),   "Add 1"                                ; "add")]
+    #[test_case(Operator::Subtract, Operand::Number(Numeric::Decimal(1.5)), "Subtract 1.5"                         ; "subtract")]
+    #[test_case(Operator::Multiply, Operand::LocalCounter,                  "Multiply by local counter"            ; "multiply")]
+    #[test_case(Operator::Divide,   Operand::GlobalCounter,                 "Divide by global counter"             ; "divide")]
+    #[test_case(Operator::Modulo,   Operand::Number(Numeric::Integer(10)),  "Remainder after division by 10"       ; "modulo")]
+    fn display(operator: Operator, operand: Operand, result: &str) {
+        assert_eq!(Arithmetic { operator, operand }.to_string(), result);
+    }
+}
@@ -0,0 +1,412 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use symphonia::core::errors::Error as AudioError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{BufReader, MediaSourceStream};
+use symphonia::core::meta::{MetadataBuilder, MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+use symphonia_metadata::id3v1::read_id3v1;
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::eval::{BaseResult, ErrorKind};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+use crate::pattern::utils::AnyString;
+
+const ID3V1_SIZE: u64 = 128;
+
+#[derive(Debug, PartialEq)]
+pub enum AudioTag {
+    Artist,
+    AlbumArtist,
+    Album,
+    Title,
+    TrackNumber,
+    DiscNumber,
+    Year,
+    Genre,
+}
+
+impl AudioTag {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        match char.map(Char::as_char) {
+            Some('a') => Ok(Self::Artist),
+            Some('A') => Ok(Self::AlbumArtist),
+            Some('b') => Ok(Self::Album),
+            Some('t') => Ok(Self::Title),
+            Some('n') => Ok(Self::TrackNumber),
+            Some('d') => Ok(Self::DiscNumber),
+            Some('y') => Ok(Self::Year),
+            Some('g') => Ok(Self::Genre),
+            _ => Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedAudioTag(char.cloned()),
+                range: position..reader.position(),
+            }),
+        }
+    }
+
+    // Missing tag is not an error, so its value can be replaced by a default one
+    pub fn get(&self, value: &str, working_dir: &Path, cache: &AudioCache) -> BaseResult<String> {
+        let tags = cache.get(&working_dir.join(value))?;
+        let key = self.standard_key();
+
+        Ok(tags
+            .iter()
+            .find(|tag| tag.std_key == Some(key))
+            .map(|tag| self.format(&tag.value.to_string()))
+            .unwrap_or_default())
+    }
+
+    fn standard_key(&self) -> StandardTagKey {
+        match self {
+            Self::Artist => StandardTagKey::Artist,
+            Self::AlbumArtist => StandardTagKey::AlbumArtist,
+            Self::Album => StandardTagKey::Album,
+            Self::Title => StandardTagKey::TrackTitle,
+            Self::TrackNumber => StandardTagKey::TrackNumber,
+            Self::DiscNumber => StandardTagKey::DiscNumber,
+            Self::Year => StandardTagKey::Date,
+            Self::Genre => StandardTagKey::Genre,
+        }
+    }
+
+    fn format(&self, value: &str) -> String {
+        let value = value.trim();
+
+        match self {
+            // Position is often stored together with total count as '3/12'
+            Self::TrackNumber | Self::DiscNumber => match value.split_once('/') {
+                Some((position, _)) => position.trim().to_string(),
+                None => value.to_string(),
+            },
+            // Full date is sometimes stored instead of just year
+            Self::Year => match value.get(..4) {
+                Some(year) if year.chars().all(|char| char.is_ascii_digit()) => year.to_string(),
+                _ => value.to_string(),
+            },
+            _ => value.to_string(),
+        }
+    }
+}
+
+// Multiple tags of the same file are extracted from a single probe of its content
+#[derive(Default)]
+pub struct AudioCache(RefCell<HashMap<PathBuf, Rc<Vec<Tag>>>>);
+
+impl AudioCache {
+    fn get(&self, path: &Path) -> BaseResult<Rc<Vec<Tag>>> {
+        if let Some(tags) = self.0.borrow().get(path) {
+            return Ok(tags.clone());
+        }
+
+        let tags = Rc::new(read_tags(path).map_err(unavailable)?);
+        self.0.borrow_mut().insert(path.to_path_buf(), tags.clone());
+        Ok(tags)
+    }
+}
+
+impl fmt::Display for AudioTag {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Artist => write!(formatter, "artist"),
+            Self::AlbumArtist => write!(formatter, "album artist"),
+            Self::Album => write!(formatter, "album"),
+            Self::Title => write!(formatter, "title"),
+            Self::TrackNumber => write!(formatter, "track number"),
+            Self::DiscNumber => write!(formatter, "disc number"),
+            Self::Year => write!(formatter, "year"),
+            Self::Genre => write!(formatter, "genre"),
+        }
+    }
+}
+
+fn unavailable(error: AudioError) -> ErrorKind {
+    ErrorKind::AudioTagsUnavailable(AnyString(error.to_string()))
+}
+
+// Tags in front of the stream (ID3v2) take precedence over container tags (FLAC, Vorbis, MP4),
+// which take precedence over tags at the end of the file (ID3v1).
+fn read_tags(path: &Path) -> Result<Vec<Tag>, AudioError> {
+    let mut tags = Vec::new();
+    let stream = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let probed = symphonia::default::get_probe().format(
+        &Hint::new(),
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    );
+
+    let probe_error = match probed {
+        Ok(mut probed) => {
+            if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+                tags.extend_from_slice(revision.tags());
+            }
+            if let Some(revision) = probed.format.metadata().current() {
+                tags.extend_from_slice(revision.tags());
+            }
+            None
+        }
+        Err(error) => Some(error),
+    };
+
+    let id3v1_tags = read_id3v1_tags(path)?;
+
+    match probe_error {
+        // MP3 stream itself might be unreadable, but it is still fine to use its ID3v1 tags
+        Some(error) if id3v1_tags.is_empty() => Err(error),
+        _ => {
+            tags.extend(id3v1_tags);
+            Ok(tags)
+        }
+    }
+}
+
+fn read_id3v1_tags(path: &Path) -> Result<Vec<Tag>, AudioError> {
+    let mut file = File::open(path)?;
+
+    if file.metadata()?.len() < ID3V1_SIZE {
+        return Ok(Vec::new());
+    }
+
+    let mut buffer = [0; ID3V1_SIZE as usize];
+    file.seek(SeekFrom::End(-(ID3V1_SIZE as i64)))?;
+    file.read_exact(&mut buffer)?;
+
+    if !buffer.starts_with(b"TAG") {
+        return Ok(Vec::new());
+    }
+
+    let mut builder = MetadataBuilder::new();
+    read_id3v1(&mut BufReader::new(&buffer), &mut builder)?;
+    Ok(builder.metadata().tags().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",  0..0, ErrorKind::ExpectedAudioTag(None)             ; "missing")]
+        #[test_case("x", 0..1, ErrorKind::ExpectedAudioTag(Some('x'.into())) ; "unknown")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                AudioTag::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("a",  AudioTag::Artist      ; "artist")]
+        #[test_case("A",  AudioTag::AlbumArtist ; "album artist")]
+        #[test_case("b",  AudioTag::Album       ; "album")]
+        #[test_case("t",  AudioTag::Title       ; "title")]
+        #[test_case("n",  AudioTag::TrackNumber ; "track number")]
+        #[test_case("d",  AudioTag::DiscNumber  ; "disc number")]
+        #[test_case("y",  AudioTag::Year        ; "year")]
+        #[test_case("gx", AudioTag::Genre       ; "genre")]
+        fn ok(input: &str, tag: AudioTag) {
+            let mut reader = Reader::from(input);
+            assert_eq!(AudioTag::parse(&mut reader), Ok(tag));
+            assert_eq!(reader.position(), 1);
+        }
+    }
+
+    mod get {
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use test_case::test_case;
+
+        use super::*;
+
+        fn flac(comments: &[&str]) -> Vec<u8> {
+            let mut comment_block = Vec::new();
+            comment_block.extend_from_slice(&0u32.to_le_bytes()); // Empty vendor
+            comment_block.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+            for comment in comments {
+                comment_block.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+                comment_block.extend_from_slice(comment.as_bytes());
+            }
+
+            let mut data = b"fLaC".to_vec();
+            data.extend_from_slice(&[0x00, 0x00, 0x00, 34]); // STREAMINFO
+            data.extend_from_slice(&[0x10, 0x00, 0x10, 0x00]); // Block sizes
+            data.extend_from_slice(&[0; 6]); // Frame sizes
+            data.extend_from_slice(&[0x0A, 0xC4, 0x42, 0xF0]); // 44.1 kHz, 2 channels, 16 bits
+            data.extend_from_slice(&[0; 20]); // Sample count and MD5
+            data.push(0x84); // Last block, VORBIS_COMMENT
+            data.extend_from_slice(&(comment_block.len() as u32).to_be_bytes()[1..]);
+            data.extend_from_slice(&comment_block);
+            data.extend_from_slice(&[0xFF, 0xF8, 0xC9, 0x18, 0x00, 0xC2]); // Frame header with CRC
+            data
+        }
+
+        fn mp3(frames: &[(&str, &str)], id3v1: bool) -> Vec<u8> {
+            let mut frames_data = Vec::new();
+            for (id, text) in frames {
+                frames_data.extend_from_slice(id.as_bytes());
+                frames_data.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+                frames_data.extend_from_slice(&[0, 0, 0]); // Flags, ISO-8859-1 encoding
+                frames_data.extend_from_slice(text.as_bytes());
+            }
+
+            let mut data = Vec::new();
+            if !frames.is_empty() {
+                let size = frames_data.len() as u32; // Syncsafe as long as it is under 128 bytes
+                data.extend_from_slice(b"ID3\x03\x00\x00\x00\x00\x00");
+                data.push(size as u8);
+                data.extend_from_slice(&frames_data);
+            }
+
+            for _ in 0..3 {
+                // MPEG-1 Layer III, 128 kbps, 44.1 kHz frame filled with silence
+                data.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+                data.extend_from_slice(&[0; 413]);
+            }
+
+            if id3v1 {
+                let mut tag = [0; 128];
+                tag[..3].copy_from_slice(b"TAG");
+                tag[3..8].copy_from_slice(b"Title");
+                tag[33..40].copy_from_slice(b"Artist1");
+                tag[93..97].copy_from_slice(b"1999");
+                tag[126] = 7; // Track number
+                data.extend_from_slice(&tag);
+            }
+
+            data
+        }
+
+        #[test_case(AudioTag::Artist,      "Artist"  ; "artist")]
+        #[test_case(AudioTag::AlbumArtist, "Various" ; "album artist")]
+        #[test_case(AudioTag::Album,       "Album"   ; "album")]
+        #[test_case(AudioTag::Title,       "Title"   ; "title")]
+        #[test_case(AudioTag::TrackNumber, "3"       ; "track number")]
+        #[test_case(AudioTag::DiscNumber,  "1"       ; "disc number")]
+        #[test_case(AudioTag::Year,        "2021"    ; "year")]
+        #[test_case(AudioTag::Genre,       ""        ; "missing")]
+        fn flac_comments(tag: AudioTag, result: &str) {
+            let dir = TempDir::new().unwrap();
+            let data = flac(&[
+                "ARTIST=Artist",
+                "ALBUMARTIST=Various",
+                "ALBUM=Album",
+                "TITLE=Title",
+                "TRACKNUMBER=3/12",
+                "DISCNUMBER=1",
+                "DATE=2021-05-04",
+            ]);
+            dir.child("file.flac").write_binary(&data).unwrap();
+
+            assert_eq!(
+                tag.get("file.flac", dir.path(), &AudioCache::default()),
+                Ok(result.into())
+            );
+        }
+
+        #[test_case(AudioTag::Artist,      "Artist2" ; "artist")]
+        #[test_case(AudioTag::Title,       "Title"   ; "title from id3v1")]
+        #[test_case(AudioTag::TrackNumber, "3"       ; "track number")]
+        #[test_case(AudioTag::Year,        "2021"    ; "year")]
+        #[test_case(AudioTag::Album,       ""        ; "missing")]
+        fn mp3_id3(tag: AudioTag, result: &str) {
+            let dir = TempDir::new().unwrap();
+            let data = mp3(
+                &[("TPE1", "Artist2"), ("TRCK", "3/12"), ("TYER", "2021")],
+                true,
+            );
+            dir.child("file.mp3").write_binary(&data).unwrap();
+
+            assert_eq!(
+                tag.get("file.mp3", dir.path(), &AudioCache::default()),
+                Ok(result.into())
+            );
+        }
+
+        #[test_case(AudioTag::Artist,      "Artist1" ; "artist")]
+        #[test_case(AudioTag::TrackNumber, "7"       ; "track number")]
+        #[test_case(AudioTag::Year,        "1999"    ; "year")]
+        fn mp3_id3v1(tag: AudioTag, result: &str) {
+            let dir = TempDir::new().unwrap();
+            dir.child("file.mp3").write_binary(&mp3(&[], true)).unwrap();
+
+            assert_eq!(
+                tag.get("file.mp3", dir.path(), &AudioCache::default()),
+                Ok(result.into())
+            );
+        }
+
+        #[test]
+        fn cached() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file.mp3").write_binary(&mp3(&[], true)).unwrap();
+            let cache = AudioCache::default();
+
+            assert_eq!(
+                AudioTag::Artist.get("file.mp3", dir.path(), &cache),
+                Ok("Artist1".into())
+            );
+
+            std::fs::remove_file(dir.path().join("file.mp3")).unwrap();
+
+            assert_eq!(
+                AudioTag::Year.get("file.mp3", dir.path(), &cache),
+                Ok("1999".into())
+            );
+        }
+
+        #[test]
+        fn unsupported() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").write_str("abc").unwrap();
+
+            assert_eq!(
+                AudioTag::Artist.get("file", dir.path(), &AudioCache::default()),
+                Err(ErrorKind::AudioTagsUnavailable(AnyString::any()))
+            );
+        }
+
+        #[test]
+        fn missing_file() {
+            let dir = TempDir::new().unwrap();
+
+            assert_eq!(
+                AudioTag::Artist.get("missing", dir.path(), &AudioCache::default()),
+                Err(ErrorKind::AudioTagsUnavailable(AnyString::any()))
+            );
+        }
+    }
+
+    #[test_case(AudioTag::Artist,      " Artist ",   "Artist"     ; "trim")]
+    #[test_case(AudioTag::TrackNumber, "3",          "3"          ; "track number")]
+    #[test_case(AudioTag::TrackNumber, "3/12",       "3"          ; "track number with total")]
+    #[test_case(AudioTag::DiscNumber,  "1 / 2",      "1"          ; "disc number with total")]
+    #[test_case(AudioTag::Year,        "2021",       "2021"       ; "year")]
+    #[test_case(AudioTag::Year,        "2021-05-04", "2021"       ; "year from date")]
+    #[test_case(AudioTag::Year,        "May 2021",   "May 2021"   ; "year unknown format")]
+    fn format(tag: AudioTag, value: &str, result: &str) {
+        assert_eq!(tag.format(value), result);
+    }
+
+    #[test_case(AudioTag::Artist,      "artist"       ; "artist")]
+    #[test_case(AudioTag::AlbumArtist, "album artist" ; "album artist")]
+    #[test_case(AudioTag::TrackNumber, "track number" ; "track number")]
+    fn display(tag: AudioTag, result: &str) {
+        assert_eq!(tag.to_string(), result);
+    }
+}
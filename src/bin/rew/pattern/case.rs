@@ -0,0 +1,215 @@
+use std::fmt;
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+
+#[derive(Debug, PartialEq)]
+pub enum CaseStyle {
+    Title,
+    Sentence,
+    Camel,
+    Pascal,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl CaseStyle {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        match char.map(Char::as_char) {
+            Some('t') => Ok(Self::Title),
+            Some('s') => Ok(Self::Sentence),
+            Some('c') => Ok(Self::Camel),
+            Some('p') => Ok(Self::Pascal),
+            Some('n') => Ok(Self::Snake),
+            Some('N') => Ok(Self::ScreamingSnake),
+            Some('k') => Ok(Self::Kebab),
+            _ => Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedCaseStyle(char.cloned()),
+                range: position..reader.position(),
+            }),
+        }
+    }
+
+    pub fn apply(&self, value: &str) -> String {
+        let words = split_words(value);
+        let mut result = String::with_capacity(value.len());
+
+        for (index, word) in words.iter().enumerate() {
+            if index > 0 {
+                result.push_str(self.separator());
+            }
+
+            match self {
+                Self::Title | Self::Pascal => push_capitalized(&mut result, word),
+                Self::Sentence if index == 0 => push_capitalized(&mut result, word),
+                Self::Camel if index > 0 => push_capitalized(&mut result, word),
+                Self::ScreamingSnake => result.push_str(&word.to_uppercase()),
+                _ => result.push_str(&word.to_lowercase()),
+            }
+        }
+
+        result
+    }
+
+    fn separator(&self) -> &'static str {
+        match self {
+            Self::Title | Self::Sentence => " ",
+            Self::Camel | Self::Pascal => "",
+            Self::Snake | Self::ScreamingSnake => "_",
+            Self::Kebab => "-",
+        }
+    }
+}
+
+impl fmt::Display for CaseStyle {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Title => write!(formatter, "Title Case"),
+            Self::Sentence => write!(formatter, "Sentence case"),
+            Self::Camel => write!(formatter, "camelCase"),
+            Self::Pascal => write!(formatter, "PascalCase"),
+            Self::Snake => write!(formatter, "snake_case"),
+            Self::ScreamingSnake => write!(formatter, "SCREAMING_SNAKE_CASE"),
+            Self::Kebab => write!(formatter, "kebab-case"),
+        }
+    }
+}
+
+fn push_capitalized(result: &mut String, word: &str) {
+    let mut chars = word.chars();
+
+    if let Some(first) = chars.next() {
+        result.extend(first.to_uppercase());
+        result.push_str(&chars.as_str().to_lowercase());
+    }
+}
+
+// Words are separated by any non-alphanumeric chars, a lowercase to uppercase change
+// ("fooBar"), the last uppercase letter of an acronym ("HTTPServer") and letter/digit changes.
+fn split_words(value: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut chars = value.char_indices().peekable();
+    let mut prev: Option<char> = None;
+
+    while let Some((index, char)) = chars.next() {
+        if !char.is_alphanumeric() {
+            if let Some(start) = start.take() {
+                words.push(&value[start..index]);
+            }
+            prev = None;
+            continue;
+        }
+
+        if let (Some(start_index), Some(prev)) = (start, prev) {
+            let next = chars.peek().map(|(_, next)| *next);
+
+            let is_boundary = (prev.is_lowercase() && char.is_uppercase())
+                || (prev.is_uppercase()
+                    && char.is_uppercase()
+                    && next.is_some_and(char::is_lowercase))
+                || (prev.is_numeric() != char.is_numeric());
+
+            if is_boundary {
+                words.push(&value[start_index..index]);
+                start = Some(index);
+            }
+        } else {
+            start = Some(index);
+        }
+
+        prev = Some(char);
+    }
+
+    if let Some(start) = start {
+        words.push(&value[start..]);
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",  0..0, ErrorKind::ExpectedCaseStyle(None)             ; "none")]
+        #[test_case("x", 0..1, ErrorKind::ExpectedCaseStyle(Some('x'.into())) ; "invalid")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                CaseStyle::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("t",  CaseStyle::Title          ; "title")]
+        #[test_case("s",  CaseStyle::Sentence       ; "sentence")]
+        #[test_case("c",  CaseStyle::Camel          ; "camel")]
+        #[test_case("p",  CaseStyle::Pascal         ; "pascal")]
+        #[test_case("n",  CaseStyle::Snake          ; "snake")]
+        #[test_case("N",  CaseStyle::ScreamingSnake ; "screaming snake")]
+        #[test_case("k",  CaseStyle::Kebab          ; "kebab")]
+        #[test_case("tx", CaseStyle::Title          ; "chars after")]
+        fn ok(input: &str, case: CaseStyle) {
+            let mut reader = Reader::from(input);
+            assert_eq!(CaseStyle::parse(&mut reader), Ok(case));
+            assert_eq!(reader.position(), 1);
+        }
+    }
+
+    #[test_case("",               &[]                             ; "empty")]
+    #[test_case(" _-. ",          &[]                             ; "separators only")]
+    #[test_case("abc",            &["abc"]                        ; "single")]
+    #[test_case("ab cd_ef-gh.ij", &["ab", "cd", "ef", "gh", "ij"] ; "separators")]
+    #[test_case("  ab__cd  ",     &["ab", "cd"]                   ; "repeated separators")]
+    #[test_case("abCdEf",         &["ab", "Cd", "Ef"]             ; "camel case")]
+    #[test_case("AbCdEf",         &["Ab", "Cd", "Ef"]             ; "pascal case")]
+    #[test_case("ABC_DEF",        &["ABC", "DEF"]                 ; "screaming snake")]
+    #[test_case("HTTPServer",     &["HTTP", "Server"]             ; "acronym")]
+    #[test_case("parseHTTP",      &["parse", "HTTP"]              ; "trailing acronym")]
+    #[test_case("file2name",      &["file", "2", "name"]          ; "digits")]
+    #[test_case("IMG_0042",       &["IMG", "0042"]                ; "digits after separator")]
+    #[test_case("čaŘeŽ",          &["ča", "Ře", "Ž"]              ; "unicode")]
+    fn words(input: &str, result: &[&str]) {
+        assert_eq!(split_words(input), result);
+    }
+
+    #[test_case(CaseStyle::Title,          "hello_WORLD-fooBar 42", "Hello World Foo Bar 42" ; "title")]
+    #[test_case(CaseStyle::Sentence,       "hello_WORLD-fooBar 42", "Hello world foo bar 42" ; "sentence")]
+    #[test_case(CaseStyle::Camel,          "hello_WORLD-fooBar 42", "helloWorldFooBar42"     ; "camel")]
+    #[test_case(CaseStyle::Pascal,         "hello_WORLD-fooBar 42", "HelloWorldFooBar42"     ; "pascal")]
+    #[test_case(CaseStyle::Snake,          "hello_WORLD-fooBar 42", "hello_world_foo_bar_42" ; "snake")]
+    #[test_case(CaseStyle::ScreamingSnake, "hello_WORLD-fooBar 42", "HELLO_WORLD_FOO_BAR_42" ; "screaming snake")]
+    #[test_case(CaseStyle::Kebab,          "hello_WORLD-fooBar 42", "hello-world-foo-bar-42" ; "kebab")]
+    #[test_case(CaseStyle::Camel,          "My HTTPServer",         "myHttpServer"           ; "camel acronym")]
+    #[test_case(CaseStyle::Snake,          "ÚčetníKniha",           "účetní_kniha"           ; "snake unicode")]
+    #[test_case(CaseStyle::Title,          "ßtraße",                "SStraße"                ; "title expanding")]
+    #[test_case(CaseStyle::Kebab,          "",                      ""                       ; "empty")]
+    fn apply(case: CaseStyle, input: &str, result: &str) {
+        assert_eq!(case.apply(input), result);
+    }
+
+    #[test_case(CaseStyle::Title,          "Title Case"           ; "title")]
+    #[test_case(CaseStyle::Sentence,       "Sentence case"        ; "sentence")]
+    #[test_case(CaseStyle::Camel,          "camelCase"            ; "camel")]
+    #[test_case(CaseStyle::Pascal,         "PascalCase"           ; "pascal")]
+    #[test_case(CaseStyle::Snake,          "snake_case"           ; "snake")]
+    #[test_case(CaseStyle::ScreamingSnake, "SCREAMING_SNAKE_CASE" ; "screaming snake")]
+    #[test_case(CaseStyle::Kebab,          "kebab-case"           ; "kebab")]
+    fn display(case: CaseStyle, result: &str) {
+        assert_eq!(case.to_string(), result);
+    }
+}
@@ -10,6 +10,7 @@ pub type EscapeSequence = [char; 2];
 pub enum Char {
     Raw(char),
     Escaped(char, EscapeSequence),
+    Literal(char), // Inserted from a nested expression, never acts as a delimiter
 }
 
 impl From<char> for Char {
@@ -27,7 +28,9 @@ impl From<EscapeSequence> for Char {
 impl fmt::Display for Char {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Raw(value) => write!(formatter, "'{}'", escape_char(*value)),
+            Self::Raw(value) | Self::Literal(value) => {
+                write!(formatter, "'{}'", escape_char(*value))
+            }
             Self::Escaped(value, sequence) => {
                 write!(
                     formatter,
@@ -45,6 +48,10 @@ pub trait AsChar: From<char> {
     fn as_char(&self) -> char;
 
     fn len_utf8(&self) -> usize;
+
+    fn is_literal(&self) -> bool {
+        false
+    }
 }
 
 impl AsChar for char {
@@ -62,6 +69,7 @@ impl AsChar for Char {
         match self {
             Self::Raw(value) => *value,
             Self::Escaped(value, _) => *value,
+            Self::Literal(value) => *value,
         }
     }
 
@@ -69,8 +77,13 @@ impl AsChar for Char {
         match self {
             Self::Raw(value) => value.len_utf8(),
             Self::Escaped(_, sequence) => sequence[0].len_utf8() + sequence[1].len_utf8(),
+            Self::Literal(value) => value.len_utf8(),
         }
     }
+
+    fn is_literal(&self) -> bool {
+        matches!(self, Self::Literal(_))
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -96,9 +109,12 @@ impl<'a, T: AsChar> Deref for Chars<'a, T> {
     }
 }
 
-impl<'a, T: AsChar> ToString for Chars<'a, T> {
-    fn to_string(&self) -> String {
-        self.0.iter().map(T::as_char).collect()
+impl<'a, T: AsChar> fmt::Display for Chars<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for char in self.0 {
+            write!(formatter, "{}", char.as_char())?;
+        }
+        Ok(())
     }
 }
 
@@ -165,6 +181,31 @@ mod tests {
         }
     }
 
+    mod char_literal {
+        use super::*;
+
+        #[test]
+        fn as_char() {
+            assert_eq!(Char::Literal('a').as_char(), 'a');
+        }
+
+        #[test]
+        fn len_utf8() {
+            assert_eq!(Char::Literal('á').len_utf8(), 2);
+        }
+
+        #[test]
+        fn is_literal() {
+            assert!(Char::Literal('a').is_literal());
+            assert!(!Char::Raw('a').is_literal());
+        }
+
+        #[test]
+        fn display() {
+            assert_eq!(Char::Literal('\n').to_string(), "'\\n'");
+        }
+    }
+
     mod chars {
         use super::*;
 
@@ -0,0 +1,578 @@
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::fmt;
+use std::path::Path;
+
+use crate::pattern::arithmetic::Numeric;
+use crate::pattern::char::{AsChar, Char, Chars};
+use crate::pattern::eval::{BaseResult, ErrorKind};
+use crate::pattern::filter::Filter;
+use crate::pattern::glob::GlobHolder;
+use crate::pattern::parse::{Config, Error, Parsed, Result};
+use crate::pattern::parser::ParsedFilter;
+use crate::pattern::reader::Reader;
+use crate::pattern::regex::{pattern_from_chars, Syntax};
+use crate::pattern::symbols::PIPE;
+use crate::pattern::{eval, parse};
+
+const NEGATION: char = '!';
+
+#[derive(Debug, PartialEq)]
+pub enum Predicate {
+    Empty,
+    Exists,
+    File,
+    Directory,
+    Glob(GlobHolder),
+    Equal(Numeric),
+    Less(Numeric),
+    Greater(Numeric),
+}
+
+impl Predicate {
+    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        let predicate = match char.map(Char::as_char) {
+            Some('z') => Self::Empty,
+            Some('e') => Self::Exists,
+            Some('f') => Self::File,
+            Some('d') => Self::Directory,
+            Some('g') => {
+                let glob_start = reader.position();
+                let glob = pattern_from_chars(&reader.read_to_end(), Syntax::Glob);
+
+                return glob.try_into().map(Self::Glob).map_err(|kind| Error {
+                    kind,
+                    range: glob_start..reader.position(),
+                });
+            }
+            Some(operator @ ('=' | '<' | '>')) => {
+                let number_start = reader.position();
+                let number = reader.read_to_end().to_string();
+
+                let number = if number.is_empty() {
+                    return Err(Error {
+                        kind: parse::ErrorKind::ExpectedNumber,
+                        range: number_start..number_start,
+                    });
+                } else {
+                    Numeric::parse(&number).ok_or_else(|| Error {
+                        kind: parse::ErrorKind::NumberInvalid(number),
+                        range: number_start..reader.position(),
+                    })?
+                };
+
+                return Ok(match operator {
+                    '=' => Self::Equal(number),
+                    '<' => Self::Less(number),
+                    _ => Self::Greater(number),
+                });
+            }
+            _ => {
+                return Err(Error {
+                    kind: parse::ErrorKind::ExpectedPredicate(char.cloned()),
+                    range: position..reader.position(),
+                })
+            }
+        };
+
+        if let Some(char) = reader.peek() {
+            let position = reader.position();
+
+            Err(Error {
+                kind: parse::ErrorKind::ExpectedDelimiterChar,
+                range: position..position + char.len_utf8(),
+            })
+        } else {
+            Ok(predicate)
+        }
+    }
+
+    pub fn reads_file(&self) -> bool {
+        matches!(self, Self::Exists | Self::File | Self::Directory)
+    }
+
+    pub fn test(&self, value: &str, working_dir: &Path) -> BaseResult<bool> {
+        match self {
+            Self::Empty => Ok(value.is_empty()),
+            Self::Exists => Ok(!value.is_empty() && working_dir.join(value).exists()),
+            Self::File => Ok(!value.is_empty() && working_dir.join(value).is_file()),
+            Self::Directory => Ok(!value.is_empty() && working_dir.join(value).is_dir()),
+            Self::Glob(glob) => Ok(glob.is_match(value)),
+            Self::Equal(number) => Ok(Self::compare(value, number)? == Ordering::Equal),
+            Self::Less(number) => Ok(Self::compare(value, number)? == Ordering::Less),
+            Self::Greater(number) => Ok(Self::compare(value, number)? == Ordering::Greater),
+        }
+    }
+
+    fn compare(value: &str, number: &Numeric) -> BaseResult<Ordering> {
+        Numeric::parse(value.trim())
+            .map(|value| value.compare(number))
+            .ok_or(ErrorKind::InputNotNumber)
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(formatter, "empty"),
+            Self::Exists => write!(formatter, "an existing path"),
+            Self::File => write!(formatter, "a file"),
+            Self::Directory => write!(formatter, "a directory"),
+            Self::Glob(glob) => write!(formatter, "matching glob '{}'", glob),
+            Self::Equal(number) => write!(formatter, "equal to {}", number),
+            Self::Less(number) => write!(formatter, "less than {}", number),
+            Self::Greater(number) => write!(formatter, "greater than {}", number),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Condition {
+    pub predicate: Predicate,
+    pub negated: bool,
+}
+
+impl Condition {
+    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+        let negated = reader.read_expected(NEGATION);
+        let predicate = Predicate::parse(reader)?;
+        Ok(Self { predicate, negated })
+    }
+
+    pub fn test(&self, value: &str, working_dir: &Path) -> BaseResult<bool> {
+        Ok(self.predicate.test(value, working_dir)? != self.negated)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.negated {
+            write!(formatter, "input is not {}", self.predicate)
+        } else {
+            write!(formatter, "input is {}", self.predicate)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Conditional {
+    pub condition: Condition,
+    pub then: Vec<ParsedFilter>,
+    pub otherwise: Vec<ParsedFilter>,
+}
+
+impl Conditional {
+    pub fn parse(reader: &mut Reader<Char>, config: &Config) -> Result<Self> {
+        if let Some(delimiter) = reader.read().cloned() {
+            let condition_start = reader.position();
+            let condition = reader.read_until(&delimiter);
+            let condition_end = condition_start + condition.len_utf8();
+            let condition = parse_part(condition.to_vec(), condition_start, Condition::parse)?;
+
+            if reader.position() == condition_end {
+                return Err(Error {
+                    kind: parse::ErrorKind::ExpectedDelimiterChar,
+                    range: condition_end..condition_end,
+                });
+            }
+
+            let then_start = reader.position();
+            let then = reader.read_until(&delimiter).to_vec();
+            let then = parse_chain(&then, then_start, config)?;
+
+            let otherwise_start = reader.position();
+            let otherwise = reader.read_to_end().to_vec();
+            let otherwise = parse_chain(&otherwise, otherwise_start, config)?;
+
+            Ok(Self {
+                condition,
+                then,
+                otherwise,
+            })
+        } else {
+            Err(Error {
+                kind: parse::ErrorKind::ExpectedConditional,
+                range: reader.position()..reader.end(),
+            })
+        }
+    }
+
+    pub fn branch(&self, value: &str, context: &eval::Context) -> BaseResult<&[ParsedFilter]> {
+        let value = if self.condition.predicate.reads_file() {
+            context.file_path(value)
+        } else {
+            value
+        };
+
+        if self.condition.test(value, context.working_dir)? {
+            Ok(&self.then)
+        } else {
+            Ok(&self.otherwise)
+        }
+    }
+
+    pub fn filters(&self) -> impl Iterator<Item = &ParsedFilter> {
+        self.then.iter().chain(self.otherwise.iter())
+    }
+
+    pub fn filters_mut(&mut self) -> impl Iterator<Item = &mut ParsedFilter> {
+        self.then.iter_mut().chain(self.otherwise.iter_mut())
+    }
+
+    // Branch filters are parsed with positions relative to their own reader
+    pub fn shift_ranges(&mut self, offset: usize) {
+        for filter in self.filters_mut() {
+            filter.range = (filter.range.start + offset)..(filter.range.end + offset);
+
+            if let Filter::Conditional(conditional) = &mut filter.value {
+                conditional.shift_ranges(offset);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Conditional {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Conditional evaluation:\n\n    if {}",
+            self.condition
+        )?;
+        write_branch(formatter, &self.then)?;
+        write!(formatter, "\n    else")?;
+        write_branch(formatter, &self.otherwise)
+    }
+}
+
+fn write_branch(formatter: &mut fmt::Formatter, filters: &[ParsedFilter]) -> fmt::Result {
+    if filters.is_empty() {
+        write!(formatter, "\n        keep input")
+    } else {
+        // Nested conditionals are indented under their branch
+        for filter in filters {
+            for line in filter.value.to_string().lines() {
+                if line.is_empty() {
+                    writeln!(formatter)?;
+                } else {
+                    write!(formatter, "\n        {}", line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Filters of a branch are separated by escaped pipes (raw pipe would end the whole filter)
+fn parse_chain(chars: &[Char], start: usize, config: &Config) -> Result<Vec<ParsedFilter>> {
+    let mut filters = Vec::new();
+
+    if chars.is_empty() {
+        return Ok(filters);
+    }
+
+    let mut filter_start = start;
+    let mut index = 0;
+
+    for filter_chars in chars.split(is_chain_separator) {
+        let filter_end = filter_start + Chars::from(filter_chars).len_utf8();
+        let mut filter = parse_part(filter_chars.to_vec(), filter_start, |reader| {
+            let filter = Filter::parse(reader, config)?;
+
+            if let Some(char) = reader.peek() {
+                let position = reader.position();
+
+                Err(Error {
+                    kind: parse::ErrorKind::ExpectedPipeOrExprEnd,
+                    range: position..position + char.len_utf8(),
+                })
+            } else {
+                Ok(filter)
+            }
+        })?;
+
+        if let Filter::Conditional(conditional) = &mut filter {
+            conditional.shift_ranges(filter_start);
+        }
+
+        filters.push(Parsed {
+            value: filter,
+            range: filter_start..filter_end,
+        });
+
+        index += filter_chars.len();
+        filter_start = filter_end + chars.get(index).map_or(0, Char::len_utf8);
+        index += 1;
+    }
+
+    Ok(filters)
+}
+
+fn is_chain_separator(char: &Char) -> bool {
+    matches!(char, Char::Escaped(PIPE, _))
+}
+
+fn parse_part<T, F>(chars: Vec<Char>, start: usize, parse: F) -> Result<T>
+where
+    F: FnOnce(&mut Reader<Char>) -> Result<T>,
+{
+    parse(&mut Reader::new(chars)).map_err(|mut error| {
+        error.range = (start + error.range.start)..(start + error.range.end);
+        error
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::utils::AnyString;
+
+    mod predicate {
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+
+        type E = parse::ErrorKind;
+
+        #[test_case("",       0..0, E::ExpectedPredicate(None)            ; "empty")]
+        #[test_case("x",      0..1, E::ExpectedPredicate(Some('x'.into())) ; "invalid")]
+        #[test_case("dx",     1..2, E::ExpectedDelimiterChar               ; "trailing chars")]
+        #[test_case("g[z-a]", 1..6, E::GlobInvalid(AnyString::any())      ; "invalid glob")]
+        #[test_case("=",      1..1, E::ExpectedNumber                      ; "expected number")]
+        #[test_case("<x",     1..2, E::NumberInvalid("x".into())           ; "invalid number")]
+        fn parse_err(input: &str, range: ErrorRange, kind: parse::ErrorKind) {
+            assert_eq!(
+                Predicate::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("z",      Predicate::Empty                         ; "empty")]
+        #[test_case("e",      Predicate::Exists                        ; "exists")]
+        #[test_case("f",      Predicate::File                          ; "file")]
+        #[test_case("d",      Predicate::Directory                     ; "directory")]
+        #[test_case("g*.jpg", Predicate::Glob("*.jpg".into())          ; "glob")]
+        #[test_case("=1",     Predicate::Equal(Numeric::Integer(1))    ; "equal")]
+        #[test_case("<1.5",   Predicate::Less(Numeric::Decimal(1.5))   ; "less")]
+        #[test_case(">-1",    Predicate::Greater(Numeric::Integer(-1)) ; "greater")]
+        fn parse_ok(input: &str, predicate: Predicate) {
+            assert_eq!(Predicate::parse(&mut Reader::from(input)), Ok(predicate));
+        }
+
+        #[test_case(Predicate::Empty,                         "",      true  ; "empty true")]
+        #[test_case(Predicate::Empty,                         "a",     false ; "empty false")]
+        #[test_case(Predicate::Glob("*.jpg".into()),          "a.jpg", true  ; "glob true")]
+        #[test_case(Predicate::Glob("*.jpg".into()),          "a.png", false ; "glob false")]
+        #[test_case(Predicate::Equal(Numeric::Integer(1)),    " 1 ",   true  ; "equal true")]
+        #[test_case(Predicate::Equal(Numeric::Integer(1)),    "1.5",   false ; "equal false")]
+        #[test_case(Predicate::Less(Numeric::Decimal(1.5)),   "1",     true  ; "less true")]
+        #[test_case(Predicate::Less(Numeric::Decimal(1.5)),   "2",     false ; "less false")]
+        #[test_case(Predicate::Greater(Numeric::Integer(-1)), "0",     true  ; "greater true")]
+        #[test_case(Predicate::Greater(Numeric::Integer(-1)), "-1",    false ; "greater false")]
+        fn test_value(predicate: Predicate, value: &str, result: bool) {
+            assert_eq!(predicate.test(value, Path::new("")), Ok(result));
+        }
+
+        #[test]
+        fn test_not_number() {
+            assert_eq!(
+                Predicate::Equal(Numeric::Integer(1)).test("a", Path::new("")),
+                Err(ErrorKind::InputNotNumber)
+            );
+        }
+
+        #[test_case(Predicate::Exists,    "file",    true  ; "exists file")]
+        #[test_case(Predicate::Exists,    "dir",     true  ; "exists dir")]
+        #[test_case(Predicate::Exists,    "missing", false ; "exists missing")]
+        #[test_case(Predicate::Exists,    "",        false ; "exists empty")]
+        #[test_case(Predicate::File,      "file",    true  ; "file file")]
+        #[test_case(Predicate::File,      "dir",     false ; "file dir")]
+        #[test_case(Predicate::Directory, "file",    false ; "directory file")]
+        #[test_case(Predicate::Directory, "dir",     true  ; "directory dir")]
+        fn test_path(predicate: Predicate, value: &str, result: bool) {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").touch().unwrap();
+            dir.child("dir").create_dir_all().unwrap();
+
+            assert_eq!(predicate.test(value, dir.path()), Ok(result));
+        }
+
+        #[test_case(Predicate::Empty,                 false ; "empty")]
+        #[test_case(Predicate::Exists,                true  ; "exists")]
+        #[test_case(Predicate::File,                  true  ; "file")]
+        #[test_case(Predicate::Directory,             true  ; "directory")]
+        #[test_case(Predicate::Glob("*.jpg".into()),  false ; "glob")]
+        fn reads_file(predicate: Predicate, result: bool) {
+            assert_eq!(predicate.reads_file(), result);
+        }
+
+        #[test_case(Predicate::Empty,                        "empty"                 ; "empty")]
+        #[test_case(Predicate::Exists,                       "an existing path"      ; "exists")]
+        #[test_case(Predicate::File,                         "a file"                ; "file")]
+        #[test_case(Predicate::Directory,                    "a directory"           ; "directory")]
+        #[test_case(Predicate::Glob("*.jpg".into()),         "matching glob '*.jpg'" ; "glob")]
+        #[test_case(Predicate::Equal(Numeric::Integer(1)),   "equal to 1"            ; "equal")]
+        #[test_case(Predicate::Less(Numeric::Integer(1)),    "less than 1"           ; "less")]
+        #[test_case(Predicate::Greater(Numeric::Integer(1)), "greater than 1"        ; "greater")]
+        fn display(predicate: Predicate, result: &str) {
+            assert_eq!(predicate.to_string(), result);
+        }
+    }
+
+    mod condition {
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case("z",  false ; "plain")]
+        #[test_case("!z", true  ; "negated")]
+        fn parse(input: &str, negated: bool) {
+            assert_eq!(
+                Condition::parse(&mut Reader::from(input)),
+                Ok(Condition {
+                    predicate: Predicate::Empty,
+                    negated
+                })
+            );
+        }
+
+        #[test_case(false, "",  true  ; "plain true")]
+        #[test_case(false, "a", false ; "plain false")]
+        #[test_case(true,  "",  false ; "negated false")]
+        #[test_case(true,  "a", true  ; "negated true")]
+        fn test(negated: bool, value: &str, result: bool) {
+            let condition = Condition {
+                predicate: Predicate::Empty,
+                negated,
+            };
+            assert_eq!(condition.test(value, Path::new("")), Ok(result));
+        }
+
+        #[test_case(false, "input is empty"     ; "plain")]
+        #[test_case(true,  "input is not empty" ; "negated")]
+        fn display(negated: bool, result: &str) {
+            let condition = Condition {
+                predicate: Predicate::Empty,
+                negated,
+            };
+            assert_eq!(condition.to_string(), result);
+        }
+    }
+
+    mod conditional {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+
+        type E = parse::ErrorKind;
+
+        #[test_case("",        0..0, E::ExpectedConditional       ; "empty")]
+        #[test_case(":",       1..1, E::ExpectedPredicate(None)   ; "missing predicate")]
+        #[test_case(":d",      2..2, E::ExpectedDelimiterChar     ; "missing then")]
+        #[test_case(":d:-",    3..4, E::UnknownFilter('-'.into()) ; "invalid then")]
+        #[test_case(":d::-",   4..5, E::UnknownFilter('-'.into()) ; "invalid otherwise")]
+        #[test_case(":d:ft",   4..5, E::ExpectedPipeOrExprEnd     ; "filter after filter")]
+        #[test_case(":d:f%|",  6..6, E::ExpectedFilter            ; "missing filter after pipe")]
+        #[test_case(":d:%|f",  3..3, E::ExpectedFilter            ; "missing filter before pipe")]
+        fn parse_err(input: &str, range: ErrorRange, kind: parse::ErrorKind) {
+            assert_eq!(
+                Conditional::parse(&mut Reader::new(chars(input)), &Config::fixture()),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test]
+        fn parse_ok() {
+            assert_eq!(
+                Conditional::parse(
+                    &mut Reader::new(chars(":!d:z%|v:q/e/t")),
+                    &Config::fixture()
+                ),
+                Ok(Conditional {
+                    condition: Condition {
+                        predicate: Predicate::Directory,
+                        negated: true,
+                    },
+                    then: vec![
+                        Parsed {
+                            value: Filter::EnsureTrailDirSeparator,
+                            range: 4..5,
+                        },
+                        Parsed {
+                            value: Filter::ToLowercase,
+                            range: 7..8,
+                        },
+                    ],
+                    otherwise: vec![Parsed {
+                        value: Filter::Conditional(Conditional {
+                            condition: Condition {
+                                predicate: Predicate::Exists,
+                                negated: false,
+                            },
+                            then: vec![Parsed {
+                                value: Filter::Trim,
+                                range: 13..14,
+                            }],
+                            otherwise: Vec::new(),
+                        }),
+                        range: 9..14,
+                    }],
+                })
+            );
+        }
+
+        #[test]
+        fn display() {
+            let conditional = Conditional {
+                condition: Condition {
+                    predicate: Predicate::Directory,
+                    negated: false,
+                },
+                then: vec![Parsed::from(Filter::EnsureTrailDirSeparator)],
+                otherwise: vec![Parsed::from(Filter::Conditional(Conditional {
+                    condition: Condition {
+                        predicate: Predicate::Empty,
+                        negated: true,
+                    },
+                    then: vec![
+                        Parsed::from(Filter::Trim),
+                        Parsed::from(Filter::ToLowercase),
+                    ],
+                    otherwise: Vec::new(),
+                }))],
+            };
+            assert_eq!(
+                conditional.to_string(),
+                "Conditional evaluation:
+
+    if input is a directory
+        Ensure trailing directory separator
+    else
+        Conditional evaluation:
+
+            if input is not empty
+                Trim
+                To lowercase
+            else
+                keep input"
+            );
+        }
+
+        fn chars(value: &str) -> Vec<Char> {
+            value
+                .replace("%|", "\0")
+                .chars()
+                .map(|char| match char {
+                    '\0' => Char::Escaped(PIPE, ['%', PIPE]),
+                    char => Char::Raw(char),
+                })
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc,
+};
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::escape::escape_str;
+use crate::pattern::reader::Reader;
+use crate::pattern::utils::AnyString;
+use crate::pattern::{eval, parse};
+
+#[derive(Debug, PartialEq)]
+pub enum DateSource {
+    Now,
+    Modified,
+    Created,
+    Input(Option<String>), // Parse format, RFC 3339 when not specified
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DateFormat {
+    pub source: DateSource,
+    pub utc: bool,
+    pub format: Option<String>, // RFC 3339 when not specified
+}
+
+impl DateFormat {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read().cloned();
+
+        let (source, utc) = match char.as_ref().map(Char::as_char) {
+            Some('n') => (DateSource::Now, false),
+            Some('N') => (DateSource::Now, true),
+            Some('m') => (DateSource::Modified, false),
+            Some('M') => (DateSource::Modified, true),
+            Some('c') => (DateSource::Created, false),
+            Some('C') => (DateSource::Created, true),
+            Some('i') => (DateSource::Input(None), false),
+            Some('I') => (DateSource::Input(None), true),
+            _ => {
+                return Err(parse::Error {
+                    kind: parse::ErrorKind::ExpectedDateSource(char),
+                    range: position..reader.position(),
+                })
+            }
+        };
+
+        if let Some(delimiter) = reader.read().cloned() {
+            let source = match source {
+                DateSource::Input(_) => DateSource::Input(parse_format(reader, Some(&delimiter))?),
+                source => source,
+            };
+            let format = parse_format(reader, None)?;
+            Ok(Self {
+                source,
+                utc,
+                format,
+            })
+        } else {
+            Ok(Self {
+                source,
+                utc,
+                format: None,
+            })
+        }
+    }
+
+    pub fn reads_file(&self) -> bool {
+        matches!(self.source, DateSource::Modified | DateSource::Created)
+    }
+
+    pub fn eval(&self, value: &str, working_dir: &Path) -> eval::BaseResult<String> {
+        let date = match &self.source {
+            DateSource::Now => Utc::now(),
+            DateSource::Modified => {
+                get_file_time(value, working_dir, |metadata| metadata.modified())?
+            }
+            DateSource::Created => {
+                get_file_time(value, working_dir, |metadata| metadata.created())?
+            }
+            DateSource::Input(format) => parse_date(value, format.as_deref(), self.utc)?,
+        };
+
+        if self.utc {
+            Ok(format_date(
+                &date.with_timezone(&Utc),
+                self.format.as_deref(),
+            ))
+        } else {
+            Ok(format_date(
+                &date.with_timezone(&Local),
+                self.format.as_deref(),
+            ))
+        }
+    }
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.source {
+            DateSource::Now => write!(formatter, "current time")?,
+            DateSource::Modified => write!(formatter, "file modification time")?,
+            DateSource::Created => write!(formatter, "file creation time")?,
+            DateSource::Input(None) => write!(formatter, "input date in RFC 3339")?,
+            DateSource::Input(Some(format)) => {
+                write!(formatter, "input date in '{}'", escape_str(format))?
+            }
+        }
+        match &self.format {
+            None => write!(formatter, " as RFC 3339")?,
+            Some(format) => write!(formatter, " as '{}'", escape_str(format))?,
+        }
+        if self.utc {
+            write!(formatter, " in UTC")
+        } else {
+            write!(formatter, " in local time zone")
+        }
+    }
+}
+
+// Empty format is the same as no format
+fn parse_format(
+    reader: &mut Reader<Char>,
+    delimiter: Option<&Char>,
+) -> parse::Result<Option<String>> {
+    let start = reader.position();
+    let format = match delimiter {
+        Some(delimiter) => reader.read_until(delimiter),
+        None => reader.read_to_end(),
+    };
+    let end = start + format.len_utf8();
+    let format = format.to_string();
+
+    if format.is_empty() {
+        Ok(None)
+    } else if StrftimeItems::new(&format).any(|item| item == Item::Error) {
+        Err(parse::Error {
+            kind: parse::ErrorKind::DateFormatInvalid(format),
+            range: start..end,
+        })
+    } else {
+        Ok(Some(format))
+    }
+}
+
+fn get_file_time<F>(value: &str, working_dir: &Path, get: F) -> eval::BaseResult<DateTime<Utc>>
+where
+    F: FnOnce(&fs::Metadata) -> std::io::Result<SystemTime>,
+{
+    fs::metadata(working_dir.join(value))
+        .and_then(|metadata| get(&metadata))
+        .map(DateTime::<Utc>::from)
+        .map_err(|error| eval::ErrorKind::MetadataUnavailable(AnyString(error.to_string())))
+}
+
+// Date without time zone is interpreted in the selected time zone
+fn parse_date(value: &str, format: Option<&str>, utc: bool) -> eval::BaseResult<DateTime<Utc>> {
+    let format = match format {
+        Some(format) => format,
+        None => {
+            return DateTime::parse_from_rfc3339(value)
+                .map(|date| date.with_timezone(&Utc))
+                .map_err(|error| eval::ErrorKind::DateParseFailed(error.to_string()))
+        }
+    };
+
+    let naive_date = match DateTime::<FixedOffset>::parse_from_str(value, format) {
+        Ok(date) => return Ok(date.with_timezone(&Utc)),
+        Err(error) => match NaiveDateTime::parse_from_str(value, format) {
+            Ok(date) => date,
+            Err(_) => match NaiveDate::parse_from_str(value, format) {
+                Ok(date) => date.and_hms_opt(0, 0, 0).expect("Midnight should be valid"),
+                Err(_) => return Err(eval::ErrorKind::DateParseFailed(error.to_string())),
+            },
+        },
+    };
+
+    if utc {
+        Ok(Utc.from_utc_datetime(&naive_date))
+    } else {
+        match Local.from_local_datetime(&naive_date).earliest() {
+            Some(date) => Ok(date.with_timezone(&Utc)),
+            None => Err(eval::ErrorKind::DateParseFailed(String::from(
+                "date does not exist in local time zone",
+            ))),
+        }
+    }
+}
+
+fn format_date<Tz: TimeZone>(date: &DateTime<Tz>, format: Option<&str>) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    match format {
+        Some(format) => date.format(format).to_string(),
+        None => date.to_rfc3339_opts(SecondsFormat::Secs, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",         0..0, ErrorKind::ExpectedDateSource(None)             ; "no source")]
+        #[test_case("x",        0..1, ErrorKind::ExpectedDateSource(Some('x'.into())) ; "invalid source")]
+        #[test_case("n:%Q",     2..4, ErrorKind::DateFormatInvalid("%Q".into())       ; "invalid format")]
+        #[test_case("i:%Q:%Y",  2..4, ErrorKind::DateFormatInvalid("%Q".into())       ; "invalid parse format")]
+        #[test_case("i:%Y:%Y%", 5..8, ErrorKind::DateFormatInvalid("%Y%".into())      ; "invalid format after parse format")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                DateFormat::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("n",          DateSource::Now,                      false, None          ; "now")]
+        #[test_case("N",          DateSource::Now,                      true,  None          ; "now utc")]
+        #[test_case("n:",         DateSource::Now,                      false, None          ; "now empty format")]
+        #[test_case("n:%Y",       DateSource::Now,                      false, Some("%Y")    ; "now format")]
+        #[test_case("m",          DateSource::Modified,                 false, None          ; "modified")]
+        #[test_case("M/%Y",       DateSource::Modified,                 true,  Some("%Y")    ; "modified utc format")]
+        #[test_case("c:%Y",       DateSource::Created,                  false, Some("%Y")    ; "created format")]
+        #[test_case("C",          DateSource::Created,                  true,  None          ; "created utc")]
+        #[test_case("i",          DateSource::Input(None),              false, None          ; "input")]
+        #[test_case("I::%Y",      DateSource::Input(None),              true,  Some("%Y")    ; "input utc format")]
+        #[test_case("i:%d",       DateSource::Input(Some("%d".into())), false, None          ; "input parse format")]
+        #[test_case("i:%d:%Y:%m", DateSource::Input(Some("%d".into())), false, Some("%Y:%m") ; "input both formats")]
+        fn ok(input: &str, source: DateSource, utc: bool, format: Option<&str>) {
+            assert_eq!(
+                DateFormat::parse(&mut Reader::from(input)),
+                Ok(DateFormat {
+                    source,
+                    utc,
+                    format: format.map(String::from)
+                })
+            );
+        }
+    }
+
+    mod eval {
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use filetime::{set_file_mtime, FileTime};
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::eval::ErrorKind;
+
+        #[test_case("abc",        None,             "premature end of input"            ; "rfc 3339")]
+        #[test_case("2021-13-01", Some("%Y-%m-%d"), "input is out of range"             ; "out of range")]
+        #[test_case("01.02.2021", Some("%Y-%m-%d"), "input contains invalid characters" ; "format mismatch")]
+        fn input_err(input: &str, parse_format: Option<&str>, reason: &str) {
+            let date_format = DateFormat {
+                source: DateSource::Input(parse_format.map(String::from)),
+                utc: true,
+                format: None,
+            };
+            assert_eq!(
+                date_format.eval(input, Path::new("")),
+                Err(ErrorKind::DateParseFailed(reason.into()))
+            );
+        }
+
+        #[test_case("2021-02-03T04:05:06+01:00", None,                      None,                   "2021-02-03T03:05:06+00:00" ; "rfc 3339")]
+        #[test_case("2021-02-03T04:05:06Z",      None,                      Some("%d.%m.%Y %H:%M"), "03.02.2021 04:05"          ; "rfc 3339 to custom")]
+        #[test_case("03.02.2021",                Some("%d.%m.%Y"),          Some("%F"),             "2021-02-03"                ; "date")]
+        #[test_case("03.02.2021 04:05",          Some("%d.%m.%Y %H:%M"),    Some("%F %T"),          "2021-02-03 04:05:00"       ; "date time")]
+        #[test_case("03.02.2021 04:05 +0100",    Some("%d.%m.%Y %H:%M %z"), Some("%F %T"),          "2021-02-03 03:05:00"       ; "date time with offset")]
+        fn input_ok(input: &str, parse_format: Option<&str>, format: Option<&str>, output: &str) {
+            let date_format = DateFormat {
+                source: DateSource::Input(parse_format.map(String::from)),
+                utc: true,
+                format: format.map(String::from),
+            };
+            assert_eq!(date_format.eval(input, Path::new("")), Ok(output.into()));
+        }
+
+        #[test]
+        fn input_local() {
+            let date_format = DateFormat {
+                source: DateSource::Input(Some("%F %T".into())),
+                utc: false,
+                format: Some("%F %T".into()),
+            };
+            assert_eq!(
+                date_format.eval("2021-02-03 04:05:06", Path::new("")),
+                Ok("2021-02-03 04:05:06".into())
+            );
+        }
+
+        #[test]
+        fn now() {
+            let date_format = DateFormat {
+                source: DateSource::Now,
+                utc: true,
+                format: Some("%s".into()),
+            };
+            let before = Utc::now().timestamp();
+            let result = date_format.eval("", Path::new("")).unwrap();
+            let after = Utc::now().timestamp();
+
+            let timestamp = result.parse::<i64>().unwrap();
+            assert!(before <= timestamp && timestamp <= after);
+        }
+
+        #[test]
+        fn modified() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            file.touch().unwrap();
+            set_file_mtime(&file, FileTime::from_unix_time(1_600_000_000, 0)).unwrap();
+
+            let date_format = DateFormat {
+                source: DateSource::Modified,
+                utc: true,
+                format: None,
+            };
+            assert_eq!(
+                date_format.eval("file", dir.path()),
+                Ok("2020-09-13T12:26:40+00:00".into())
+            );
+        }
+
+        #[test_case(DateSource::Modified ; "modified")]
+        #[test_case(DateSource::Created  ; "created")]
+        fn missing_file(source: DateSource) {
+            let dir = TempDir::new().unwrap();
+            let date_format = DateFormat {
+                source,
+                utc: true,
+                format: None,
+            };
+            assert_eq!(
+                date_format.eval("missing", dir.path()),
+                Err(ErrorKind::MetadataUnavailable(AnyString::any()))
+            );
+        }
+    }
+
+    #[test_case(DateSource::Now,         false ; "now")]
+    #[test_case(DateSource::Modified,    true  ; "modified")]
+    #[test_case(DateSource::Created,     true  ; "created")]
+    #[test_case(DateSource::Input(None), false ; "input")]
+    fn reads_file(source: DateSource, result: bool) {
+        let date_format = DateFormat {
+            source,
+            utc: false,
+            format: None,
+        };
+        assert_eq!(date_format.reads_file(), result);
+    }
+
+    #[test_case(DateSource::Now,                      false, None,       "current time as RFC 3339 in local time zone"   ; "now")]
+    #[test_case(DateSource::Modified,                 true,  Some("%Y"), "file modification time as '%Y' in UTC"         ; "modified")]
+    #[test_case(DateSource::Created,                  false, Some("%Y"), "file creation time as '%Y' in local time zone" ; "created")]
+    #[test_case(DateSource::Input(None),              true,  None,       "input date in RFC 3339 as RFC 3339 in UTC"     ; "input")]
+    #[test_case(DateSource::Input(Some("%d".into())), true,  Some("%Y"), "input date in '%d' as '%Y' in UTC"             ; "input with format")]
+    fn display(source: DateSource, utc: bool, format: Option<&str>, result: &str) {
+        let date_format = DateFormat {
+            source,
+            utc,
+            format: format.map(String::from),
+        };
+        assert_eq!(date_format.to_string(), result);
+    }
+}
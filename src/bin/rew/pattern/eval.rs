@@ -1,8 +1,12 @@
 use std::path::Path;
 use std::{error, fmt, result};
 
+use crate::pattern::audio::AudioCache;
 use crate::pattern::error::{ErrorRange, GetErrorRange};
+use crate::pattern::escape::escape_str;
+use crate::pattern::exif::ExifCache;
 use crate::pattern::filter::Filter;
+use crate::pattern::parse;
 use crate::pattern::utils::AnyString;
 
 pub type Counter = u32;
@@ -13,6 +17,15 @@ pub struct Context<'a> {
     pub local_counter: Counter,
     pub regex_captures: Option<regex::Captures<'a>>,
     pub expression_quotes: Option<char>,
+    pub exif_cache: ExifCache,
+    pub audio_cache: AudioCache,
+    pub normalized_input: Option<NormalizedInput<'a>>,
+}
+
+// Input value which was normalized before the pattern evaluation
+pub struct NormalizedInput<'a> {
+    pub original: &'a str,
+    pub normalized: &'a str,
 }
 
 impl<'a> Context<'a> {
@@ -23,6 +36,14 @@ impl<'a> Context<'a> {
             .map_or("", |capture| capture.as_str())
     }
 
+    // Filters reading a file need the path as it is on the disk, not its normalized form
+    pub fn file_path<'b>(&'b self, value: &'b str) -> &'b str {
+        match &self.normalized_input {
+            Some(input) if input.normalized == value => input.original,
+            _ => value,
+        }
+    }
+
     #[cfg(test)]
     pub fn fixture() -> Self {
         Context {
@@ -34,6 +55,9 @@ impl<'a> Context<'a> {
             global_counter: 2,
             regex_captures: regex::Regex::new("(.).(.)").unwrap().captures("abc"),
             expression_quotes: None,
+            exif_cache: ExifCache::default(),
+            audio_cache: AudioCache::default(),
+            normalized_input: None,
         }
     }
 }
@@ -71,6 +95,19 @@ impl<'a> fmt::Display for Error<'a> {
 pub enum ErrorKind {
     InputNotUtf8,
     CanonicalizationFailed(AnyString),
+    MetadataUnavailable(AnyString),
+    ExifUnavailable(AnyString),
+    AudioTagsUnavailable(AnyString),
+    FileReadFailed(AnyString),
+    DateParseFailed(String),
+    InputNotNumber,
+    DivisionByZero,
+    ArithmeticOverflow,
+    InputNotInteger,
+    RomanNumeralOutOfRange,
+    ExpandedFilterInvalid(parse::ErrorKind),
+    VariableUndefined(String),
+    LookupKeyMissing(String),
 }
 
 impl fmt::Display for ErrorKind {
@@ -80,6 +117,33 @@ impl fmt::Display for ErrorKind {
             Self::CanonicalizationFailed(reason) => {
                 write!(formatter, "Path canonicalization failed: {}", reason)
             }
+            Self::MetadataUnavailable(reason) => {
+                write!(formatter, "Cannot read file metadata: {}", reason)
+            }
+            Self::ExifUnavailable(reason) => write!(formatter, "Cannot read EXIF data: {}", reason),
+            Self::AudioTagsUnavailable(reason) => {
+                write!(formatter, "Cannot read audio tags: {}", reason)
+            }
+            Self::FileReadFailed(reason) => write!(formatter, "Cannot read file: {}", reason),
+            Self::DateParseFailed(reason) => write!(formatter, "Cannot parse date: {}", reason),
+            Self::InputNotNumber => write!(formatter, "Input is not a number"),
+            Self::DivisionByZero => write!(formatter, "Division by zero"),
+            Self::ArithmeticOverflow => write!(formatter, "Arithmetic overflow"),
+            Self::InputNotInteger => write!(formatter, "Input is not an integer"),
+            Self::RomanNumeralOutOfRange => write!(formatter, "Input exceeds roman numeral range"),
+            Self::ExpandedFilterInvalid(kind) => {
+                write!(formatter, "Invalid filter after expansion: {}", kind)
+            }
+            Self::VariableUndefined(name) => {
+                write!(formatter, "Variable '{}' is not defined", name)
+            }
+            Self::LookupKeyMissing(key) => {
+                write!(
+                    formatter,
+                    "Key '{}' not found in lookup table",
+                    escape_str(key)
+                )
+            }
         }
     }
 }
@@ -112,6 +176,28 @@ mod tests {
         }
     }
 
+    mod eval_context_file_path {
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case("cafe\u{301}", "cafe\u{301}" ; "not normalized")]
+        fn none(value: &str, result: &str) {
+            assert_eq!(Context::fixture().file_path(value), result);
+        }
+
+        #[test_case("caf\u{e9}",  "cafe\u{301}" ; "input")]
+        #[test_case("caf\u{e9}/", "caf\u{e9}/"  ; "other value")]
+        fn some(value: &str, result: &str) {
+            let mut context = Context::fixture();
+            context.normalized_input = Some(NormalizedInput {
+                original: "cafe\u{301}",
+                normalized: "caf\u{e9}",
+            });
+            assert_eq!(context.file_path(value), result);
+        }
+    }
+
     mod error {
         use super::*;
 
@@ -144,8 +230,21 @@ mod tests {
         }
     }
 
-    #[test_case(ErrorKind::InputNotUtf8,                         "Input does not have UTF-8 encoding" ; "input not utf-8")]
-    #[test_case(ErrorKind::CanonicalizationFailed("abc".into()), "Path canonicalization failed: abc"  ; "canonicalization failed")]
+    #[test_case(ErrorKind::InputNotUtf8,                                            "Input does not have UTF-8 encoding"              ; "input not utf-8")]
+    #[test_case(ErrorKind::CanonicalizationFailed("abc".into()),                    "Path canonicalization failed: abc"               ; "canonicalization failed")]
+    #[test_case(ErrorKind::MetadataUnavailable("abc".into()),                       "Cannot read file metadata: abc"                  ; "metadata unavailable")]
+    #[test_case(ErrorKind::ExifUnavailable("abc".into()),                           "Cannot read EXIF data: abc"                      ; "exif unavailable")]
+    #[test_case(ErrorKind::AudioTagsUnavailable("abc".into()),                      "Cannot read audio tags: abc"                     ; "audio tags unavailable")]
+    #[test_case(ErrorKind::FileReadFailed("abc".into()),                            "Cannot read file: abc"                           ; "file read failed")]
+    #[test_case(ErrorKind::DateParseFailed("abc".into()),                           "Cannot parse date: abc"                          ; "date parse failed")]
+    #[test_case(ErrorKind::InputNotNumber,                                          "Input is not a number"                           ; "input not number")]
+    #[test_case(ErrorKind::DivisionByZero,                                          "Division by zero"                                ; "division by zero")]
+    #[test_case(ErrorKind::ArithmeticOverflow,                                      "Arithmetic overflow"                             ; "arithmetic overflow")]
+    #[test_case(ErrorKind::InputNotInteger,                                         "Input is not an integer"                         ; "input not integer")]
+    #[test_case(ErrorKind::RomanNumeralOutOfRange,                                  "Input exceeds roman numeral range"               ; "roman numeral out of range")]
+    #[test_case(ErrorKind::ExpandedFilterInvalid(parse::ErrorKind::ExpectedNumber), "Invalid filter after expansion: Expected number" ; "expanded filter invalid")]
+    #[test_case(ErrorKind::VariableUndefined("x".into()),                           "Variable 'x' is not defined"                     ; "variable undefined")]
+    #[test_case(ErrorKind::LookupKeyMissing("x".into()),                            "Key 'x' not found in lookup table"               ; "lookup key missing")]
     fn error_kind_display(kind: ErrorKind, result: &str) {
         assert_eq!(kind.to_string(), result);
     }
@@ -0,0 +1,240 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use ::exif::{Exif, Field, In, Reader as ExifReader, Tag, Value};
+
+use crate::pattern::char::Char;
+use crate::pattern::escape::escape_str;
+use crate::pattern::eval::{BaseResult, ErrorKind};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+use crate::pattern::utils::AnyString;
+
+#[derive(Debug, PartialEq)]
+pub struct ExifTag(pub String);
+
+impl ExifTag {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let name = reader.read_to_end().to_string();
+
+        if name.is_empty() {
+            Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedExifTag,
+                range: position..reader.end(),
+            })
+        } else {
+            Ok(Self(name))
+        }
+    }
+
+    // Missing tag is not an error, so its value can be replaced by a default one
+    pub fn get(&self, value: &str, working_dir: &Path, cache: &ExifCache) -> BaseResult<String> {
+        let exif = cache.get(&working_dir.join(value))?;
+
+        // Thumbnail has its own set of tags which we are not interested in
+        let field = exif.fields().find(|field| {
+            field.ifd_num == In::PRIMARY && field.tag.to_string().eq_ignore_ascii_case(&self.0)
+        });
+
+        Ok(field.map(format_field).unwrap_or_default())
+    }
+}
+
+// Multiple tags of the same file are extracted from a single parsed EXIF
+#[derive(Default)]
+pub struct ExifCache(RefCell<HashMap<PathBuf, Rc<Exif>>>);
+
+impl ExifCache {
+    fn get(&self, path: &Path) -> BaseResult<Rc<Exif>> {
+        if let Some(exif) = self.0.borrow().get(path) {
+            return Ok(exif.clone());
+        }
+
+        let file = File::open(path).map_err(unavailable)?;
+        let exif = ExifReader::new()
+            .read_from_container(&mut BufReader::new(file))
+            .map_err(unavailable)?;
+
+        let exif = Rc::new(exif);
+        self.0.borrow_mut().insert(path.to_path_buf(), exif.clone());
+        Ok(exif)
+    }
+}
+
+impl fmt::Display for ExifTag {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "EXIF tag '{}'", escape_str(&self.0))
+    }
+}
+
+fn unavailable<E: ToString>(error: E) -> ErrorKind {
+    ErrorKind::ExifUnavailable(AnyString(error.to_string()))
+}
+
+fn format_field(field: &Field) -> String {
+    match &field.value {
+        // Dates are displayed as 'YYYY-MM-DD HH:MM:SS' instead of 'YYYY:MM:DD HH:MM:SS'
+        Value::Ascii(_) if is_date_time(field.tag) => field.display_value().to_string(),
+        // Display value of a string would be quoted and escaped
+        Value::Ascii(strings) => strings
+            .iter()
+            .map(|string| String::from_utf8_lossy(string).trim().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => field.display_value().to_string(),
+    }
+}
+
+fn is_date_time(tag: Tag) -> bool {
+    tag == Tag::DateTime || tag == Tag::DateTimeOriginal || tag == Tag::DateTimeDigitized
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use super::*;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test]
+        fn err() {
+            assert_eq!(
+                ExifTag::parse(&mut Reader::from("")),
+                Err(Error {
+                    kind: ErrorKind::ExpectedExifTag,
+                    range: 0..0,
+                })
+            );
+        }
+
+        #[test]
+        fn ok() {
+            let mut reader = Reader::from("Model");
+            assert_eq!(ExifTag::parse(&mut reader), Ok(ExifTag("Model".into())));
+            assert_eq!(reader.position(), 5);
+        }
+    }
+
+    mod get {
+        use ::exif::experimental::Writer;
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use test_case::test_case;
+
+        use super::*;
+
+        fn create_image(dir: &TempDir) {
+            let fields = [
+                Field {
+                    tag: Tag::Model,
+                    ifd_num: In::PRIMARY,
+                    value: Value::Ascii(vec![b"Camera 1 ".to_vec()]),
+                },
+                Field {
+                    tag: Tag::DateTime,
+                    ifd_num: In::PRIMARY,
+                    value: Value::Ascii(vec![b"2021:02:03 04:05:06".to_vec()]),
+                },
+                Field {
+                    tag: Tag::ImageWidth,
+                    ifd_num: In::PRIMARY,
+                    value: Value::Long(vec![4000]),
+                },
+                Field {
+                    tag: Tag::Orientation,
+                    ifd_num: In::PRIMARY,
+                    value: Value::Short(vec![1]),
+                },
+                Field {
+                    tag: Tag::Software,
+                    ifd_num: In::THUMBNAIL,
+                    value: Value::Ascii(vec![b"Thumbnail".to_vec()]),
+                },
+            ];
+
+            let mut writer = Writer::new();
+            for field in &fields {
+                writer.push_field(field);
+            }
+
+            let mut output = std::io::Cursor::new(Vec::new());
+            writer.write(&mut output, false).unwrap();
+            dir.child("image.tiff")
+                .write_binary(&output.into_inner())
+                .unwrap();
+        }
+
+        #[test_case("Model",       "Camera 1"                          ; "ascii")]
+        #[test_case("model",       "Camera 1"                          ; "case insensitive")]
+        #[test_case("DateTime",    "2021-02-03 04:05:06"               ; "date time")]
+        #[test_case("ImageWidth",  "4000"                              ; "number")]
+        #[test_case("Orientation", "row 0 at top and column 0 at left" ; "described")]
+        #[test_case("Software",    ""                                  ; "thumbnail")]
+        #[test_case("Make",        ""                                  ; "missing")]
+        #[test_case("Unknown",     ""                                  ; "unknown")]
+        fn ok(name: &str, result: &str) {
+            let dir = TempDir::new().unwrap();
+            create_image(&dir);
+
+            assert_eq!(
+                ExifTag(name.into()).get("image.tiff", dir.path(), &ExifCache::default()),
+                Ok(result.into())
+            );
+        }
+
+        #[test]
+        fn cached() {
+            let dir = TempDir::new().unwrap();
+            create_image(&dir);
+            let cache = ExifCache::default();
+
+            assert_eq!(
+                ExifTag("Model".into()).get("image.tiff", dir.path(), &cache),
+                Ok("Camera 1".into())
+            );
+
+            std::fs::remove_file(dir.path().join("image.tiff")).unwrap();
+
+            assert_eq!(
+                ExifTag("ImageWidth".into()).get("image.tiff", dir.path(), &cache),
+                Ok("4000".into())
+            );
+        }
+
+        #[test]
+        fn no_exif() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").write_str("abc").unwrap();
+
+            assert_eq!(
+                ExifTag("Model".into()).get("file", dir.path(), &ExifCache::default()),
+                Err(ErrorKind::ExifUnavailable(AnyString::any()))
+            );
+        }
+
+        #[test]
+        fn missing_file() {
+            let dir = TempDir::new().unwrap();
+
+            assert_eq!(
+                ExifTag("Model".into()).get("missing", dir.path(), &ExifCache::default()),
+                Err(ErrorKind::ExifUnavailable(AnyString::any()))
+            );
+        }
+    }
+
+    #[test_case("Model", "EXIF tag 'Model'" ; "simple")]
+    #[test_case("A\nB",  "EXIF tag 'A\\nB'" ; "escaped")]
+    fn display(name: &str, result: &str) {
+        assert_eq!(ExifTag(name.into()).to_string(), result);
+    }
+}
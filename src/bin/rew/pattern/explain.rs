@@ -5,33 +5,71 @@ use common::color::spec_color;
 use termcolor::{Color, WriteColor};
 
 use crate::output::highlight_range;
+use crate::pattern::filter::Filter;
 use crate::pattern::parse::Parsed;
-use crate::pattern::parser::Item;
+use crate::pattern::parser::{Item, ParsedFilter};
 use crate::pattern::Pattern;
 
+const INDENT: &str = "    ";
+
 impl Pattern {
     pub fn explain<O: Write + WriteColor>(&self, output: &mut O, all: bool) -> Result<()> {
         for item in &self.items {
             match &item.value {
                 Item::Constant(_) => {
                     if all {
-                        self.explain_part(output, item, Color::Green)?;
+                        self.explain_part(output, item, Color::Green, 0)?;
                     }
                 }
                 Item::Expression(filters) => {
                     if all {
-                        self.explain_part(output, item, Color::Yellow)?;
+                        self.explain_part(output, item, Color::Yellow, 0)?;
+                    }
+                    self.explain_filters(output, filters, 0)?;
+                }
+                Item::Binding(filters, binding) => {
+                    if all {
+                        self.explain_part(output, item, Color::Yellow, 0)?;
                     }
-                    for filter in filters {
-                        self.explain_part(output, filter, Color::Blue)?;
+                    self.explain_filters(output, filters, 0)?;
+                    self.explain_part(output, binding, Color::Magenta, 0)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn explain_filters<'a, O, I>(&self, output: &mut O, filters: I, depth: usize) -> Result<()>
+    where
+        O: Write + WriteColor,
+        I: IntoIterator<Item = &'a ParsedFilter>,
+    {
+        for filter in filters {
+            self.explain_part(output, filter, Color::Blue, depth)?;
+
+            match &filter.value {
+                Filter::Conditional(conditional) => {
+                    self.explain_filters(output, conditional.filters(), depth + 1)?;
+                }
+                Filter::Template(template) => {
+                    for expression in template.expressions() {
+                        self.explain_part(output, expression, Color::Yellow, depth + 1)?;
+                        self.explain_filters(output, &expression.value.0, depth + 1)?;
                     }
                 }
+                _ => {}
             }
         }
         Ok(())
     }
 
-    fn explain_part<O, T>(&self, output: &mut O, part: &Parsed<T>, color: Color) -> Result<()>
+    fn explain_part<O, T>(
+        &self,
+        output: &mut O,
+        part: &Parsed<T>,
+        color: Color,
+        depth: usize,
+    ) -> Result<()>
     where
         O: Write + WriteColor,
         T: Display,
@@ -39,25 +77,51 @@ impl Pattern {
         highlight_range(output, &self.source, &part.range, color)?;
         writeln!(output)?;
         output.set_color(&spec_color(color))?;
-        write!(output, "{}", part.value)?;
+        write!(output, "{}", indent(&part.value.to_string(), depth))?;
         output.reset()?;
         write!(output, "\n\n")
     }
 }
 
+// Explanation of nested parts is indented to render the whole pattern as a tree
+fn indent(value: &str, depth: usize) -> String {
+    if depth == 0 {
+        return value.to_string();
+    }
+
+    let prefix = INDENT.repeat(depth);
+    let lines: Vec<String> = value
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use common::testing::{ColoredOuput, OutputChunk};
     use test_case::test_case;
 
     use super::*;
+    use crate::pattern::char::Char;
     use crate::pattern::filter::Filter;
-    use crate::pattern::parse::Parsed;
+    use crate::pattern::parse::{Config, Parsed};
+    use crate::pattern::template::{Expression, Part, Template};
+    use crate::pattern::variable::Binding;
 
-    #[test_case(empty_pattern(),    false, Vec::new()      ; "empty filters")]
-    #[test_case(empty_pattern(),    true,  Vec::new()      ; "empty all")]
-    #[test_case(nonempty_pattern(), false, filter_chunks() ; "nonempty filters")]
-    #[test_case(nonempty_pattern(), true,  all_chunks()    ; "nonempty all")]
+    #[test_case(empty_pattern(),    false, Vec::new()       ; "empty filters")]
+    #[test_case(empty_pattern(),    true,  Vec::new()       ; "empty all")]
+    #[test_case(nonempty_pattern(), false, filter_chunks()  ; "nonempty filters")]
+    #[test_case(nonempty_pattern(), true,  all_chunks()     ; "nonempty all")]
+    #[test_case(binding_pattern(),  false, binding_chunks() ; "binding filters")]
+    #[test_case(nested_pattern(),   false, nested_chunks()  ; "nested filters")]
     fn explain(pattern: Pattern, all: bool, chunks: Vec<OutputChunk>) {
         let mut output = ColoredOuput::new();
         pattern.explain(&mut output, all).unwrap();
@@ -142,4 +206,87 @@ mod tests {
             OutputChunk::plain("\n\n"),
         ]
     }
+
+    fn binding_pattern() -> Pattern {
+        Pattern {
+            source: "{f|as x}".into(),
+            items: vec![Parsed {
+                value: Item::Binding(
+                    vec![Parsed {
+                        value: Filter::FileName,
+                        range: 1..2,
+                    }],
+                    Parsed {
+                        value: Binding("x".into()),
+                        range: 3..7,
+                    },
+                ),
+                range: 0..8,
+            }],
+        }
+    }
+
+    fn binding_chunks() -> Vec<OutputChunk> {
+        vec![
+            OutputChunk::plain("{"),
+            OutputChunk::bold_color(Color::Blue, "f"),
+            OutputChunk::plain("|as x}\n "),
+            OutputChunk::bold_color(Color::Blue, "^"),
+            OutputChunk::plain("\n\n"),
+            OutputChunk::color(Color::Blue, "File name"),
+            OutputChunk::plain("\n\n{f|"),
+            OutputChunk::bold_color(Color::Magenta, "as x"),
+            OutputChunk::plain("}\n   "),
+            OutputChunk::bold_color(Color::Magenta, "^^^^"),
+            OutputChunk::plain("\n\n"),
+            OutputChunk::color(Color::Magenta, "Bind result to variable 'x'"),
+            OutputChunk::plain("\n\n"),
+        ]
+    }
+    fn nested_pattern() -> Pattern {
+        let parts = vec![
+            Part::Chars(vec![Char::Raw('r'), Char::Raw(':')]),
+            Part::Expression(Parsed {
+                value: Expression(vec![Parsed {
+                    value: Filter::ParentDirectory,
+                    range: 4..5,
+                }]),
+                range: 3..6,
+            }),
+        ];
+        Pattern {
+            source: "{r:{d}}".into(),
+            items: vec![Parsed {
+                value: Item::Expression(vec![Parsed {
+                    value: Filter::Template(Template::new(parts, &Config::fixture()).unwrap()),
+                    range: 1..6,
+                }]),
+                range: 0..7,
+            }],
+        }
+    }
+
+    fn nested_chunks() -> Vec<OutputChunk> {
+        vec![
+            OutputChunk::plain("{"),
+            OutputChunk::bold_color(Color::Blue, "r:{d}"),
+            OutputChunk::plain("}\n "),
+            OutputChunk::bold_color(Color::Blue, "^^^^^"),
+            OutputChunk::plain("\n\n"),
+            OutputChunk::color(Color::Blue, "Filter with a nested expression"),
+            OutputChunk::plain("\n\n{r:"),
+            OutputChunk::bold_color(Color::Yellow, "{d}"),
+            OutputChunk::plain("}\n   "),
+            OutputChunk::bold_color(Color::Yellow, "^^^"),
+            OutputChunk::plain("\n\n"),
+            OutputChunk::color(Color::Yellow, "    Nested expression with a filter"),
+            OutputChunk::plain("\n\n{r:{"),
+            OutputChunk::bold_color(Color::Blue, "d"),
+            OutputChunk::plain("}}\n    "),
+            OutputChunk::bold_color(Color::Blue, "^"),
+            OutputChunk::plain("\n\n"),
+            OutputChunk::color(Color::Blue, "    Parent directory"),
+            OutputChunk::plain("\n\n"),
+        ]
+    }
 }
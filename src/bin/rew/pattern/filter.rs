@@ -2,19 +2,33 @@ use std::fmt;
 
 use unidecode::unidecode;
 
+use crate::pattern::arithmetic::Arithmetic;
+use crate::pattern::audio::AudioTag;
+use crate::pattern::case::CaseStyle;
 use crate::pattern::char::{AsChar, Char};
+use crate::pattern::conditional::Conditional;
+use crate::pattern::date::DateFormat;
+use crate::pattern::exif::ExifTag;
 use crate::pattern::field::Field;
+use crate::pattern::hash::{Hash, HashSource};
 use crate::pattern::integer::parse_integer;
+use crate::pattern::lookup::Lookup;
+use crate::pattern::metadata::Attribute;
+use crate::pattern::normalize::{remove_diacritics, Normalization};
 use crate::pattern::number::NumberRange;
+use crate::pattern::number_format::NumberFormat;
 use crate::pattern::padding::Padding;
 use crate::pattern::reader::Reader;
-use crate::pattern::regex::RegexMatcher;
+use crate::pattern::regex::{RegexMatcher, Syntax};
 use crate::pattern::repeat::Repetition;
 use crate::pattern::replace::{EmptySubstitution, RegexSubstitution, StringSubstitution};
+use crate::pattern::sanitize::Profile;
 use crate::pattern::substr::CharIndexRange;
 use crate::pattern::switch::RegexSwitch;
 use crate::pattern::symbols::REVERSE_INDEX;
+use crate::pattern::template::Template;
 use crate::pattern::uuid::random_uuid;
+use crate::pattern::variable::{is_name_start, parse_name};
 use crate::pattern::{eval, parse, path};
 
 #[derive(Debug, PartialEq)]
@@ -34,6 +48,9 @@ pub enum Filter {
     ExtensionWithDot,
     EnsureTrailDirSeparator,
     RemoveTrailDirSeparator,
+    Metadata(Attribute),
+    ExifTag(ExifTag),
+    AudioTag(AudioTag),
     Substring(CharIndexRange),
     SubstringRev(CharIndexRange),
     GetField(Field),
@@ -46,19 +63,31 @@ pub enum Filter {
     RegexReplaceFirst(RegexSubstitution),
     RegexReplaceAll(RegexSubstitution),
     RegexSwitch(RegexSwitch),
+    Lookup(Lookup),
+    Conditional(Conditional),
+    Template(Template),
     RegexCapture(usize),
+    Variable(String),
     Trim,
     ToLowercase,
     ToUppercase,
+    ToCase(CaseStyle),
     ToAscii,
     RemoveNonAscii,
+    Normalize(Normalization),
+    RemoveDiacritics,
+    Sanitize(Profile),
     LeftPad(Padding),
     RightPad(Padding),
     Repeat(Repetition),
+    Arithmetic(Arithmetic),
+    FormatNumber(NumberFormat),
     LocalCounter,
     GlobalCounter,
     RandomNumber(NumberRange),
     RandomUuid,
+    FormatDate(DateFormat),
+    Hash(Hash),
 }
 
 impl Filter {
@@ -82,6 +111,9 @@ impl Filter {
                 'E' => Ok(Self::ExtensionWithDot),
                 'z' => Ok(Self::EnsureTrailDirSeparator),
                 'Z' => Ok(Self::RemoveTrailDirSeparator),
+                'm' => Ok(Self::Metadata(Attribute::parse(reader)?)),
+                'x' => Ok(Self::ExifTag(ExifTag::parse(reader)?)),
+                'g' => Ok(Self::AudioTag(AudioTag::parse(reader)?)),
                 '#' => {
                     if reader.read_expected(REVERSE_INDEX) {
                         Ok(Self::SubstringRev(CharIndexRange::parse(reader)?))
@@ -100,29 +132,49 @@ impl Filter {
                 'r' => Ok(Self::ReplaceFirst(StringSubstitution::parse(reader)?)),
                 'R' => Ok(Self::ReplaceAll(StringSubstitution::parse(reader)?)),
                 '?' => Ok(Self::ReplaceEmpty(EmptySubstitution::parse(reader)?)),
-                '=' => {
-                    if reader.read_expected(REVERSE_INDEX) {
-                        Ok(Self::RegexMatchRev(RegexMatcher::parse(reader)?))
+                '=' | 's' | 'S' | '@' => Self::parse_regex(char.as_char(), reader, Syntax::Regex),
+                'G' => {
+                    let glob_position = reader.position();
+                    match reader.read().cloned() {
+                        Some(char) if matches!(char.as_char(), '=' | 's' | 'S' | '@') => {
+                            Self::parse_regex(char.as_char(), reader, Syntax::Glob)
+                        }
+                        char => Err(parse::Error {
+                            kind: parse::ErrorKind::ExpectedGlobFilter(char),
+                            range: glob_position..reader.position(),
+                        }),
+                    }
+                }
+                'l' => Ok(Self::Lookup(Lookup::parse(reader, &config.working_dir)?)),
+                'q' => Ok(Self::Conditional(Conditional::parse(reader, config)?)),
+                '$' => {
+                    if reader.peek_char().is_some_and(is_name_start) {
+                        Ok(Self::Variable(parse_name(reader)))
                     } else {
-                        Ok(Self::RegexMatch(RegexMatcher::parse(reader)?))
+                        Ok(Self::RegexCapture(parse_integer(reader)?))
                     }
                 }
-                's' => Ok(Self::RegexReplaceFirst(RegexSubstitution::parse(reader)?)),
-                'S' => Ok(Self::RegexReplaceAll(RegexSubstitution::parse(reader)?)),
-                '@' => Ok(Self::RegexSwitch(RegexSwitch::parse(reader)?)),
-                '$' => Ok(Self::RegexCapture(parse_integer(reader)?)),
                 't' => Ok(Self::Trim),
                 'v' => Ok(Self::ToLowercase),
                 '^' => Ok(Self::ToUppercase),
+                '~' => Ok(Self::ToCase(CaseStyle::parse(reader)?)),
                 'i' => Ok(Self::ToAscii),
                 'I' => Ok(Self::RemoveNonAscii),
+                'n' => Ok(Self::Normalize(Normalization::parse(reader)?)),
+                'N' => Ok(Self::RemoveDiacritics),
+                '!' => Ok(Self::Sanitize(Profile::parse(reader)?)),
                 '<' => Ok(Self::LeftPad(Padding::parse(reader, '<')?)),
                 '>' => Ok(Self::RightPad(Padding::parse(reader, '>')?)),
                 '*' => Ok(Self::Repeat(Repetition::parse(reader)?)),
+                'o' => Ok(Self::Arithmetic(Arithmetic::parse(reader)?)),
+                'k' => Ok(Self::FormatNumber(NumberFormat::parse(reader)?)),
                 'c' => Ok(Self::LocalCounter),
                 'C' => Ok(Self::GlobalCounter),
                 'u' => Ok(Self::RandomNumber(NumberRange::parse(reader)?)),
                 'U' => Ok(Self::RandomUuid),
+                'T' => Ok(Self::FormatDate(DateFormat::parse(reader)?)),
+                'h' => Ok(Self::Hash(Hash::parse(reader, HashSource::Input)?)),
+                'H' => Ok(Self::Hash(Hash::parse(reader, HashSource::File)?)),
                 _ => Err(parse::Error {
                     kind: parse::ErrorKind::UnknownFilter(char.clone()),
                     range: position..reader.position(),
@@ -136,6 +188,22 @@ impl Filter {
         }
     }
 
+    fn parse_regex(filter: char, reader: &mut Reader<Char>, syntax: Syntax) -> parse::Result<Self> {
+        match filter {
+            '=' => {
+                if reader.read_expected(REVERSE_INDEX) {
+                    Ok(Self::RegexMatchRev(RegexMatcher::parse(reader, syntax)?))
+                } else {
+                    Ok(Self::RegexMatch(RegexMatcher::parse(reader, syntax)?))
+                }
+            }
+            's' => RegexSubstitution::parse(reader, syntax).map(Self::RegexReplaceFirst),
+            'S' => RegexSubstitution::parse(reader, syntax).map(Self::RegexReplaceAll),
+            '@' => RegexSwitch::parse(reader, syntax).map(Self::RegexSwitch),
+            _ => unreachable!("Filter '{}' should accept a regex", filter),
+        }
+    }
+
     pub fn eval(&self, mut value: String, context: &eval::Context) -> eval::BaseResult<String> {
         match self {
             Self::WorkingDir => path::to_string(context.working_dir),
@@ -153,6 +221,19 @@ impl Filter {
             Self::ExtensionWithDot => path::get_extension_with_dot(&value),
             Self::EnsureTrailDirSeparator => Ok(path::ensure_trailing_dir_separator(value)),
             Self::RemoveTrailDirSeparator => Ok(path::remove_trailing_dir_separator(value)),
+            Self::Metadata(attribute) => {
+                attribute.get(context.file_path(&value), context.working_dir)
+            }
+            Self::ExifTag(tag) => tag.get(
+                context.file_path(&value),
+                context.working_dir,
+                &context.exif_cache,
+            ),
+            Self::AudioTag(tag) => tag.get(
+                context.file_path(&value),
+                context.working_dir,
+                &context.audio_cache,
+            ),
             Self::Substring(range) => Ok(range.substr(value)),
             Self::SubstringRev(range) => Ok(range.substr_rev(value)),
             Self::GetField(field) => Ok(field.get(&value).to_string()),
@@ -165,22 +246,46 @@ impl Filter {
             Self::RegexReplaceFirst(substitution) => Ok(substitution.replace_first(&value)),
             Self::RegexReplaceAll(substitution) => Ok(substitution.replace_all(&value)),
             Self::RegexSwitch(switch) => Ok(switch.eval(&value).to_string()),
+            Self::Lookup(lookup) => lookup.eval(value),
+            Self::Conditional(_) => {
+                unreachable!("Conditionals should be resolved during pattern evaluation")
+            }
+            Self::Template(_) => {
+                unreachable!("Templates should be resolved during pattern evaluation")
+            }
             Self::RegexCapture(number) => Ok(context.regex_capture(*number).to_string()),
+            Self::Variable(_) => {
+                unreachable!("Variables should be resolved during pattern evaluation")
+            }
             Self::Trim => Ok(value.trim().to_string()),
             Self::ToLowercase => Ok(value.to_lowercase()),
             Self::ToUppercase => Ok(value.to_uppercase()),
+            Self::ToCase(style) => Ok(style.apply(&value)),
             Self::ToAscii => Ok(unidecode(&value)),
             Self::RemoveNonAscii => {
                 value.retain(|ch| ch.is_ascii());
                 Ok(value)
             }
+            Self::Normalize(normalization) => Ok(normalization.apply(&value)),
+            Self::RemoveDiacritics => Ok(remove_diacritics(&value)),
+            Self::Sanitize(profile) => Ok(profile.apply(&value)),
             Self::LeftPad(padding) => Ok(padding.apply_left(value)),
             Self::RightPad(padding) => Ok(padding.apply_right(value)),
             Self::Repeat(repetition) => Ok(repetition.expand(&value)),
+            Self::Arithmetic(arithmetic) => arithmetic.eval(&value, context),
+            Self::FormatNumber(format) => format.eval(&value),
             Self::LocalCounter => Ok(context.local_counter.to_string()),
             Self::GlobalCounter => Ok(context.global_counter.to_string()),
             Self::RandomNumber(range) => Ok(range.random().to_string()),
             Self::RandomUuid => Ok(random_uuid()),
+            Self::FormatDate(format) if format.reads_file() => {
+                format.eval(context.file_path(&value), context.working_dir)
+            }
+            Self::FormatDate(format) => format.eval(&value, context.working_dir),
+            Self::Hash(hash) if hash.reads_file() => {
+                hash.eval(context.file_path(&value), context.working_dir)
+            }
+            Self::Hash(hash) => hash.eval(&value, context.working_dir),
         }
     }
 }
@@ -207,6 +312,9 @@ impl fmt::Display for Filter {
             Self::RemoveTrailDirSeparator => {
                 write!(formatter, "Remove trailing directory separator")
             }
+            Self::Metadata(attribute) => write!(formatter, "File {}", attribute),
+            Self::ExifTag(tag) => write!(formatter, "Image {}", tag),
+            Self::AudioTag(tag) => write!(formatter, "Audio {}", tag),
             Self::Substring(range) => write!(formatter, "Substring from {}", range),
             Self::SubstringRev(range) => {
                 write!(formatter, "Substring from {} backward", range)
@@ -218,23 +326,43 @@ impl fmt::Display for Filter {
             Self::ReplaceEmpty(substitution) => {
                 write!(formatter, "Replace {}", substitution)
             }
-            Self::RegexMatch(matcher) => write!(formatter, "Regular expression match {}", matcher),
-            Self::RegexMatchRev(matcher) => {
-                write!(formatter, "Regular expression backward match {}", matcher)
+            Self::RegexMatch(matcher) => {
+                write!(
+                    formatter,
+                    "{} match {}",
+                    matcher.regex.syntax().title(),
+                    matcher
+                )
             }
+            Self::RegexMatchRev(matcher) => write!(
+                formatter,
+                "{} backward match {}",
+                matcher.regex.syntax().title(),
+                matcher
+            ),
             Self::RegexReplaceFirst(substitution) => write!(
                 formatter,
-                "Replace first match of regular expression {}",
+                "Replace first match of {} {}",
+                substitution.target.syntax(),
                 substitution
             ),
             Self::RegexReplaceAll(substitution) => write!(
                 formatter,
-                "Replace all matches of regular expression {}",
+                "Replace all matches of {} {}",
+                substitution.target.syntax(),
                 substitution
             ),
             Self::RegexSwitch(switch) => {
-                write!(formatter, "Regular expression switch with {}", switch)
+                write!(
+                    formatter,
+                    "{} switch with {}",
+                    switch.syntax().title(),
+                    switch
+                )
             }
+            Self::Lookup(lookup) => write!(formatter, "{}", lookup),
+            Self::Conditional(conditional) => write!(formatter, "{}", conditional),
+            Self::Template(template) => write!(formatter, "{}", template),
             Self::RegexCapture(number) => {
                 write!(
                     formatter,
@@ -242,18 +370,27 @@ impl fmt::Display for Filter {
                     number
                 )
             }
+            Self::Variable(name) => write!(formatter, "Variable '{}'", name),
             Self::Trim => write!(formatter, "Trim"),
             Self::ToLowercase => write!(formatter, "To lowercase"),
             Self::ToUppercase => write!(formatter, "To uppercase"),
+            Self::ToCase(style) => write!(formatter, "To {}", style),
             Self::ToAscii => write!(formatter, "To ASCII"),
             Self::RemoveNonAscii => write!(formatter, "Remove non-ASCII"),
+            Self::Normalize(normalization) => write!(formatter, "Normalize to {}", normalization),
+            Self::RemoveDiacritics => write!(formatter, "Remove diacritics"),
+            Self::Sanitize(profile) => write!(formatter, "Sanitize ({} profile)", profile),
             Self::LeftPad(padding) => write!(formatter, "Left pad with {}", padding),
             Self::RightPad(padding) => write!(formatter, "Right pad with {}", padding),
             Self::Repeat(repetition) => write!(formatter, "Repeat {}", repetition),
+            Self::Arithmetic(arithmetic) => write!(formatter, "{}", arithmetic),
+            Self::FormatNumber(format) => write!(formatter, "Format number {}", format),
             Self::LocalCounter => write!(formatter, "Local counter"),
             Self::GlobalCounter => write!(formatter, "Global counter"),
             Self::RandomNumber(interval) => write!(formatter, "Random number from {}", interval),
             Self::RandomUuid => write!(formatter, "Random UUID"),
+            Self::FormatDate(format) => write!(formatter, "Format {}", format),
+            Self::Hash(hash) => write!(formatter, "{}", hash),
         }
     }
 }
@@ -265,16 +402,29 @@ mod tests {
     use test_case::test_case;
 
     use super::Filter;
+    use crate::pattern::arithmetic::{Arithmetic, Numeric, Operand, Operator};
+    use crate::pattern::audio::AudioTag;
+    use crate::pattern::case::CaseStyle;
+    use crate::pattern::conditional::{Condition, Conditional, Predicate};
+    use crate::pattern::date::{DateFormat, DateSource};
     use crate::pattern::error::ErrorRange;
+    use crate::pattern::exif::ExifTag;
     use crate::pattern::field::Field;
+    use crate::pattern::glob::GlobHolder;
+    use crate::pattern::hash::{Hash, HashAlgorithm, HashSource};
+    use crate::pattern::lookup::Lookup;
+    use crate::pattern::metadata::Attribute;
+    use crate::pattern::normalize::Normalization;
     use crate::pattern::number::NumberRange;
+    use crate::pattern::number_format::NumberFormat;
     use crate::pattern::padding::Padding;
-    use crate::pattern::parse::Separator;
+    use crate::pattern::parse::{Parsed, Separator};
     use crate::pattern::regex::{RegexMatcher, RegexRange};
     use crate::pattern::repeat::Repetition;
     use crate::pattern::replace::{
         EmptySubstitution, RegexSubstitution, StringSubstitution, Substitution,
     };
+    use crate::pattern::sanitize::Profile;
     use crate::pattern::substr::CharIndexRange;
     use crate::pattern::switch::{Case, RegexSwitch};
     use crate::pattern::utils::{AnyString, Empty};
@@ -292,6 +442,15 @@ mod tests {
 
         #[test_case("",         0..0, E::ExpectedFilter                              ; "empty")]
         #[test_case("-",        0..1, E::UnknownFilter('-'.into())                   ; "unknown")]
+        #[test_case("m",        1..1, E::ExpectedMetadataAttribute(None)             ; "metadata expected attribute")]
+        #[test_case("mx",       1..2, E::ExpectedMetadataAttribute(Some('x'.into())) ; "metadata invalid attribute")]
+        #[test_case("x",        1..1, E::ExpectedExifTag                             ; "exif expected tag")]
+        #[test_case("g",        1..1, E::ExpectedAudioTag(None)                      ; "audio expected tag")]
+        #[test_case("gx",       1..2, E::ExpectedAudioTag(Some('x'.into()))          ; "audio invalid tag")]
+        #[test_case("G",        1..1, E::ExpectedGlobFilter(None)                    ; "glob expected filter")]
+        #[test_case("Gx",       1..2, E::ExpectedGlobFilter(Some('x'.into()))        ; "glob invalid filter")]
+        #[test_case("~",        1..1, E::ExpectedCaseStyle(None)                     ; "case expected style")]
+        #[test_case("~x",       1..2, E::ExpectedCaseStyle(Some('x'.into()))         ; "case invalid style")]
         #[test_case("#",        1..1, E::ExpectedRange                               ; "substring expected range")]
         #[test_case("#-",       2..2, E::ExpectedRange                               ; "substring rev expected range")]
         #[test_case("&",        1..1, E::ExpectedNumber                              ; "field expected number")]
@@ -311,9 +470,22 @@ mod tests {
         #[test_case("S",        1..1, E::ExpectedSubstitution                        ; "regex replace all expected substitution")]
         #[test_case("S/[0/",    2..4, E::RegexInvalid(AnyString::any())              ; "regex replace all invalid regex")]
         #[test_case("@:[0:X:Y", 2..4, E::RegexInvalid(AnyString::any())              ; "regex switch invalid regex")]
+        #[test_case("l",        1..1, E::ExpectedLookupTable                         ; "lookup expected table")]
+        #[test_case("l:a.txt",  2..7, E::LookupTableFormatUnknown("a.txt".into())    ; "lookup table format unknown")]
+        #[test_case("q",        1..1, E::ExpectedConditional                         ; "conditional expected conditional")]
+        #[test_case("q:x",      2..3, E::ExpectedPredicate(Some('x'.into()))         ; "conditional invalid predicate")]
         #[test_case("$",        1..1, E::ExpectedNumber                              ; "regex capture expected number")]
+        #[test_case("n",        1..1, E::ExpectedNormalizationForm(None)             ; "normalize expected form")]
+        #[test_case("!",        1..1, E::ExpectedSanitizeProfile(None)               ; "sanitize expected profile")]
         #[test_case("<x",       1..2, E::PaddingPrefixInvalid('<', Some('x'.into())) ; "padding left prefix invalid")]
         #[test_case(">y",       1..2, E::PaddingPrefixInvalid('>', Some('y'.into())) ; "padding right prefix invalid")]
+        #[test_case("o",        1..1, E::ExpectedOperator(None)                      ; "arithmetic expected operator")]
+        #[test_case("o+x",      2..3, E::NumberInvalid("x".into())                   ; "arithmetic invalid number")]
+        #[test_case("k",        1..1, E::ExpectedNumberFormat(None)                  ; "number format expected format")]
+        #[test_case("T",        1..1, E::ExpectedDateSource(None)                    ; "date expected source")]
+        #[test_case("Tn:%Q",    3..5, E::DateFormatInvalid("%Q".into())              ; "date invalid format")]
+        #[test_case("h",        1..1, E::ExpectedHashAlgorithm(None)                 ; "hash expected algorithm")]
+        #[test_case("Hm0",      2..3, E::HashLengthZero                              ; "hash zero length")]
         fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
             assert_eq!(
                 Filter::parse(&mut Reader::from(input), &Config::fixture()),
@@ -336,6 +508,13 @@ mod tests {
         #[test_case("E",            F::ExtensionWithDot                       ; "extension with dot")]
         #[test_case("z",            F::EnsureTrailDirSeparator                ; "ensure trail dir separator")]
         #[test_case("Z",            F::RemoveTrailDirSeparator                ; "remove trail dir separator")]
+        #[test_case("ms",           F::Metadata(Attribute::Size)              ; "metadata size")]
+        #[test_case("mS",           F::Metadata(Attribute::HumanSize)         ; "metadata human size")]
+        #[test_case("mm",           F::Metadata(Attribute::ModifiedTime)      ; "metadata modified time")]
+        #[test_case("mP",           F::Metadata(Attribute::PermissionsRwx)    ; "metadata permissions rwx")]
+        #[test_case("xModel",       F::ExifTag(ExifTag("Model".into()))       ; "exif tag")]
+        #[test_case("ga",           F::AudioTag(AudioTag::Artist)             ; "audio artist")]
+        #[test_case("gn",           F::AudioTag(AudioTag::TrackNumber)        ; "audio track number")]
         #[test_case("#2",           F::Substring(index_range_at())            ; "substring at")]
         #[test_case("#2-",          F::Substring(index_range_from())          ; "substring from")]
         #[test_case("#2-3",         F::Substring(index_range_between())       ; "substring between")]
@@ -366,25 +545,41 @@ mod tests {
         #[test_case("S/[0-9]+",     F::RegexReplaceAll(subst_regex_1())       ; "regex remove all")]
         #[test_case("S/[0-9]+/x",   F::RegexReplaceAll(subst_regex_2())       ; "regex replace all")]
         #[test_case("@:[0-9]+:X:Y", F::RegexSwitch(regex_switch())            ; "regex switch ")]
+        #[test_case("G=1:*_*",      F::RegexMatch(glob_matcher())             ; "glob match")]
+        #[test_case("Gs:*_*:$2_$1", F::RegexReplaceFirst(subst_glob())        ; "glob replace first")]
+        #[test_case("GS:*_*:$2_$1", F::RegexReplaceAll(subst_glob())          ; "glob replace all")]
+        #[test_case("G@:*.jpg:X:Y", F::RegexSwitch(glob_switch())             ; "glob switch ")]
+        #[test_case("q:!z:t",       F::Conditional(conditional())             ; "conditional ")]
         #[test_case("$0",           F::RegexCapture(0)                        ; "regex capture 0")]
         #[test_case("$10",          F::RegexCapture(10)                       ; "regex capture 10")]
+        #[test_case("$name_1",      F::Variable("name_1".into())              ; "variable")]
         #[test_case("t",            F::Trim                                   ; "trim")]
         #[test_case("v",            F::ToLowercase                            ; "to lowercase")]
         #[test_case("^",            F::ToUppercase                            ; "to uppercase")]
+        #[test_case("~n",           F::ToCase(CaseStyle::Snake)               ; "to case")]
         #[test_case("i",            F::ToAscii                                ; "to ascii")]
         #[test_case("I",            F::RemoveNonAscii                         ; "remove non-ascii")]
+        #[test_case("nkc",          F::Normalize(Normalization::Nfkc)         ; "normalize")]
+        #[test_case("N",            F::RemoveDiacritics                       ; "remove diacritics")]
+        #[test_case("!w",           F::Sanitize(Profile::Windows)             ; "sanitize")]
         #[test_case("<<abcd",       F::LeftPad(padding_fixed())               ; "left pad fixed")]
         #[test_case("<2:abc",       F::LeftPad(padding_repeated())            ; "left pad repeated")]
         #[test_case(">>abcd",       F::RightPad(padding_fixed())              ; "right pad fixed")]
         #[test_case(">2:abc",       F::RightPad(padding_repeated())           ; "right pad repeated")]
         #[test_case("*2",           F::Repeat(repetition_input())             ; "repetition input ")]
         #[test_case("*2:abc",       F::Repeat(repetition_value())             ; "repetition value ")]
+        #[test_case("o+100",        F::Arithmetic(arithmetic_add())           ; "arithmetic")]
+        #[test_case("k04",          F::FormatNumber(NumberFormat::ZeroPad(4)) ; "number format")]
         #[test_case("c",            F::LocalCounter                           ; "local counter")]
         #[test_case("C",            F::GlobalCounter                          ; "global counter")]
         #[test_case("u",            F::RandomNumber(number_range_full())      ; "random number")]
         #[test_case("u2-",          F::RandomNumber(number_range_from())      ; "random number from")]
         #[test_case("u2-10",        F::RandomNumber(number_range_between())   ; "random number between")]
         #[test_case("U",            F::RandomUuid                             ; "random uuid")]
+        #[test_case("TN",           F::FormatDate(date_format_now())          ; "date now")]
+        #[test_case("TI:%F:%Y",     F::FormatDate(date_format_input())        ; "date input")]
+        #[test_case("hS8",          F::Hash(hash_input())                     ; "hash of input")]
+        #[test_case("Hm",           F::Hash(hash_file())                      ; "hash of file")]
         fn ok(input: &str, filter: Filter) {
             assert_eq!(
                 Filter::parse(&mut Reader::from(input), &Config::fixture()),
@@ -409,7 +604,15 @@ mod tests {
         use crate::pattern::eval::{Context, ErrorKind};
         use crate::pattern::uuid::assert_uuid;
 
-        #[test_case("non-existent", F::CanonicalPath, ErrorKind::CanonicalizationFailed(AnyString::any()) ; "canonicalization failed")]
+        #[test_case("non-existent", F::CanonicalPath,                   ErrorKind::CanonicalizationFailed(AnyString::any())                    ; "canonicalization failed")]
+        #[test_case("non-existent", F::Metadata(Attribute::Size),       ErrorKind::MetadataUnavailable(AnyString::any())                       ; "metadata unavailable")]
+        #[test_case("non-existent", F::ExifTag(ExifTag("Make".into())), ErrorKind::ExifUnavailable(AnyString::any())                           ; "exif unavailable")]
+        #[test_case("non-existent", F::AudioTag(AudioTag::Title),       ErrorKind::AudioTagsUnavailable(AnyString::any())                      ; "audio tags unavailable")]
+        #[test_case("03.02.2021",   F::FormatDate(date_format_input()), ErrorKind::DateParseFailed("input contains invalid characters".into()) ; "date parse failed")]
+        #[test_case("non-existent", F::Hash(hash_file()),               ErrorKind::FileReadFailed(AnyString::any())                            ; "file read failed")]
+        #[test_case("abc",          F::Arithmetic(arithmetic_add()),    ErrorKind::InputNotNumber                                              ; "input not number")]
+        #[test_case("1.5",          F::FormatNumber(number_hex()),      ErrorKind::InputNotInteger                                             ; "input not integer")]
+        #[test_case("c",            F::Lookup(lookup_table()),          ErrorKind::LookupKeyMissing("c".into())                                ; "lookup key missing")]
         fn err(input: &str, filter: Filter, kind: ErrorKind) {
             assert_eq!(filter.eval(input.into(), &Context::fixture()), Err(kind))
         }
@@ -464,22 +667,37 @@ mod tests {
         #[test_case("12_34",         F::RegexReplaceAll(subst_regex_2()),       "x_x"      ; "regex replace all")]
         #[test_case("1",             F::RegexSwitch(regex_switch()),            "X"        ; "regex switch case")]
         #[test_case("a",             F::RegexSwitch(regex_switch()),            "Y"        ; "regex switch default")]
+        #[test_case("ab_cd",         F::RegexMatch(glob_matcher()),             "ab_cd"    ; "glob match")]
+        #[test_case("ab.cd",         F::RegexMatch(glob_matcher()),             ""         ; "glob match none")]
+        #[test_case("ab_cd",         F::RegexReplaceFirst(subst_glob()),        "cd_ab"    ; "glob replace first")]
+        #[test_case("ab.cd",         F::RegexReplaceAll(subst_glob()),          "ab.cd"    ; "glob replace all none")]
+        #[test_case("a.jpg",         F::RegexSwitch(glob_switch()),             "X"        ; "glob switch case")]
+        #[test_case("a.jpg.gz",      F::RegexSwitch(glob_switch()),             "Y"        ; "glob switch default")]
+        #[test_case("a",             F::Lookup(lookup_table()),                 "b"        ; "lookup")]
         #[test_case("",              F::RegexCapture(1),                        "a"        ; "regex capture")]
         #[test_case(" abcd ",        F::Trim,                                   "abcd"     ; "trim")]
         #[test_case("ábčdÁBČD",      F::ToLowercase,                            "ábčdábčd" ; "to lowercase")]
         #[test_case("ábčdÁBČD",      F::ToUppercase,                            "ÁBČDÁBČD" ; "to uppercase")]
+        #[test_case("fooBar",        F::ToCase(CaseStyle::Kebab),               "foo-bar"  ; "to case")]
         #[test_case("ábčdÁBČD",      F::ToAscii,                                "abcdABCD" ; "to ascii")]
         #[test_case("ábčdÁBČD",      F::RemoveNonAscii,                         "bdBD"     ; "remove non-ascii")]
+        #[test_case("e\u{301}",      F::Normalize(Normalization::Nfc),          "\u{e9}"   ; "normalize")]
+        #[test_case("ábčdÁBČD",      F::RemoveDiacritics,                       "abcdABCD" ; "remove diacritics")]
+        #[test_case("a:b?",          F::Sanitize(Profile::Windows),             "a_b_"     ; "sanitize")]
         #[test_case("01",            F::LeftPad(padding_fixed()),               "ab01"     ; "left pad fixed")]
         #[test_case("01",            F::LeftPad(padding_repeated()),            "abca01"   ; "left pad repeated")]
         #[test_case("01",            F::RightPad(padding_fixed()),              "01cd"     ; "right pad fixed")]
         #[test_case("01",            F::RightPad(padding_repeated()),           "01cabc"   ; "right pad repeated")]
         #[test_case("01",            F::Repeat(repetition_input()),             "0101"     ; "repetition input ")]
         #[test_case("01",            F::Repeat(repetition_value()),             "abcabc"   ; "repetition value ")]
+        #[test_case("0042",          F::Arithmetic(arithmetic_add()),           "0142"     ; "arithmetic")]
+        #[test_case("1234",          F::FormatNumber(NumberFormat::Group(',')), "1,234"    ; "number format")]
         #[test_case("",              F::LocalCounter,                           "1"        ; "local counter")]
         #[test_case("",              F::GlobalCounter,                          "2"        ; "global counter")]
         #[test_case("",              F::RandomNumber(number_range_zero()),      "0"        ; "random number")]
         #[test_case("",              F::RandomUuid,                             ""         ; "random uuid")]
+        #[test_case("2021-02-03",    F::FormatDate(date_format_input()),        "2021"     ; "date input")]
+        #[test_case("abc",           F::Hash(hash_input()),                     "ba7816bf" ; "hash of input")]
         fn ok(input: &str, filter: Filter, output: &str) {
             match filter {
                 Filter::CanonicalPath => {
@@ -520,6 +738,10 @@ mod tests {
     #[test_case(F::ExtensionWithDot,                       "Extension with dot"                  ; "extension with dot")]
     #[test_case(F::EnsureTrailDirSeparator,                "Ensure trailing directory separator" ; "ensure trail dir separator")]
     #[test_case(F::RemoveTrailDirSeparator,                "Remove trailing directory separator" ; "remove trail dir separator")]
+    #[test_case(F::Metadata(Attribute::Size),              "File size in bytes"                  ; "metadata size")]
+    #[test_case(F::Metadata(Attribute::Permissions),       "File permissions in octal form"      ; "metadata permissions")]
+    #[test_case(F::ExifTag(ExifTag("Model".into())),       "Image EXIF tag 'Model'"              ; "exif tag")]
+    #[test_case(F::AudioTag(AudioTag::TrackNumber),        "Audio track number"                  ; "audio track number")]
     #[test_case(F::Substring(index_range_at()),            "Substring from 2..2"                 ; "substring at")]
     #[test_case(F::Substring(index_range_from()),          "Substring from 2.."                  ; "substring from")]
     #[test_case(F::Substring(index_range_between()),       "Substring from 2..3"                 ; "substring between")]
@@ -547,29 +769,52 @@ mod tests {
     #[test_case(F::RegexReplaceFirst(subst_regex_2()),     "Replace first match of regular expression '[0-9]+' with 'x'"   ; "regex replace first")]
     #[test_case(F::RegexReplaceAll(subst_regex_1()),       "Replace all matches of regular expression '[0-9]+' with ''"    ; "regex remove all")]
     #[test_case(F::RegexReplaceAll(subst_regex_2()),       "Replace all matches of regular expression '[0-9]+' with 'x'"   ; "regex replace all")]
+    #[test_case(F::RegexMatch(glob_matcher()),             "Glob match #1 of '*_*'"                                        ; "glob match")]
+    #[test_case(F::RegexReplaceFirst(subst_glob()),        "Replace first match of glob '*_*' with '$2_$1'"                ; "glob replace first")]
+    #[test_case(F::Lookup(lookup_table()),                 "Lookup in table 'table.csv'"                                   ; "lookup")]
     #[test_case(
         F::RegexSwitch(regex_switch()),
         "Regular expression switch with variable output:\n\n    if input matches '[0-9]+'\n        output is 'X'\n    else\n        output is 'Y'";
         "regex switch "
     )]
+    #[test_case(
+        F::RegexSwitch(glob_switch()),
+        "Glob switch with variable output:\n\n    if input matches '*.jpg'\n        output is 'X'\n    else\n        output is 'Y'";
+        "glob switch "
+    )]
+    #[test_case(
+        F::Conditional(conditional()),
+        "Conditional evaluation:\n\n    if input is not empty\n        Trim\n    else\n        keep input";
+        "conditional "
+    )]
     #[test_case(F::RegexCapture(1),                      "Capture group #1 of a global regular expression" ; "regex capture")]
+    #[test_case(F::Variable("name".into()),              "Variable 'name'"                                 ; "variable")]
     #[test_case(F::Trim,                                 "Trim"                                            ; "trim")]
     #[test_case(F::ToLowercase,                          "To lowercase"                                    ; "to lowercase")]
     #[test_case(F::ToUppercase,                          "To uppercase"                                    ; "to uppercase")]
+    #[test_case(F::ToCase(CaseStyle::Camel),             "To camelCase"                                    ; "to case")]
     #[test_case(F::ToAscii,                              "To ASCII"                                        ; "to ascii")]
     #[test_case(F::RemoveNonAscii,                       "Remove non-ASCII"                                ; "remove non-ascii")]
+    #[test_case(F::Normalize(Normalization::Nfd),        "Normalize to NFD"                                ; "normalize")]
+    #[test_case(F::RemoveDiacritics,                     "Remove diacritics"                               ; "remove diacritics")]
+    #[test_case(F::Sanitize(Profile::Slug),              "Sanitize (URL slug profile)"                     ; "sanitize")]
     #[test_case(F::LeftPad(padding_fixed()),             "Left pad with 'abcd'"                            ; "left pad fixed")]
     #[test_case(F::LeftPad(padding_repeated()),          "Left pad with 2x 'abc'"                          ; "left pad repeated")]
     #[test_case(F::RightPad(padding_fixed()),            "Right pad with 'abcd'"                           ; "right pad fixed")]
     #[test_case(F::RightPad(padding_repeated()),         "Right pad with 2x 'abc'"                         ; "right pad repeated")]
     #[test_case(F::Repeat(repetition_input()),           "Repeat 2x"                                       ; "repetition input ")]
     #[test_case(F::Repeat(repetition_value()),           "Repeat 2x 'abc'"                                 ; "repetition value ")]
+    #[test_case(F::Arithmetic(arithmetic_add()),         "Add 100"                                         ; "arithmetic")]
+    #[test_case(F::FormatNumber(NumberFormat::Ordinal),  "Format number as ordinal"                        ; "number format")]
     #[test_case(F::LocalCounter,                         "Local counter"                                   ; "local counter")]
     #[test_case(F::GlobalCounter,                        "Global counter"                                  ; "global counter")]
     #[test_case(F::RandomNumber(number_range_full()),    "Random number from [0, 2^64)"                    ; "random number")]
     #[test_case(F::RandomNumber(number_range_from()),    "Random number from [2, 2^64)"                    ; "random number from")]
     #[test_case(F::RandomNumber(number_range_between()), "Random number from [2, 10]"                      ; "random number between")]
     #[test_case(F::RandomUuid,                           "Random UUID"                                     ; "random uuid")]
+    #[test_case(F::FormatDate(date_format_now()),        "Format current time as RFC 3339 in UTC"          ; "date now")]
+    #[test_case(F::FormatDate(date_format_input()),      "Format input date in '%F' as '%Y' in UTC"        ; "date input")]
+    #[test_case(F::Hash(hash_input()),                   "SHA-256 hash of input (first 8 characters)"      ; "hash of input")]
     fn display(filter: Filter, result: &str) {
         assert_eq!(filter.to_string(), result);
     }
@@ -667,6 +912,38 @@ mod tests {
         }
     }
 
+    fn subst_glob() -> RegexSubstitution {
+        Substitution {
+            target: GlobHolder::from("*_*").into(),
+            replacement: "$2_$1".into(),
+        }
+    }
+
+    fn glob_matcher() -> RegexMatcher {
+        RegexMatcher {
+            regex: GlobHolder::from("*_*").into(),
+            range: RegexRange::new(0, Some(1)),
+        }
+    }
+
+    fn glob_switch() -> RegexSwitch {
+        RegexSwitch {
+            cases: vec![Case {
+                matcher: GlobHolder::from("*.jpg").into(),
+                result: "X".into(),
+            }],
+            default: "Y".into(),
+        }
+    }
+
+    fn lookup_table() -> Lookup {
+        Lookup {
+            path: "table.csv".into(),
+            table: vec![("a".into(), "b".into())].into_iter().collect(),
+            default: None,
+        }
+    }
+
     fn regex_switch() -> RegexSwitch {
         RegexSwitch {
             cases: vec![Case {
@@ -677,6 +954,20 @@ mod tests {
         }
     }
 
+    fn conditional() -> Conditional {
+        Conditional {
+            condition: Condition {
+                predicate: Predicate::Empty,
+                negated: true,
+            },
+            then: vec![Parsed {
+                value: F::Trim,
+                range: 5..6,
+            }],
+            otherwise: Vec::new(),
+        }
+    }
+
     fn padding_fixed() -> Padding {
         Padding::Fixed("abcd".into())
     }
@@ -699,6 +990,17 @@ mod tests {
         }
     }
 
+    fn arithmetic_add() -> Arithmetic {
+        Arithmetic {
+            operator: Operator::Add,
+            operand: Operand::Number(Numeric::Integer(100)),
+        }
+    }
+
+    fn number_hex() -> NumberFormat {
+        NumberFormat::LowerHex
+    }
+
     fn number_range_full() -> NumberRange {
         NumberRange::new(0, None)
     }
@@ -714,4 +1016,36 @@ mod tests {
     fn number_range_zero() -> NumberRange {
         NumberRange::new(0, Some(0))
     }
+
+    fn date_format_now() -> DateFormat {
+        DateFormat {
+            source: DateSource::Now,
+            utc: true,
+            format: None,
+        }
+    }
+
+    fn date_format_input() -> DateFormat {
+        DateFormat {
+            source: DateSource::Input(Some("%F".into())),
+            utc: true,
+            format: Some("%Y".into()),
+        }
+    }
+
+    fn hash_input() -> Hash {
+        Hash {
+            algorithm: HashAlgorithm::Sha256,
+            source: HashSource::Input,
+            length: Some(8),
+        }
+    }
+
+    fn hash_file() -> Hash {
+        Hash {
+            algorithm: HashAlgorithm::Md5,
+            source: HashSource::File,
+            length: None,
+        }
+    }
 }
@@ -0,0 +1,197 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::pattern::parse::ErrorKind;
+use crate::pattern::utils::AnyString;
+
+#[derive(Debug, Clone)]
+pub struct GlobHolder {
+    pub glob: String,
+    pub regex: Regex,
+}
+
+impl GlobHolder {
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+#[cfg(test)]
+impl From<&str> for GlobHolder {
+    fn from(value: &str) -> Self {
+        Self::try_from(value.to_string()).unwrap()
+    }
+}
+
+impl TryFrom<String> for GlobHolder {
+    type Error = ErrorKind;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match Regex::new(&glob_to_regex(&value)) {
+            Ok(regex) => Ok(Self { glob: value, regex }),
+            Err(error) => Err(ErrorKind::GlobInvalid(AnyString(error.to_string()))),
+        }
+    }
+}
+
+impl FromStr for GlobHolder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value.to_string()).map_err(|kind| kind.to_string())
+    }
+}
+
+impl PartialEq for GlobHolder {
+    fn eq(&self, other: &Self) -> bool {
+        self.glob == other.glob
+    }
+}
+
+impl fmt::Display for GlobHolder {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.glob.fmt(formatter)
+    }
+}
+
+// Every wildcard becomes a capture group, so their matches can be referenced later
+pub fn glob_to_regex(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut regex = String::from("^");
+    let mut index = 0;
+
+    while index < chars.len() {
+        match chars[index] {
+            '*' => {
+                while chars.get(index + 1) == Some(&'*') {
+                    index += 1;
+                }
+                regex.push_str("(.*)");
+            }
+            '?' => regex.push_str("(.)"),
+            '[' => match find_class_end(&chars, index) {
+                Some(end) => {
+                    regex.push_str(&class_to_regex(&chars[(index + 1)..end]));
+                    index = end;
+                }
+                None => regex.push_str(r"\["),
+            },
+            char => regex.push_str(&regex::escape(char.encode_utf8(&mut [0; 4]))),
+        }
+        index += 1;
+    }
+
+    regex.push('$');
+    regex
+}
+
+// Class with a single member matches the wildcard itself
+pub fn escape_glob_char(char: char) -> String {
+    match char {
+        '*' | '?' | '[' => format!("[{}]", char),
+        char => char.to_string(),
+    }
+}
+
+// Leading `]` (also after negation) is a member of the class
+fn find_class_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut index = start + 1;
+
+    if matches!(chars.get(index), Some('!' | '^')) {
+        index += 1;
+    }
+    if chars.get(index) == Some(&']') {
+        index += 1;
+    }
+
+    chars[index..]
+        .iter()
+        .position(|char| *char == ']')
+        .map(|position| index + position)
+}
+
+fn class_to_regex(members: &[char]) -> String {
+    let mut class = String::from("([");
+
+    for (index, char) in members.iter().enumerate() {
+        match char {
+            '!' | '^' if index == 0 => class.push('^'),
+            '\\' | '[' | ']' | '^' | '&' | '~' => {
+                class.push('\\');
+                class.push(*char);
+            }
+            _ => class.push(*char),
+        }
+    }
+
+    class.push_str("])");
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("",          "^$"                   ; "empty")]
+    #[test_case("abc",       "^abc$"                ; "constant")]
+    #[test_case("a.b+c",     r"^a\.b\+c$"           ; "constant special")]
+    #[test_case("*.jpg",     r"^(.*)\.jpg$"         ; "any")]
+    #[test_case("**/*",      "^(.*)/(.*)$"          ; "any repeated")]
+    #[test_case("a?c",       "^a(.)c$"              ; "single")]
+    #[test_case("[a-c]",     "^([a-c])$"            ; "class")]
+    #[test_case("[!a-c]",    "^([^a-c])$"           ; "class negated")]
+    #[test_case("[^a-c]",    "^([^a-c])$"           ; "class negated caret")]
+    #[test_case("[]a]",      r"^([\]a])$"           ; "class bracket")]
+    #[test_case("[a^[]",     r"^([a\^\[])$"         ; "class special")]
+    #[test_case("[abc",      r"^\[abc$"             ; "class unterminated")]
+    fn to_regex(glob: &str, regex: &str) {
+        assert_eq!(glob_to_regex(glob), regex);
+    }
+
+    #[test_case('a', "a"   ; "constant")]
+    #[test_case('*', "[*]" ; "any")]
+    #[test_case('?', "[?]" ; "single")]
+    #[test_case('[', "[[]" ; "class start")]
+    #[test_case(']', "]"   ; "class end")]
+    fn escape_char(char: char, result: &str) {
+        assert_eq!(escape_glob_char(char), result);
+        assert!(GlobHolder::try_from(result.to_string())
+            .unwrap()
+            .is_match(&char.to_string()));
+    }
+
+    #[test_case("*.jpg",   "image.jpg",  true  ; "any match")]
+    #[test_case("*.jpg",   "image.png",  false ; "any mismatch")]
+    #[test_case("*.jpg",   "a/b.jpg",    true  ; "any with separator")]
+    #[test_case("img_??",  "img_01",     true  ; "single match")]
+    #[test_case("img_??",  "img_1",      false ; "single mismatch")]
+    #[test_case("[!.]*",   ".hidden",    false ; "class mismatch")]
+    #[test_case("[!.]*",   "visible",    true  ; "class match")]
+    fn is_match(glob: &str, value: &str, result: bool) {
+        assert_eq!(GlobHolder::from(glob).is_match(value), result);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            GlobHolder::try_from("[z-a]".to_string()),
+            Err(ErrorKind::GlobInvalid(AnyString::any()))
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("*.jpg".parse(), Ok(GlobHolder::from("*.jpg")));
+        assert!("[z-a]".parse::<GlobHolder>().is_err());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(GlobHolder::from("*.jpg").to_string(), "*.jpg");
+    }
+}
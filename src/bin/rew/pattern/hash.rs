@@ -0,0 +1,330 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::eval::{BaseResult, ErrorKind};
+use crate::pattern::integer::parse_integer;
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+use crate::pattern::utils::AnyString;
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Blake3,
+    Crc32,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HashSource {
+    Input,
+    File,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Hash {
+    pub algorithm: HashAlgorithm,
+    pub source: HashSource,
+    pub length: Option<usize>, // Full hash when not specified
+}
+
+impl Hash {
+    pub fn parse(reader: &mut Reader<Char>, source: HashSource) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        let algorithm = match char.map(Char::as_char) {
+            Some('m') => HashAlgorithm::Md5,
+            Some('s') => HashAlgorithm::Sha1,
+            Some('S') => HashAlgorithm::Sha256,
+            Some('b') => HashAlgorithm::Blake3,
+            Some('c') => HashAlgorithm::Crc32,
+            _ => {
+                return Err(parse::Error {
+                    kind: parse::ErrorKind::ExpectedHashAlgorithm(char.cloned()),
+                    range: position..reader.position(),
+                })
+            }
+        };
+
+        let length = if let Some('0'..='9') = reader.peek_char() {
+            let position = reader.position();
+            let length = parse_integer(reader)?;
+
+            if length == 0 {
+                return Err(parse::Error {
+                    kind: parse::ErrorKind::HashLengthZero,
+                    range: position..reader.position(),
+                });
+            }
+
+            Some(length)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            algorithm,
+            source,
+            length,
+        })
+    }
+
+    pub fn reads_file(&self) -> bool {
+        self.source == HashSource::File
+    }
+
+    pub fn eval(&self, value: &str, working_dir: &Path) -> BaseResult<String> {
+        let mut hasher = Hasher::new(self.algorithm);
+
+        match self.source {
+            HashSource::Input => hasher.update(value.as_bytes()),
+            HashSource::File => hash_file(&mut hasher, &working_dir.join(value))
+                .map_err(|error| ErrorKind::FileReadFailed(AnyString(error.to_string())))?,
+        }
+
+        let mut result = hasher.finalize();
+
+        if let Some(length) = self.length {
+            result.truncate(length);
+        }
+
+        Ok(result)
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let algorithm = match self.algorithm {
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Sha1 => "SHA-1",
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Blake3 => "BLAKE3",
+            HashAlgorithm::Crc32 => "CRC32",
+        };
+        let source = match self.source {
+            HashSource::Input => "input",
+            HashSource::File => "file content",
+        };
+
+        write!(formatter, "{} hash of {}", algorithm, source)?;
+
+        match self.length {
+            Some(1) => write!(formatter, " (first character)"),
+            Some(length) => write!(formatter, " (first {} characters)", length),
+            None => Ok(()),
+        }
+    }
+}
+
+enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+    Crc32(crc32fast::Hasher),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => Self::Md5(Md5::new()),
+            HashAlgorithm::Sha1 => Self::Sha1(Sha1::new()),
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::Crc32 => Self::Crc32(crc32fast::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(hasher) => hasher.update(data),
+            Self::Sha1(hasher) => hasher.update(data),
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            Self::Crc32(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Self::Md5(hasher) => to_hex(&hasher.finalize()),
+            Self::Sha1(hasher) => to_hex(&hasher.finalize()),
+            Self::Sha256(hasher) => to_hex(&hasher.finalize()),
+            Self::Blake3(hasher) => to_hex(hasher.finalize().as_bytes()),
+            Self::Crc32(hasher) => to_hex(&hasher.finalize().to_be_bytes()),
+        }
+    }
+}
+
+// File is processed in chunks, so it does not have to fit into memory
+fn hash_file(hasher: &mut Hasher, path: &Path) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(size) => hasher.update(&buffer[..size]),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",    0..0, ErrorKind::ExpectedHashAlgorithm(None)             ; "missing algorithm")]
+        #[test_case("x",   0..1, ErrorKind::ExpectedHashAlgorithm(Some('x'.into())) ; "unknown algorithm")]
+        #[test_case("m0",  1..2, ErrorKind::HashLengthZero                          ; "zero length")]
+        #[test_case("m00", 1..3, ErrorKind::HashLengthZero                          ; "zeros length")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                Hash::parse(&mut Reader::from(input), HashSource::Input),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("m",   HashAlgorithm::Md5,    None,     1 ; "md5")]
+        #[test_case("s",   HashAlgorithm::Sha1,   None,     1 ; "sha1")]
+        #[test_case("S",   HashAlgorithm::Sha256, None,     1 ; "sha256")]
+        #[test_case("b",   HashAlgorithm::Blake3, None,     1 ; "blake3")]
+        #[test_case("c",   HashAlgorithm::Crc32,  None,     1 ; "crc32")]
+        #[test_case("S8",  HashAlgorithm::Sha256, Some(8),  2 ; "length")]
+        #[test_case("S12", HashAlgorithm::Sha256, Some(12), 3 ; "long length")]
+        #[test_case("mx",  HashAlgorithm::Md5,    None,     1 ; "chars after")]
+        fn ok(input: &str, algorithm: HashAlgorithm, length: Option<usize>, position: usize) {
+            let mut reader = Reader::from(input);
+            assert_eq!(
+                Hash::parse(&mut reader, HashSource::File),
+                Ok(Hash {
+                    algorithm,
+                    source: HashSource::File,
+                    length
+                })
+            );
+            assert_eq!(reader.position(), position);
+        }
+    }
+
+    mod eval {
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case(HashAlgorithm::Md5,    None,      "900150983cd24fb0d6963f7d28e17f72"                                 ; "md5")]
+        #[test_case(HashAlgorithm::Sha1,   None,      "a9993e364706816aba3e25717850c26c9cd0d89d"                         ; "sha1")]
+        #[test_case(HashAlgorithm::Sha256, None,      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad" ; "sha256")]
+        #[test_case(HashAlgorithm::Blake3, None,      "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85" ; "blake3")]
+        #[test_case(HashAlgorithm::Crc32,  None,      "352441c2"                                                         ; "crc32")]
+        #[test_case(HashAlgorithm::Sha256, Some(8),   "ba7816bf"                                                         ; "length")]
+        #[test_case(HashAlgorithm::Crc32,  Some(100), "352441c2"                                                         ; "length over")]
+        fn input(algorithm: HashAlgorithm, length: Option<usize>, result: &str) {
+            let hash = Hash {
+                algorithm,
+                source: HashSource::Input,
+                length,
+            };
+            assert_eq!(hash.eval("abc", Path::new("")), Ok(result.into()));
+        }
+
+        #[test]
+        fn file() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").write_str("abc").unwrap();
+
+            let hash = Hash {
+                algorithm: HashAlgorithm::Md5,
+                source: HashSource::File,
+                length: None,
+            };
+            assert_eq!(
+                hash.eval("file", dir.path()),
+                Ok("900150983cd24fb0d6963f7d28e17f72".into())
+            );
+        }
+
+        #[test]
+        fn large_file() {
+            let dir = TempDir::new().unwrap();
+            let data = vec![b'a'; BUFFER_SIZE * 2 + 1];
+            dir.child("file").write_binary(&data).unwrap();
+
+            let hash = Hash {
+                algorithm: HashAlgorithm::Sha256,
+                source: HashSource::File,
+                length: None,
+            };
+            let expected = to_hex(&Sha256::digest(&data));
+            assert_eq!(hash.eval("file", dir.path()), Ok(expected));
+        }
+
+        #[test]
+        fn missing_file() {
+            let dir = TempDir::new().unwrap();
+
+            let hash = Hash {
+                algorithm: HashAlgorithm::Md5,
+                source: HashSource::File,
+                length: None,
+            };
+            assert_eq!(
+                hash.eval("missing", dir.path()),
+                Err(ErrorKind::FileReadFailed(AnyString::any()))
+            );
+        }
+    }
+
+    #[test_case(HashSource::Input, false ; "input")]
+    #[test_case(HashSource::File,  true  ; "file")]
+    fn reads_file(source: HashSource, result: bool) {
+        let hash = Hash {
+            algorithm: HashAlgorithm::Md5,
+            source,
+            length: None,
+        };
+        assert_eq!(hash.reads_file(), result);
+    }
+
+    #[test_case(HashAlgorithm::Md5,    HashSource::Input, None,    "MD5 hash of input"                             ; "md5 input")]
+    #[test_case(HashAlgorithm::Sha256, HashSource::File,  None,    "SHA-256 hash of file content"                  ; "sha256 file")]
+    #[test_case(HashAlgorithm::Blake3, HashSource::File,  Some(1), "BLAKE3 hash of file content (first character)" ; "blake3 length 1")]
+    #[test_case(HashAlgorithm::Crc32,  HashSource::Input, Some(4), "CRC32 hash of input (first 4 characters)"      ; "crc32 length")]
+    fn display(algorithm: HashAlgorithm, source: HashSource, length: Option<usize>, result: &str) {
+        let hash = Hash {
+            algorithm,
+            source,
+            length,
+        };
+        assert_eq!(hash.to_string(), result);
+    }
+}
@@ -8,12 +8,17 @@ pub const PATTERN: &str = indoc! {r#"
   `{x}`         Expression with a filter
   `{x|y|z}`     Expression with multiple filters
   `a{}b{x|y}c`  Mixed constant and expresions.
+  `{x|y|as v}`  Expression bound to variable `v`
+  `{$v}`        Value of variable `v`
+  `{x:{y}}`     Filter argument with a nested expression
 
 # RULES
 
   1. Constants are directly copied to output.
   2. Expression is replaced by input value.
   3. Filters are consecutively applied on input value.
+  4. Variable must be bound before it is referenced.
+  5. Nested expression is evaluated before its filter.
 
 # ESCAPING
 
@@ -45,6 +50,61 @@ pub const FILTERS: &str = indoc! {r#"
   `z`  Ensure trailing directory separator
   `Z`  Remove trailing directory separator
 
+# METADATA
+
+  `ms`  Size in bytes           `mm`  Modification time
+  `mS`  Human-readable size     `mc`  Creation time
+  `mt`  Type                    `ma`  Access time
+
+  `mp`  Permissions in octal    `mu`  Owner
+  `mP`  Permissions as `rwx`      `mg`  Group
+  `mi`  Inode
+
+# EXIF
+
+  `xT`  Value of EXIF tag `T`     (e.g. `xModel`, `xDateTimeOriginal`)
+                              (empty when tag is missing)
+
+# AUDIO
+
+  `ga`  Artist                  `gn`  Track number
+  `gA`  Album artist            `gd`  Disc number
+  `gb`  Album                   `gy`  Year
+  `gt`  Title                   `gg`  Genre
+
+# DATE
+
+  `Tn:F`    Current time as `F`             (`:` = any delimiter char)
+  `Tm:F`    Modification time as `F`        (`F` = strftime format, `%%` for `%`)
+  `Tc:F`    Creation time as `F`            (`N`, `M`, `C`, `I` = UTC)
+  `Ti:P:F`  Input parsed with `P` as `F`      (omitted `F`, `P` = RFC 3339)
+
+# HASH
+
+  `hA`   Hash of input          (`A` = `m` MD5, `s` SHA-1, `S` SHA-256,
+  `HA`   Hash of file content       `b` BLAKE3, `c` CRC32)
+  `hAN`  First `N` chars of hash  (`N` = hex digits to keep)
+
+# ARITHMETIC
+
+  `o+X`   Add `X`                 (`X` = number, `c` local counter,
+  `o-X`   Subtract `X`                 `C` global counter)
+  `o*X`   Multiply by `X`         (`%` must be escaped as `%%`)
+  `o/X`   Divide by `X`
+  `o%%X`  Remainder after division by `X`
+
+# NUMBER FORMAT
+
+  `k0N`   Zero pad to width `N`   (sign stays in front of zeros)
+  `k,S`   Group thousands by `S`  (omitted `S` = `,`)
+  `kx`    To hexadecimal        (`kX` uppercase)
+  `ko`    To octal
+  `kb`    To binary
+  `kr`    To roman numeral      (`kR` uppercase, `1` to `3999`)
+  `kn`    To ordinal number     (`1st`, `2nd`, `3rd`, ...)
+
+  Use `c|kF` or `C|kF` to format a counter (e.g. `c|k03`).
+
 # SUBSTRING
 
   `#A-B`  From `A` to `B`         (`A`, `B` = inclusive 1-based index)
@@ -82,9 +142,30 @@ pub const FILTERS: &str = indoc! {r#"
 
   `@:X1:Y1:...:Xn:Yn:D`  Output `Yi` for first match of `Xi`, `D` for no match
 
+# GLOB MATCHING
+
+  `Gs:X:Y`  Glob `X` instead of regex in `s/S/=/@`    (`G` = glob prefix before filter char)
+  `G=1:X`   Input if it matches the whole glob `X`  (wildcards are captured as `$1`, `$2`, ...)
+
 # REGEX CAPTURES
 
-  `$0`, `$1`, `$2`, ...  Capture group of a global regex or `s/S/@` regex
+  `$0`, `$1`, `$2`, ...  Capture group of a global regex/glob or `s/S/@` regex/glob
+
+# CONDITIONAL
+
+  `q:P:T:E`  Apply `T` if `P` else `E`    (`:` = any delimiter char)
+  `q:P:T`    Apply `T` if `P`           (`%|` separates filters of `T`, `E`)
+
+  `z`   Empty               `gG`  Matches glob `G`
+  `e`   Existing path       `=N`  Equal to `N`
+  `f`   File                `<N`  Less than `N`
+  `d`   Directory           `>N`  Greater than `N`
+  `!P`  Negated `P`
+
+# LOOKUP
+
+  `l:F`    Value of input key from table file `F`  (`:` = any delimiter char)
+  `l:F:D`  `l:F` with default `D` for missing key  (`F` = `.csv`, `.tsv` or `.json` file)
 
 # FORMATTING
 
@@ -92,6 +173,14 @@ pub const FILTERS: &str = indoc! {r#"
   `^`  To uppercase    `i`   To ASCII
   `v`  To lowercase    `I`   Remove non-ASCII chars
 
+  `nc`  Normalize to NFC        (`nd` NFD, `nkc` NFKC, `nkd` NFKD)
+  `N`   Remove diacritics
+
+  `~S`  To case style `S`         (`t` Title, `s` Sentence, `c` camel, `p` Pascal,
+                               `n` snake, `N` SCREAMING_SNAKE, `k` kebab)
+  `!P`  Sanitize for profile `P`  (`u` POSIX, `w` Windows, `f` FAT32,
+                               `p` portable, `s` URL slug)
+
   `*N`    Repeat `N` times
   `<<M`   Left pad with `M`            (`>>` or `>` to right pad)
   `<N:M`  Left pad `N` times with `M`    (`:` = any delimiter char)
@@ -109,6 +198,8 @@ pub const FILTERS: &str = indoc! {r#"
 
 pub const REGEX_HINT: &str =
     "Visit `https://docs.rs/regex/1/regex/#syntax` for regular expression syntax.";
+pub const DATE_FORMAT_HINT: &str =
+    "Visit `https://docs.rs/chrono/0.4/chrono/format/strftime/` for date format syntax.";
 pub const PATTERN_HINT: &str = "Use `--help-pattern` flag to print pattern syntax reference.";
 pub const FILTERS_HINT: &str = "Use `--help-filters` flag to print filter reference.";
 
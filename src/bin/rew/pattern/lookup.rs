@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::pattern::char::Char;
+use crate::pattern::escape::escape_str;
+use crate::pattern::eval::{BaseResult, ErrorKind};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+use crate::pattern::utils::AnyString;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TableFormat {
+    Csv,
+    Tsv,
+    Json,
+}
+
+impl TableFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path
+            .extension()
+            .and_then(OsStr::to_str)
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("csv") => Some(Self::Csv),
+            Some("tsv") => Some(Self::Tsv),
+            Some("json") => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Lookup {
+    pub path: String,
+    pub table: HashMap<String, String>,
+    pub default: Option<String>, // Missing key is an error when not specified
+}
+
+impl Lookup {
+    // Table is loaded during parsing, so it is read only once for all input values
+    pub fn parse(reader: &mut Reader<Char>, working_dir: &Path) -> parse::Result<Self> {
+        let position = reader.position();
+
+        if let Some(delimiter) = reader.read().cloned() {
+            let path_start = reader.position();
+            let path = reader.read_until(&delimiter);
+            let path_end = path_start + path.len_utf8();
+            let path = path.to_string();
+
+            if path.is_empty() {
+                return Err(parse::Error {
+                    kind: parse::ErrorKind::ExpectedLookupTable,
+                    range: position..path_end,
+                });
+            }
+
+            let table = load_table(Path::new(&path), working_dir).map_err(|kind| parse::Error {
+                kind,
+                range: path_start..path_end,
+            })?;
+
+            let default = if reader.position() > path_end {
+                Some(reader.read_to_end().to_string())
+            } else {
+                None
+            };
+
+            Ok(Self {
+                path,
+                table,
+                default,
+            })
+        } else {
+            Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedLookupTable,
+                range: position..position,
+            })
+        }
+    }
+
+    pub fn eval(&self, value: String) -> BaseResult<String> {
+        match self.table.get(&value) {
+            Some(result) => Ok(result.clone()),
+            None => match &self.default {
+                Some(default) => Ok(default.clone()),
+                None => Err(ErrorKind::LookupKeyMissing(value)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Lookup {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Lookup in table '{}'", self.path)?;
+
+        if let Some(default) = &self.default {
+            write!(formatter, " with default '{}'", escape_str(default))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Relative path is resolved against working directory, the same way as paths of input values
+pub fn load_table(
+    path: &Path,
+    working_dir: &Path,
+) -> Result<HashMap<String, String>, parse::ErrorKind> {
+    let format = TableFormat::from_path(path)
+        .ok_or_else(|| parse::ErrorKind::LookupTableFormatUnknown(path.to_string_lossy().into()))?;
+
+    let content = fs::read_to_string(working_dir.join(path))
+        .map_err(|error| parse::ErrorKind::LookupTableInvalid(AnyString(error.to_string())))?;
+
+    // Spreadsheet applications often prepend BOM to exported files
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+
+    match format {
+        TableFormat::Csv => parse_csv(content).and_then(table_from_rows),
+        TableFormat::Tsv => table_from_rows(parse_tsv(content)),
+        TableFormat::Json => parse_json(content),
+    }
+    .map_err(|reason| parse::ErrorKind::LookupTableInvalid(AnyString(reason)))
+}
+
+fn table_from_rows(rows: Vec<Vec<String>>) -> Result<HashMap<String, String>, String> {
+    let mut table = HashMap::new();
+
+    for (index, mut row) in rows.into_iter().enumerate() {
+        match row.len() {
+            0 => {}
+            1 if row[0].is_empty() => {}
+            1 => return Err(format!("Row {} does not have a value column", index + 1)),
+            _ => {
+                let value = row.swap_remove(1);
+                let key = row.swap_remove(0);
+                table.insert(key, value);
+            }
+        }
+    }
+
+    Ok(table)
+}
+
+fn parse_tsv(content: &str) -> Vec<Vec<String>> {
+    content
+        .lines()
+        .map(|line| line.split('\t').map(String::from).collect())
+        .collect()
+}
+
+// RFC 4180: fields can be quoted, quote inside a quoted field is doubled
+fn parse_csv(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if quoted {
+            match char {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(char),
+            }
+        } else {
+            match char {
+                '"' if field.is_empty() => quoted = true,
+                ',' => row.push(std::mem::take(&mut field)),
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                _ => field.push(char),
+            }
+        }
+    }
+
+    if quoted {
+        return Err(format!(
+            "Row {} has unterminated quoted field",
+            rows.len() + 1
+        ));
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn parse_json(content: &str) -> Result<HashMap<String, String>, String> {
+    match serde_json::from_str(content) {
+        Ok(Value::Object(object)) => object
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(string) => Ok((key, string)),
+                Value::Number(number) => Ok((key, number.to_string())),
+                Value::Bool(bool) => Ok((key, bool.to_string())),
+                _ => Err(format!(
+                    "Value of key '{}' is not a string or a number",
+                    key
+                )),
+            })
+            .collect(),
+        Ok(_) => Err("Expected JSON object".into()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("a.csv",  Some(TableFormat::Csv)  ; "csv")]
+    #[test_case("a.TSV",  Some(TableFormat::Tsv)  ; "tsv uppercase")]
+    #[test_case("a.json", Some(TableFormat::Json) ; "json")]
+    #[test_case("a.txt",  None                    ; "unknown")]
+    #[test_case("a",      None                    ; "none")]
+    fn table_format(path: &str, result: Option<TableFormat>) {
+        assert_eq!(TableFormat::from_path(Path::new(path)), result);
+    }
+
+    mod parse {
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",       0..0, ErrorKind::ExpectedLookupTable                      ; "empty")]
+        #[test_case(":",      0..1, ErrorKind::ExpectedLookupTable                      ; "empty path")]
+        #[test_case("::x",    0..1, ErrorKind::ExpectedLookupTable                      ; "empty path with default")]
+        #[test_case(":a.txt", 1..6, ErrorKind::LookupTableFormatUnknown("a.txt".into()) ; "unknown format")]
+        #[test_case(":_.csv", 1..6, ErrorKind::LookupTableInvalid(AnyString::any())     ; "missing file")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                Lookup::parse(&mut Reader::from(input), Path::new("/work")),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("",     None        ; "no default")]
+        #[test_case(":",    Some("")    ; "empty default")]
+        #[test_case(":x",   Some("x")   ; "default")]
+        #[test_case(":x:y", Some("x:y") ; "default with delimiter")]
+        fn ok(suffix: &str, default: Option<&str>) {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("table.tsv");
+            file.write_str("a\tb\n").unwrap();
+
+            let input = format!(":table.tsv{}", suffix);
+
+            assert_eq!(
+                Lookup::parse(&mut Reader::from(input.as_str()), dir.path()),
+                Ok(Lookup {
+                    path: "table.tsv".into(),
+                    table: table(&[("a", "b")]),
+                    default: default.map(String::from),
+                })
+            );
+        }
+    }
+
+    mod load_table {
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::parse::ErrorKind;
+
+        #[test_case("t.csv",  "a,1\nb,2\n",                       &[("a", "1"), ("b", "2")]                ; "csv")]
+        #[test_case("t.csv",  "a,1\r\nb,2",                       &[("a", "1"), ("b", "2")]                ; "csv crlf no trailing newline")]
+        #[test_case("t.csv",  "\"a,b\",\"x \"\"y\"\"\"\n",        &[("a,b", "x \"y\"")]                    ; "csv quoted")]
+        #[test_case("t.csv",  "\"a\nb\",1\n",                     &[("a\nb", "1")]                         ; "csv quoted newline")]
+        #[test_case("t.csv",  "a,1,extra\n\nb,2\n",               &[("a", "1"), ("b", "2")]                ; "csv extra columns and empty row")]
+        #[test_case("t.csv",  "a,1\na,2\n",                       &[("a", "2")]                            ; "csv duplicate key")]
+        #[test_case("t.csv",  "\u{feff}a,1\n",                    &[("a", "1")]                            ; "csv bom")]
+        #[test_case("t.tsv",  "a\t1\nb,c\t2\n",                   &[("a", "1"), ("b,c", "2")]              ; "tsv")]
+        #[test_case("t.json", r#"{"a": "1", "b": 2, "c": true}"#, &[("a", "1"), ("b", "2"), ("c", "true")] ; "json")]
+        fn ok(name: &str, content: &str, result: &[(&str, &str)]) {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child(name);
+            file.write_str(content).unwrap();
+
+            assert_eq!(load_table(Path::new(name), dir.path()), Ok(table(result)));
+        }
+
+        #[test_case("t.csv",  "a,1\nb\n"       ; "csv missing value")]
+        #[test_case("t.csv",  "\"a,1\n"        ; "csv unterminated quote")]
+        #[test_case("t.tsv",  "a\n"            ; "tsv missing value")]
+        #[test_case("t.json", "[\"a\"]"        ; "json not object")]
+        #[test_case("t.json", r#"{"a": null}"# ; "json null value")]
+        #[test_case("t.json", "{"              ; "json invalid")]
+        fn err(name: &str, content: &str) {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child(name);
+            file.write_str(content).unwrap();
+
+            assert_eq!(
+                load_table(Path::new(name), dir.path()),
+                Err(ErrorKind::LookupTableInvalid(AnyString::any()))
+            );
+        }
+    }
+
+    #[test_case("a", None,      Ok("1".into())                               ; "found")]
+    #[test_case("a", Some("x"), Ok("1".into())                               ; "found with default")]
+    #[test_case("b", Some("x"), Ok("x".into())                               ; "default")]
+    #[test_case("b", None,      Err(ErrorKind::LookupKeyMissing("b".into())) ; "missing")]
+    fn eval(input: &str, default: Option<&str>, result: BaseResult<String>) {
+        let lookup = Lookup {
+            path: "t.csv".into(),
+            table: table(&[("a", "1")]),
+            default: default.map(String::from),
+        };
+        assert_eq!(lookup.eval(input.into()), result);
+    }
+
+    #[test_case(None,       "Lookup in table 't.csv'"                    ; "no default")]
+    #[test_case(Some("x\n"), "Lookup in table 't.csv' with default 'x\\n'" ; "default")]
+    fn display(default: Option<&str>, result: &str) {
+        let lookup = Lookup {
+            path: "t.csv".into(),
+            table: HashMap::new(),
+            default: default.map(String::from),
+        };
+        assert_eq!(lookup.to_string(), result);
+    }
+
+    fn table(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+}
@@ -0,0 +1,501 @@
+use std::fs::{self, Metadata};
+use std::path::Path;
+use std::time::SystemTime;
+use std::{fmt, io};
+
+use chrono::{DateTime, Local, SecondsFormat};
+use common::utils::format_bytes;
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::eval::{BaseResult, ErrorKind};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+use crate::pattern::utils::AnyString;
+
+#[derive(Debug, PartialEq)]
+pub enum Attribute {
+    Size,
+    HumanSize,
+    ModifiedTime,
+    CreatedTime,
+    AccessedTime,
+    Permissions,
+    PermissionsRwx,
+    Owner,
+    Group,
+    Inode,
+    FileType,
+}
+
+impl Attribute {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        match char.map(Char::as_char) {
+            Some('s') => Ok(Self::Size),
+            Some('S') => Ok(Self::HumanSize),
+            Some('m') => Ok(Self::ModifiedTime),
+            Some('c') => Ok(Self::CreatedTime),
+            Some('a') => Ok(Self::AccessedTime),
+            Some('p') => Ok(Self::Permissions),
+            Some('P') => Ok(Self::PermissionsRwx),
+            Some('u') => Ok(Self::Owner),
+            Some('g') => Ok(Self::Group),
+            Some('i') => Ok(Self::Inode),
+            Some('t') => Ok(Self::FileType),
+            _ => Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedMetadataAttribute(char.cloned()),
+                range: position..reader.position(),
+            }),
+        }
+    }
+
+    pub fn get(&self, value: &str, working_dir: &Path) -> BaseResult<String> {
+        let path = working_dir.join(value);
+
+        if *self == Self::FileType {
+            // The only attribute describing symlink itself rather than its target
+            return fs::symlink_metadata(&path)
+                .map(|metadata| get_file_type(&metadata).into())
+                .map_err(unavailable);
+        }
+
+        let metadata = fs::metadata(&path).map_err(unavailable)?;
+
+        match self {
+            Self::Size => Ok(metadata.len().to_string()),
+            Self::HumanSize => Ok(format_bytes(metadata.len())),
+            Self::ModifiedTime => metadata.modified().map(format_time).map_err(unavailable),
+            Self::CreatedTime => metadata.created().map(format_time).map_err(unavailable),
+            Self::AccessedTime => metadata.accessed().map(format_time).map_err(unavailable),
+            Self::Permissions => Ok(format!("{:o}", get_mode(&metadata))),
+            Self::PermissionsRwx => Ok(format_mode(get_mode(&metadata))),
+            Self::Owner => get_owner(&metadata),
+            Self::Group => get_group(&metadata),
+            Self::Inode => get_inode(&metadata),
+            Self::FileType => unreachable!("File type should be already handled"),
+        }
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Size => write!(formatter, "size in bytes"),
+            Self::HumanSize => write!(formatter, "human-readable size"),
+            Self::ModifiedTime => write!(formatter, "modification time"),
+            Self::CreatedTime => write!(formatter, "creation time"),
+            Self::AccessedTime => write!(formatter, "access time"),
+            Self::Permissions => write!(formatter, "permissions in octal form"),
+            Self::PermissionsRwx => write!(formatter, "permissions in rwx form"),
+            Self::Owner => write!(formatter, "owner"),
+            Self::Group => write!(formatter, "group"),
+            Self::Inode => write!(formatter, "inode"),
+            Self::FileType => write!(formatter, "type"),
+        }
+    }
+}
+
+fn unavailable(error: io::Error) -> ErrorKind {
+    ErrorKind::MetadataUnavailable(AnyString(error.to_string()))
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time).to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+fn get_file_type(metadata: &Metadata) -> &'static str {
+    let file_type = metadata.file_type();
+
+    if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_dir() {
+        "dir"
+    } else if file_type.is_file() {
+        "file"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(unix)]
+fn get_mode(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn get_mode(metadata: &Metadata) -> u32 {
+    if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    }
+}
+
+fn format_mode(mode: u32) -> String {
+    let mut result = String::with_capacity(9);
+
+    for (shift, special, special_char) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = mode >> shift;
+        let executable = bits & 0o1 != 0;
+
+        result.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        result.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        result.push(match (mode & special != 0, executable) {
+            (true, true) => special_char,
+            (true, false) => special_char.to_ascii_uppercase(),
+            (false, true) => 'x',
+            (false, false) => '-',
+        });
+    }
+
+    result
+}
+
+#[cfg(unix)]
+fn get_owner(metadata: &Metadata) -> BaseResult<String> {
+    use std::os::unix::fs::MetadataExt;
+    let uid = metadata.uid();
+    Ok(unix::user_name(uid).unwrap_or_else(|| uid.to_string())) // Same fallback as `ls -l`
+}
+
+#[cfg(unix)]
+fn get_group(metadata: &Metadata) -> BaseResult<String> {
+    use std::os::unix::fs::MetadataExt;
+    let gid = metadata.gid();
+    Ok(unix::group_name(gid).unwrap_or_else(|| gid.to_string()))
+}
+
+#[cfg(unix)]
+fn get_inode(metadata: &Metadata) -> BaseResult<String> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(metadata.ino().to_string())
+}
+
+#[cfg(not(unix))]
+fn get_owner(_: &Metadata) -> BaseResult<String> {
+    Err(not_supported())
+}
+
+#[cfg(not(unix))]
+fn get_group(_: &Metadata) -> BaseResult<String> {
+    Err(not_supported())
+}
+
+#[cfg(not(unix))]
+fn get_inode(_: &Metadata) -> BaseResult<String> {
+    Err(not_supported())
+}
+
+#[cfg(not(unix))]
+fn not_supported() -> ErrorKind {
+    ErrorKind::MetadataUnavailable(AnyString("Not supported on this platform".into()))
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::ffi::CStr;
+    use std::{mem, ptr};
+
+    const DEFAULT_BUFFER_SIZE: usize = 1024; // When the system does not suggest any
+    const MAX_BUFFER_SIZE: usize = 1024 * 1024;
+
+    pub fn user_name(uid: libc::uid_t) -> Option<String> {
+        lookup_name(libc::_SC_GETPW_R_SIZE_MAX, |buffer| {
+            let mut passwd: libc::passwd = unsafe { mem::zeroed() };
+            let mut result = ptr::null_mut();
+
+            // SAFETY: All pointers are valid for the whole call and the buffer length matches
+            let code = unsafe {
+                libc::getpwuid_r(
+                    uid,
+                    &mut passwd,
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    &mut result,
+                )
+            };
+
+            if code != 0 {
+                Err(code)
+            } else if result.is_null() {
+                Ok(None)
+            } else {
+                // SAFETY: Name points into the buffer which is still alive
+                Ok(Some(
+                    unsafe { CStr::from_ptr(passwd.pw_name) }
+                        .to_string_lossy()
+                        .into_owned(),
+                ))
+            }
+        })
+    }
+
+    pub fn group_name(gid: libc::gid_t) -> Option<String> {
+        lookup_name(libc::_SC_GETGR_R_SIZE_MAX, |buffer| {
+            let mut group: libc::group = unsafe { mem::zeroed() };
+            let mut result = ptr::null_mut();
+
+            // SAFETY: All pointers are valid for the whole call and the buffer length matches
+            let code = unsafe {
+                libc::getgrgid_r(
+                    gid,
+                    &mut group,
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    &mut result,
+                )
+            };
+
+            if code != 0 {
+                Err(code)
+            } else if result.is_null() {
+                Ok(None)
+            } else {
+                // SAFETY: Name points into the buffer which is still alive
+                Ok(Some(
+                    unsafe { CStr::from_ptr(group.gr_name) }
+                        .to_string_lossy()
+                        .into_owned(),
+                ))
+            }
+        })
+    }
+
+    // Entries with many members (e.g. large groups) do not fit the suggested size, so the buffer grows on ERANGE
+    pub fn lookup_name<F>(size_name: libc::c_int, mut lookup: F) -> Option<String>
+    where
+        F: FnMut(&mut [libc::c_char]) -> Result<Option<String>, libc::c_int>,
+    {
+        // SAFETY: Querying a configuration value has no side effects
+        let size = match unsafe { libc::sysconf(size_name) } {
+            size if size > 0 => size as usize,
+            _ => DEFAULT_BUFFER_SIZE,
+        };
+        let mut buffer = vec![0; size];
+
+        loop {
+            match lookup(&mut buffer) {
+                Err(libc::ERANGE) if buffer.len() < MAX_BUFFER_SIZE => {
+                    buffer.resize(buffer.len() * 2, 0);
+                }
+                Ok(name) => return name,
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",  0..0, ErrorKind::ExpectedMetadataAttribute(None)             ; "missing")]
+        #[test_case("x", 0..1, ErrorKind::ExpectedMetadataAttribute(Some('x'.into())) ; "unknown")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                Attribute::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("s",  Attribute::Size           ; "size")]
+        #[test_case("S",  Attribute::HumanSize      ; "human size")]
+        #[test_case("m",  Attribute::ModifiedTime   ; "modified time")]
+        #[test_case("c",  Attribute::CreatedTime    ; "created time")]
+        #[test_case("a",  Attribute::AccessedTime   ; "accessed time")]
+        #[test_case("p",  Attribute::Permissions    ; "permissions")]
+        #[test_case("P",  Attribute::PermissionsRwx ; "permissions rwx")]
+        #[test_case("u",  Attribute::Owner          ; "owner")]
+        #[test_case("g",  Attribute::Group          ; "group")]
+        #[test_case("i",  Attribute::Inode          ; "inode")]
+        #[test_case("tx", Attribute::FileType       ; "file type")]
+        fn ok(input: &str, attribute: Attribute) {
+            let mut reader = Reader::from(input);
+            assert_eq!(Attribute::parse(&mut reader), Ok(attribute));
+            assert_eq!(reader.position(), 1);
+        }
+    }
+
+    mod get {
+        use assert_fs::prelude::*;
+        use assert_fs::TempDir;
+        use filetime::{set_file_mtime, FileTime};
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case(Attribute::Size      ; "size")]
+        #[test_case(Attribute::Owner     ; "owner")]
+        #[test_case(Attribute::FileType  ; "file type")]
+        fn missing(attribute: Attribute) {
+            let dir = TempDir::new().unwrap();
+            assert_eq!(
+                attribute.get("missing", dir.path()),
+                Err(ErrorKind::MetadataUnavailable(AnyString::any()))
+            );
+        }
+
+        #[test]
+        fn size() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").write_binary(&[0; 1536]).unwrap();
+
+            assert_eq!(Attribute::Size.get("file", dir.path()), Ok("1536".into()));
+            assert_eq!(
+                Attribute::HumanSize.get("file", dir.path()),
+                Ok("1.5 KiB".into())
+            );
+        }
+
+        #[test]
+        fn modified_time() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            file.touch().unwrap();
+            set_file_mtime(&file, FileTime::from_unix_time(1_600_000_000, 0)).unwrap();
+
+            let expected = DateTime::<Local>::from(
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000),
+            );
+            let result = Attribute::ModifiedTime.get("file", dir.path()).unwrap();
+
+            assert_eq!(DateTime::parse_from_rfc3339(&result).unwrap(), expected);
+        }
+
+        #[test]
+        fn absolute_path() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").write_str("abc").unwrap();
+            let path = dir.join("file");
+
+            assert_eq!(
+                Attribute::Size.get(path.to_str().unwrap(), Path::new("/missing")),
+                Ok("3".into())
+            );
+        }
+
+        #[test]
+        fn file_type() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").touch().unwrap();
+            dir.child("dir").create_dir_all().unwrap();
+
+            assert_eq!(
+                Attribute::FileType.get("file", dir.path()),
+                Ok("file".into())
+            );
+            assert_eq!(Attribute::FileType.get("dir", dir.path()), Ok("dir".into()));
+            assert_eq!(Attribute::FileType.get("", dir.path()), Ok("dir".into()));
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn symlink() {
+            let dir = TempDir::new().unwrap();
+            dir.child("file").write_str("abc").unwrap();
+            dir.child("link").symlink_to_file(dir.join("file")).unwrap();
+
+            assert_eq!(
+                Attribute::FileType.get("link", dir.path()),
+                Ok("symlink".into())
+            );
+            assert_eq!(Attribute::Size.get("link", dir.path()), Ok("3".into()));
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn unix() {
+            use std::fs::Permissions;
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            file.touch().unwrap();
+            fs::set_permissions(&file, Permissions::from_mode(0o640)).unwrap();
+            let metadata = file.metadata().unwrap();
+
+            assert_eq!(
+                Attribute::Permissions.get("file", dir.path()),
+                Ok("640".into())
+            );
+            assert_eq!(
+                Attribute::PermissionsRwx.get("file", dir.path()),
+                Ok("rw-r-----".into())
+            );
+            assert_eq!(
+                Attribute::Inode.get("file", dir.path()),
+                Ok(metadata.ino().to_string())
+            );
+            assert!(!Attribute::Owner.get("file", dir.path()).unwrap().is_empty());
+            assert!(!Attribute::Group.get("file", dir.path()).unwrap().is_empty());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lookup_name_grows_buffer() {
+        let mut sizes = Vec::new();
+        let name = unix::lookup_name(libc::_SC_GETPW_R_SIZE_MAX, |buffer| {
+            sizes.push(buffer.len());
+            if buffer.len() < 100_000 {
+                Err(libc::ERANGE)
+            } else {
+                Ok(Some("name".into()))
+            }
+        });
+
+        assert_eq!(name, Some("name".into()));
+        assert!(sizes.windows(2).all(|pair| pair[1] == pair[0] * 2));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lookup_name_gives_up() {
+        assert_eq!(
+            unix::lookup_name(libc::_SC_GETPW_R_SIZE_MAX, |_| Err(libc::ERANGE)),
+            None
+        );
+        assert_eq!(
+            unix::lookup_name(libc::_SC_GETPW_R_SIZE_MAX, |_| Err(libc::EIO)),
+            None
+        );
+    }
+
+    #[test_case(0o000,  "---------" ; "none")]
+    #[test_case(0o755,  "rwxr-xr-x" ; "executable")]
+    #[test_case(0o640,  "rw-r-----" ; "regular")]
+    #[test_case(0o4755, "rwsr-xr-x" ; "setuid")]
+    #[test_case(0o2644, "rw-r-Sr--" ; "setgid not executable")]
+    #[test_case(0o1777, "rwxrwxrwt" ; "sticky")]
+    #[test_case(0o1776, "rwxrwxrwT" ; "sticky not executable")]
+    fn format_mode(mode: u32, result: &str) {
+        assert_eq!(super::format_mode(mode), result);
+    }
+
+    #[test_case(Attribute::Size,           "size in bytes"             ; "size")]
+    #[test_case(Attribute::HumanSize,      "human-readable size"       ; "human size")]
+    #[test_case(Attribute::ModifiedTime,   "modification time"         ; "modified time")]
+    #[test_case(Attribute::CreatedTime,    "creation time"             ; "created time")]
+    #[test_case(Attribute::AccessedTime,   "access time"               ; "accessed time")]
+    #[test_case(Attribute::Permissions,    "permissions in octal form" ; "permissions")]
+    #[test_case(Attribute::PermissionsRwx, "permissions in rwx form"   ; "permissions rwx")]
+    #[test_case(Attribute::Owner,          "owner"                     ; "owner")]
+    #[test_case(Attribute::Group,          "group"                     ; "group")]
+    #[test_case(Attribute::Inode,          "inode"                     ; "inode")]
+    #[test_case(Attribute::FileType,       "type"                      ; "file type")]
+    fn display(attribute: Attribute, result: &str) {
+        assert_eq!(attribute.to_string(), result);
+    }
+}
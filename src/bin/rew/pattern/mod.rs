@@ -1,18 +1,34 @@
+use std::collections::HashMap;
+
+use crate::pattern::arithmetic::Operand;
 use crate::pattern::filter::Filter;
-use crate::pattern::parser::{Item, ParsedItem, Parser};
+use crate::pattern::parse::Parsed;
+use crate::pattern::parser::{Item, ParsedFilter, ParsedItem, Parser};
 
+mod arithmetic;
+mod audio;
+mod case;
 mod char;
+mod conditional;
+mod date;
 pub mod error;
 mod escape;
 pub mod eval;
+mod exif;
 mod explain;
 mod field;
 pub mod filter;
+pub mod glob;
+mod hash;
 pub mod help;
 mod index;
 mod integer;
 mod lexer;
+mod lookup;
+mod metadata;
+pub mod normalize;
 mod number;
+mod number_format;
 mod padding;
 pub mod parse;
 mod parser;
@@ -22,11 +38,14 @@ mod reader;
 pub mod regex;
 mod repeat;
 mod replace;
+mod sanitize;
 mod substr;
 mod switch;
 pub mod symbols;
+mod template;
 mod utils;
 mod uuid;
+mod variable;
 
 #[derive(Debug, PartialEq)]
 pub struct Pattern {
@@ -43,11 +62,19 @@ impl Pattern {
     }
 
     pub fn uses_local_counter(&self) -> bool {
-        self.uses_filter(|filter| *filter == Filter::LocalCounter)
+        self.uses_filter(|filter| match filter {
+            Filter::LocalCounter => true,
+            Filter::Arithmetic(arithmetic) => arithmetic.operand == Operand::LocalCounter,
+            _ => false,
+        })
     }
 
     pub fn uses_global_counter(&self) -> bool {
-        self.uses_filter(|filter| *filter == Filter::GlobalCounter)
+        self.uses_filter(|filter| match filter {
+            Filter::GlobalCounter => true,
+            Filter::Arithmetic(arithmetic) => arithmetic.operand == Operand::GlobalCounter,
+            _ => false,
+        })
     }
 
     pub fn uses_regex_capture(&self) -> bool {
@@ -55,53 +82,128 @@ impl Pattern {
     }
 
     fn uses_filter<F: Fn(&Filter) -> bool>(&self, test: F) -> bool {
-        self.items.iter().any(|item| {
-            if let Item::Expression(filters) = &item.value {
-                filters.iter().any(|filter| test(&filter.value))
-            } else {
-                false
-            }
+        self.items.iter().any(|item| match &item.value {
+            Item::Expression(filters) | Item::Binding(filters, _) => any_filter(filters, &test),
+            Item::Constant(_) => false,
         })
     }
 
-    pub fn eval(&self, input: &str, context: &eval::Context) -> eval::Result<String> {
+    pub fn eval(&self, input: &str, context: &eval::Context) -> eval::Result<'_, String> {
         let mut output = String::new();
+        let mut variables = HashMap::new();
 
         for item in &self.items {
-            match &item.value {
-                Item::Constant(value) => output.push_str(value),
+            let value = match &item.value {
+                Item::Constant(value) => {
+                    output.push_str(value);
+                    continue;
+                }
                 Item::Expression(filters) => {
-                    let mut value = input.to_string();
-
-                    for filter in filters.iter() {
-                        match filter.value.eval(value, context) {
-                            Ok(result) => value = result,
-                            Err(kind) => {
-                                return Err(eval::Error {
-                                    kind,
-                                    value: input.to_string(),
-                                    cause: &filter.value,
-                                    range: &filter.range,
-                                });
-                            }
-                        }
+                    Self::eval_filters(filters, input, input.into(), context, &variables)?
+                }
+                Item::Binding(filters, binding) => {
+                    let value =
+                        Self::eval_filters(filters, input, input.into(), context, &variables)?;
+                    variables.insert(binding.value.0.as_str(), value.clone());
+                    value
+                }
+            };
+
+            if let Some(quotes) = context.expression_quotes {
+                output.push(quotes);
+                output.push_str(&value);
+                output.push(quotes);
+            } else {
+                output.push_str(&value);
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn eval_filters<'a>(
+        filters: &'a [ParsedFilter],
+        input: &str,
+        mut value: String,
+        context: &eval::Context,
+        variables: &HashMap<&str, String>,
+    ) -> eval::Result<'a, String> {
+        for filter in filters.iter() {
+            let result = match &filter.value {
+                // Parser checks references, only an expanded template can reference undefined variable
+                Filter::Variable(name) => match variables.get(name.as_str()) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(eval::ErrorKind::VariableUndefined(name.clone())),
+                },
+                Filter::Conditional(conditional) => match conditional.branch(&value, context) {
+                    // Errors of branch filters are reported with their own cause and range
+                    Ok(branch) => Ok(Self::eval_filters(
+                        branch, input, value, context, variables,
+                    )?),
+                    Err(kind) => Err(kind),
+                },
+                Filter::Template(template) => {
+                    let mut values = Vec::new();
+
+                    for expression in template.expressions() {
+                        let filters = &expression.value.0;
+                        values.push(Self::eval_filters(
+                            filters,
+                            input,
+                            input.into(),
+                            context,
+                            variables,
+                        )?);
                     }
 
-                    if let Some(quotes) = context.expression_quotes {
-                        output.push(quotes);
-                        output.push_str(&value);
-                        output.push(quotes);
-                    } else {
-                        output.push_str(&value);
+                    match template.expand(&values) {
+                        // Expanded filter is temporary, its errors are reported with the template
+                        Ok(expanded) => {
+                            let expanded = [Parsed {
+                                value: expanded,
+                                range: filter.range.clone(),
+                            }];
+                            Self::eval_filters(&expanded, input, value, context, variables)
+                                .map_err(|error| error.kind)
+                        }
+                        Err(kind) => Err(eval::ErrorKind::ExpandedFilterInvalid(kind)),
                     }
                 }
+                filter => filter.eval(value, context),
+            };
+            match result {
+                Ok(result) => value = result,
+                Err(kind) => {
+                    return Err(eval::Error {
+                        kind,
+                        value: input.to_string(),
+                        cause: &filter.value,
+                        range: &filter.range,
+                    });
+                }
             }
         }
 
-        Ok(output)
+        Ok(value)
     }
 }
 
+fn any_filter<'a, I, F>(filters: I, test: &F) -> bool
+where
+    I: IntoIterator<Item = &'a ParsedFilter>,
+    F: Fn(&Filter) -> bool,
+{
+    filters.into_iter().any(|filter| match &filter.value {
+        Filter::Conditional(conditional) => {
+            test(&filter.value) || any_filter(conditional.filters(), test)
+        }
+        Filter::Template(template) => template
+            .expressions()
+            .any(|expression| any_filter(&expression.value.0, test)),
+        value => test(value),
+    })
+}
+
 #[cfg(test)]
 impl From<Vec<ParsedItem>> for Pattern {
     fn from(items: Vec<ParsedItem>) -> Self {
@@ -116,14 +218,17 @@ impl From<Vec<ParsedItem>> for Pattern {
 mod tests {
     use test_case::test_case;
 
+    use super::arithmetic::{Arithmetic, Operand, Operator};
+    use super::conditional::{Condition, Conditional, Predicate};
     use super::filter::Filter;
-    use super::parse::Parsed;
-    use super::parser::Item;
+    use super::parse::{Config, Parsed};
+    use super::parser::{Item, ParsedFilter};
+    use super::variable::Binding;
     use super::Pattern;
     use crate::pattern::utils::AnyString;
 
     mod parse {
-        use super::super::parse::{Config, Error, ErrorKind, Parsed};
+        use super::super::parse::{Error, ErrorKind};
         use super::*;
 
         #[test]
@@ -171,10 +276,12 @@ mod tests {
         }
     }
 
-    #[test_case(Filter::FileName,        false, false, false ; "none")]
-    #[test_case(Filter::LocalCounter,    true,  false, false ; "local counter")]
-    #[test_case(Filter::GlobalCounter,   false, true,  false ; "global counter")]
-    #[test_case(Filter::RegexCapture(1), false, false, true  ; "regex capture")]
+    #[test_case(Filter::FileName,                                       false, false, false ; "none")]
+    #[test_case(Filter::LocalCounter,                                   true,  false, false ; "local counter")]
+    #[test_case(Filter::GlobalCounter,                                  false, true,  false ; "global counter")]
+    #[test_case(Filter::RegexCapture(1),                                false, false, true  ; "regex capture")]
+    #[test_case(Filter::Arithmetic(arithmetic(Operand::LocalCounter)),  true,  false, false ; "arithmetic local counter")]
+    #[test_case(Filter::Arithmetic(arithmetic(Operand::GlobalCounter)), false, true,  false ; "arithmetic global counter")]
     fn uses(filter: Filter, local_counter: bool, global_counter: bool, regex_capture: bool) {
         let pattern = Pattern::from(vec![
             Parsed::from(Item::Constant("a".into())),
@@ -185,11 +292,55 @@ mod tests {
         assert_eq!(pattern.uses_regex_capture(), regex_capture);
     }
 
+    #[test]
+    fn uses_in_binding() {
+        let pattern = Pattern::from(vec![Parsed::from(Item::Binding(
+            vec![Parsed::from(Filter::LocalCounter)],
+            Parsed::from(Binding("x".into())),
+        ))]);
+        assert!(pattern.uses_local_counter());
+    }
+
+    #[test]
+    fn uses_in_conditional() {
+        let pattern = Pattern::from(vec![Parsed::from(Item::Expression(vec![Parsed::from(
+            Filter::Conditional(conditional(vec![Parsed::from(Filter::GlobalCounter)])),
+        )]))]);
+        assert!(pattern.uses_global_counter());
+    }
+
+    #[test]
+    fn uses_in_template() {
+        let pattern = Pattern::parse("{r:_:{c}}", &Config::fixture()).unwrap();
+        assert!(pattern.uses_local_counter());
+    }
+
+    fn conditional(then: Vec<ParsedFilter>) -> Conditional {
+        Conditional {
+            condition: Condition {
+                predicate: Predicate::Empty,
+                negated: true,
+            },
+            then,
+            otherwise: Vec::new(),
+        }
+    }
+
+    fn arithmetic(operand: Operand) -> Arithmetic {
+        Arithmetic {
+            operator: Operator::Add,
+            operand,
+        }
+    }
+
     mod eval {
         use test_case::test_case;
 
         use super::super::eval::{Context, Error, ErrorKind};
         use super::*;
+        use crate::pattern::char::Char;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::ErrorKind as E;
         use crate::pattern::parser::ParsedItem;
 
         #[test]
@@ -209,12 +360,51 @@ mod tests {
             );
         }
 
-        #[test_case("",    constant(),      None, "abc"                 ; "constant ")]
-        #[test_case("a/b", empty_expr(),    None, "a/b"                 ; "empty expression")]
-        #[test_case("a/b", single_filter(), None, "b"                   ; "single filter ")]
-        #[test_case("a/b", multi_filter(),  None, "B"                   ; "multi filter ")]
-        #[test_case("a/b", complex_expr(),  None, "1 a 2 B 3"           ; "complex expression")]
-        #[test_case("a/b", complex_expr(),  Some('\''), "1 'a' 2 'B' 3" ; "quoted complex expression")]
+        #[test]
+        fn err_in_conditional() {
+            let pattern = Pattern::from(vec![Parsed::from(Item::Expression(vec![Parsed {
+                value: Filter::Conditional(conditional(vec![Parsed {
+                    value: Filter::CanonicalPath,
+                    range: 5..6,
+                }])),
+                range: 1..6,
+            }]))]);
+            assert_eq!(
+                pattern.eval("dir/file.ext", &Context::fixture()),
+                Err(Error {
+                    kind: ErrorKind::CanonicalizationFailed(AnyString::any()),
+                    value: "dir/file.ext".into(),
+                    cause: &Filter::CanonicalPath,
+                    range: &(5..6usize),
+                })
+            );
+        }
+
+        #[test_case("{r{f}}",  "a", 1..5, ErrorKind::ExpandedFilterInvalid(E::SubstitutionWithoutTarget(Char::Literal('a'))) ; "expanded filter invalid")]
+        #[test_case("{${f}}",  "x", 1..5, ErrorKind::VariableUndefined("x".into())                                           ; "expanded variable undefined")]
+        #[test_case("{r:{P}}", "x", 4..5, ErrorKind::CanonicalizationFailed(AnyString::any())                                ; "nested expression failed")]
+        fn err_in_template(source: &str, input: &str, range: ErrorRange, kind: ErrorKind) {
+            let pattern = Pattern::parse(source, &Config::fixture()).unwrap();
+            assert_eq!(
+                pattern
+                    .eval(input, &Context::fixture())
+                    .map_err(|error| (error.kind, error.range.clone())),
+                Err((kind, range))
+            );
+        }
+
+        #[test_case("",    constant(),         None,       "abc"           ; "constant ")]
+        #[test_case("a/b", empty_expr(),       None,       "a/b"           ; "empty expression")]
+        #[test_case("a/b", single_filter(),    None,       "b"             ; "single filter ")]
+        #[test_case("a/b", multi_filter(),     None,       "B"             ; "multi filter ")]
+        #[test_case("a/b", complex_expr(),     None,       "1 a 2 B 3"     ; "complex expression")]
+        #[test_case("a/b", complex_expr(),     Some('\''), "1 'a' 2 'B' 3" ; "quoted complex expression")]
+        #[test_case("a/b", variable_expr(),    None,       "B-b"           ; "variable expression")]
+        #[test_case("a/b", variable_expr(),    Some('\''), "'B'-'b'"       ; "quoted variable expression")]
+        #[test_case("a/b", conditional_expr(), None,       "B"             ; "conditional then")]
+        #[test_case("",    conditional_expr(), None,       ""              ; "conditional otherwise")]
+        #[test_case("a/b", template_expr(),    None,       "A"             ; "template ")]
+        #[test_case("a/b", template_expr(),    Some('\''), "'A'"           ; "quoted template")]
         fn ok(input: &str, items: Vec<ParsedItem>, quotes: Option<char>, output: &str) {
             let pattern = Pattern::from(items);
             let mut context = Context::fixture();
@@ -257,5 +447,37 @@ mod tests {
                 Parsed::from(Item::Constant(" 3".into())),
             ]
         }
+
+        fn conditional_expr() -> Vec<ParsedItem> {
+            vec![Parsed::from(Item::Expression(vec![Parsed::from(
+                Filter::Conditional(conditional(vec![
+                    Parsed::from(Filter::FileName),
+                    Parsed::from(Filter::ToUppercase),
+                ])),
+            )]))]
+        }
+
+        fn template_expr() -> Vec<ParsedItem> {
+            Pattern::parse("{f|r:b:{d|^}}", &Config::fixture())
+                .unwrap()
+                .items
+        }
+
+        fn variable_expr() -> Vec<ParsedItem> {
+            vec![
+                Parsed::from(Item::Binding(
+                    vec![
+                        Parsed::from(Filter::FileName),
+                        Parsed::from(Filter::ToUppercase),
+                    ],
+                    Parsed::from(Binding("x".into())),
+                )),
+                Parsed::from(Item::Constant("-".into())),
+                Parsed::from(Item::Expression(vec![
+                    Parsed::from(Filter::Variable("x".into())),
+                    Parsed::from(Filter::ToLowercase),
+                ])),
+            ]
+        }
     }
 }
@@ -0,0 +1,152 @@
+use std::fmt;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+
+pub const NORMALIZATION_FORMS: &[&str] = &[NFC, NFD, NFKC, NFKD];
+
+const NFC: &str = "nfc";
+const NFD: &str = "nfd";
+const NFKC: &str = "nfkc";
+const NFKD: &str = "nfkd";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Normalization {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl Normalization {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let compatibility = reader.read_expected('k');
+        let position = reader.position();
+        let char = reader.read();
+
+        match (char.map(Char::as_char), compatibility) {
+            (Some('c'), false) => Ok(Self::Nfc),
+            (Some('d'), false) => Ok(Self::Nfd),
+            (Some('c'), true) => Ok(Self::Nfkc),
+            (Some('d'), true) => Ok(Self::Nfkd),
+            _ => Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedNormalizationForm(char.cloned()),
+                range: position..reader.position(),
+            }),
+        }
+    }
+
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            Self::Nfc => value.nfc().collect(),
+            Self::Nfd => value.nfd().collect(),
+            Self::Nfkc => value.nfkc().collect(),
+            Self::Nfkd => value.nfkd().collect(),
+        }
+    }
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Nfc => write!(formatter, "NFC"),
+            Self::Nfd => write!(formatter, "NFD"),
+            Self::Nfkc => write!(formatter, "NFKC"),
+            Self::Nfkd => write!(formatter, "NFKD"),
+        }
+    }
+}
+
+pub fn parse_normalization(string: &str) -> Result<Normalization, &'static str> {
+    match string {
+        NFC => Ok(Normalization::Nfc),
+        NFD => Ok(Normalization::Nfd),
+        NFKC => Ok(Normalization::Nfkc),
+        NFKD => Ok(Normalization::Nfkd),
+        _ => Err("invalid value"),
+    }
+}
+
+// Unlike conversion to ASCII, letters without diacritics (e.g. 'ß', 'ø', 'я') are kept as they are
+pub fn remove_diacritics(value: &str) -> String {
+    value
+        .nfd()
+        .filter(|char| !is_combining_mark(*char))
+        .nfc()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",   0..0, ErrorKind::ExpectedNormalizationForm(None)             ; "none")]
+        #[test_case("x",  0..1, ErrorKind::ExpectedNormalizationForm(Some('x'.into())) ; "invalid")]
+        #[test_case("k",  1..1, ErrorKind::ExpectedNormalizationForm(None)             ; "compatibility none")]
+        #[test_case("kx", 1..2, ErrorKind::ExpectedNormalizationForm(Some('x'.into())) ; "compatibility invalid")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                Normalization::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("c",  Normalization::Nfc,  1 ; "nfc")]
+        #[test_case("d",  Normalization::Nfd,  1 ; "nfd")]
+        #[test_case("kc", Normalization::Nfkc, 2 ; "nfkc")]
+        #[test_case("kd", Normalization::Nfkd, 2 ; "nfkd")]
+        #[test_case("cx", Normalization::Nfc,  1 ; "chars after")]
+        fn ok(input: &str, normalization: Normalization, position: usize) {
+            let mut reader = Reader::from(input);
+            assert_eq!(Normalization::parse(&mut reader), Ok(normalization));
+            assert_eq!(reader.position(), position);
+        }
+    }
+
+    #[test_case(Normalization::Nfc,  "e\u{301}ﬁ", "\u{e9}ﬁ"    ; "nfc")]
+    #[test_case(Normalization::Nfd,  "\u{e9}ﬁ",   "e\u{301}ﬁ"  ; "nfd")]
+    #[test_case(Normalization::Nfkc, "e\u{301}ﬁ", "\u{e9}fi"   ; "nfkc")]
+    #[test_case(Normalization::Nfkd, "\u{e9}ﬁ",   "e\u{301}fi" ; "nfkd")]
+    fn apply(normalization: Normalization, input: &str, output: &str) {
+        assert_eq!(normalization.apply(input), output);
+    }
+
+    #[test_case(Normalization::Nfc,  "NFC"  ; "nfc")]
+    #[test_case(Normalization::Nfd,  "NFD"  ; "nfd")]
+    #[test_case(Normalization::Nfkc, "NFKC" ; "nfkc")]
+    #[test_case(Normalization::Nfkd, "NFKD" ; "nfkd")]
+    fn display(normalization: Normalization, result: &str) {
+        assert_eq!(normalization.to_string(), result);
+    }
+
+    #[test_case("nfc",  Ok(Normalization::Nfc)  ; "nfc")]
+    #[test_case("nfd",  Ok(Normalization::Nfd)  ; "nfd")]
+    #[test_case("nfkc", Ok(Normalization::Nfkc) ; "nfkc")]
+    #[test_case("nfkd", Ok(Normalization::Nfkd) ; "nfkd")]
+    #[test_case("NFC",  Err("invalid value")    ; "invalid")]
+    fn parse_normalization(input: &str, result: Result<Normalization, &'static str>) {
+        assert_eq!(super::parse_normalization(input), result);
+    }
+
+    #[test_case("",                            ""                 ; "empty")]
+    #[test_case("Příliš žluťoučký",            "Prilis zlutoucky" ; "composed")]
+    #[test_case("Pr\u{30c}i\u{301}lis\u{30c}", "Prilis"           ; "decomposed")]
+    #[test_case("Straße Ørsted",               "Straße Ørsted"    ; "no diacritics")]
+    #[test_case("Ελληνικά",                    "Ελληνικα"         ; "greek")]
+    fn remove_diacritics(input: &str, output: &str) {
+        assert_eq!(super::remove_diacritics(input), output);
+    }
+}
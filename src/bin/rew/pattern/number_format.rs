@@ -0,0 +1,278 @@
+use std::fmt;
+
+use crate::pattern::arithmetic::Numeric;
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::eval::{BaseResult, ErrorKind};
+use crate::pattern::integer::parse_integer;
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+
+const DEFAULT_GROUP_SEPARATOR: char = ',';
+const ROMAN_MAX: i64 = 3999;
+const ROMAN_NUMERALS: [(i64, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum NumberFormat {
+    ZeroPad(usize),
+    Group(char),
+    LowerHex,
+    UpperHex,
+    Octal,
+    Binary,
+    LowerRoman,
+    UpperRoman,
+    Ordinal,
+}
+
+impl NumberFormat {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        match char.map(Char::as_char) {
+            Some('0') => Ok(Self::ZeroPad(parse_integer(reader)?)),
+            Some(',') => Ok(Self::Group(
+                reader.read_char().unwrap_or(DEFAULT_GROUP_SEPARATOR),
+            )),
+            Some('x') => Ok(Self::LowerHex),
+            Some('X') => Ok(Self::UpperHex),
+            Some('o') => Ok(Self::Octal),
+            Some('b') => Ok(Self::Binary),
+            Some('r') => Ok(Self::LowerRoman),
+            Some('R') => Ok(Self::UpperRoman),
+            Some('n') => Ok(Self::Ordinal),
+            _ => Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedNumberFormat(char.cloned()),
+                range: position..reader.position(),
+            }),
+        }
+    }
+
+    pub fn eval(&self, value: &str) -> BaseResult<String> {
+        let number = Numeric::parse(value.trim()).ok_or(ErrorKind::InputNotNumber)?;
+
+        match self {
+            Self::ZeroPad(width) => Ok(zero_pad(&number.to_string(), *width)),
+            Self::Group(separator) => Ok(group_thousands(&number.to_string(), *separator)),
+            _ => match number {
+                Numeric::Integer(integer) => self.format_integer(integer),
+                Numeric::Decimal(_) => Err(ErrorKind::InputNotInteger),
+            },
+        }
+    }
+
+    fn format_integer(&self, integer: i64) -> BaseResult<String> {
+        let sign = if integer < 0 { "-" } else { "" };
+        let abs = integer.unsigned_abs();
+
+        match self {
+            Self::LowerHex => Ok(format!("{}{:x}", sign, abs)),
+            Self::UpperHex => Ok(format!("{}{:X}", sign, abs)),
+            Self::Octal => Ok(format!("{}{:o}", sign, abs)),
+            Self::Binary => Ok(format!("{}{:b}", sign, abs)),
+            Self::LowerRoman => Ok(to_roman(integer)?.to_lowercase()),
+            Self::UpperRoman => to_roman(integer),
+            Self::Ordinal => Ok(to_ordinal(integer)),
+            Self::ZeroPad(_) | Self::Group(_) => {
+                unreachable!("Number format {:?} is not integer specific", self)
+            }
+        }
+    }
+}
+
+impl fmt::Display for NumberFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ZeroPad(width) => write!(formatter, "with zero padding to width {}", width),
+            Self::Group(separator) => {
+                write!(formatter, "with '{}' thousands separator", separator)
+            }
+            Self::LowerHex => write!(formatter, "as lowercase hexadecimal"),
+            Self::UpperHex => write!(formatter, "as uppercase hexadecimal"),
+            Self::Octal => write!(formatter, "as octal"),
+            Self::Binary => write!(formatter, "as binary"),
+            Self::LowerRoman => write!(formatter, "as lowercase roman numeral"),
+            Self::UpperRoman => write!(formatter, "as uppercase roman numeral"),
+            Self::Ordinal => write!(formatter, "as ordinal"),
+        }
+    }
+}
+
+fn split_sign(number: &str) -> (&str, &str) {
+    match number.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", number),
+    }
+}
+
+// Sign is kept in front of the zeros and counts into the width
+fn zero_pad(number: &str, width: usize) -> String {
+    let (sign, digits) = split_sign(number);
+    let width = width.saturating_sub(sign.len());
+    format!("{}{:0>width$}", sign, digits, width = width)
+}
+
+// Only the integer part is grouped, decimal places are kept as they are
+fn group_thousands(number: &str, separator: char) -> String {
+    let (sign, digits) = split_sign(number);
+    let (integer, fraction) = match digits.find('.') {
+        Some(index) => digits.split_at(index),
+        None => (digits, ""),
+    };
+
+    let mut result = String::with_capacity(number.len() + integer.len() / 3);
+    result.push_str(sign);
+
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            result.push(separator);
+        }
+        result.push(digit);
+    }
+
+    result.push_str(fraction);
+    result
+}
+
+fn to_roman(mut number: i64) -> BaseResult<String> {
+    if !(1..=ROMAN_MAX).contains(&number) {
+        return Err(ErrorKind::RomanNumeralOutOfRange);
+    }
+
+    let mut result = String::new();
+
+    for (value, numeral) in ROMAN_NUMERALS.iter() {
+        while number >= *value {
+            result.push_str(numeral);
+            number -= value;
+        }
+    }
+
+    Ok(result)
+}
+
+fn to_ordinal(number: i64) -> String {
+    let abs = number.unsigned_abs();
+    let suffix = match (abs % 10, abs % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", number, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",   0..0, ErrorKind::ExpectedNumberFormat(None)             ; "none")]
+        #[test_case("y",  0..1, ErrorKind::ExpectedNumberFormat(Some('y'.into())) ; "invalid")]
+        #[test_case("0",  1..1, ErrorKind::ExpectedNumber                         ; "zero pad without width")]
+        #[test_case("0a", 1..2, ErrorKind::ExpectedNumber                         ; "zero pad invalid width")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                NumberFormat::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("05",  NumberFormat::ZeroPad(5),  2 ; "zero pad")]
+        #[test_case("012", NumberFormat::ZeroPad(12), 3 ; "zero pad long")]
+        #[test_case(",",   NumberFormat::Group(','),  1 ; "group")]
+        #[test_case(",_",  NumberFormat::Group('_'),  2 ; "group custom")]
+        #[test_case("x",   NumberFormat::LowerHex,    1 ; "lower hex")]
+        #[test_case("X",   NumberFormat::UpperHex,    1 ; "upper hex")]
+        #[test_case("o",   NumberFormat::Octal,       1 ; "octal")]
+        #[test_case("b",   NumberFormat::Binary,      1 ; "binary")]
+        #[test_case("r",   NumberFormat::LowerRoman,  1 ; "lower roman")]
+        #[test_case("R",   NumberFormat::UpperRoman,  1 ; "upper roman")]
+        #[test_case("n",   NumberFormat::Ordinal,     1 ; "ordinal")]
+        #[test_case("xy",  NumberFormat::LowerHex,    1 ; "chars after")]
+        fn ok(input: &str, format: NumberFormat, position: usize) {
+            let mut reader = Reader::from(input);
+            assert_eq!(NumberFormat::parse(&mut reader), Ok(format));
+            assert_eq!(reader.position(), position);
+        }
+    }
+
+    mod eval {
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case(NumberFormat::ZeroPad(4),   "42",       "0042"        ; "zero pad")]
+        #[test_case(NumberFormat::ZeroPad(4),   "-42",      "-042"        ; "zero pad negative")]
+        #[test_case(NumberFormat::ZeroPad(4),   "0001",     "0001"        ; "zero pad leading zeros")]
+        #[test_case(NumberFormat::ZeroPad(6),   "1.5",      "0001.5"      ; "zero pad decimal")]
+        #[test_case(NumberFormat::ZeroPad(2),   "12345",    "12345"       ; "zero pad over width")]
+        #[test_case(NumberFormat::Group(','),   "123",      "123"         ; "group short")]
+        #[test_case(NumberFormat::Group(','),   "1234567",  "1,234,567"   ; "group")]
+        #[test_case(NumberFormat::Group(' '),   "-123456",  "-123 456"    ; "group negative")]
+        #[test_case(NumberFormat::Group(' '),   "12345.75", "12 345.75"   ; "group decimal")]
+        #[test_case(NumberFormat::LowerHex,     "255",      "ff"          ; "lower hex")]
+        #[test_case(NumberFormat::UpperHex,     "-255",     "-FF"         ; "upper hex negative")]
+        #[test_case(NumberFormat::Octal,        "8",        "10"          ; "octal")]
+        #[test_case(NumberFormat::Binary,       " 5 ",      "101"         ; "binary trimmed")]
+        #[test_case(NumberFormat::LowerRoman,   "14",       "xiv"         ; "lower roman")]
+        #[test_case(NumberFormat::UpperRoman,   "1994",     "MCMXCIV"     ; "upper roman")]
+        #[test_case(NumberFormat::UpperRoman,   "3999",     "MMMCMXCIX"   ; "upper roman max")]
+        #[test_case(NumberFormat::Ordinal,      "1",        "1st"         ; "ordinal 1")]
+        #[test_case(NumberFormat::Ordinal,      "2",        "2nd"         ; "ordinal 2")]
+        #[test_case(NumberFormat::Ordinal,      "3",        "3rd"         ; "ordinal 3")]
+        #[test_case(NumberFormat::Ordinal,      "4",        "4th"         ; "ordinal 4")]
+        #[test_case(NumberFormat::Ordinal,      "11",       "11th"        ; "ordinal 11")]
+        #[test_case(NumberFormat::Ordinal,      "112",      "112th"       ; "ordinal 112")]
+        #[test_case(NumberFormat::Ordinal,      "121",      "121st"       ; "ordinal 121")]
+        #[test_case(NumberFormat::Ordinal,      "-22",      "-22nd"       ; "ordinal negative")]
+        fn ok(format: NumberFormat, input: &str, output: &str) {
+            assert_eq!(format.eval(input), Ok(output.into()));
+        }
+
+        #[test_case(NumberFormat::ZeroPad(4),  "abc",  ErrorKind::InputNotNumber         ; "not number")]
+        #[test_case(NumberFormat::LowerHex,    "1.5",  ErrorKind::InputNotInteger        ; "hex decimal")]
+        #[test_case(NumberFormat::Ordinal,     "1.5",  ErrorKind::InputNotInteger        ; "ordinal decimal")]
+        #[test_case(NumberFormat::UpperRoman,  "0",    ErrorKind::RomanNumeralOutOfRange ; "roman zero")]
+        #[test_case(NumberFormat::LowerRoman,  "4000", ErrorKind::RomanNumeralOutOfRange ; "roman over max")]
+        fn err(format: NumberFormat, input: &str, kind: ErrorKind) {
+            assert_eq!(format.eval(input), Err(kind));
+        }
+    }
+
+    #[test_case(NumberFormat::ZeroPad(4), "with zero padding to width 4" ; "zero pad")]
+    #[test_case(NumberFormat::Group(','), "with ',' thousands separator" ; "group")]
+    #[test_case(NumberFormat::LowerHex,   "as lowercase hexadecimal"     ; "lower hex")]
+    #[test_case(NumberFormat::UpperHex,   "as uppercase hexadecimal"     ; "upper hex")]
+    #[test_case(NumberFormat::Octal,      "as octal"                     ; "octal")]
+    #[test_case(NumberFormat::Binary,     "as binary"                    ; "binary")]
+    #[test_case(NumberFormat::LowerRoman, "as lowercase roman numeral"   ; "lower roman")]
+    #[test_case(NumberFormat::UpperRoman, "as uppercase roman numeral"   ; "upper roman")]
+    #[test_case(NumberFormat::Ordinal,    "as ordinal"                   ; "ordinal")]
+    fn display(format: NumberFormat, result: &str) {
+        assert_eq!(format.to_string(), result);
+    }
+}
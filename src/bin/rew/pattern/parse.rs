@@ -1,5 +1,6 @@
 use std::convert::Infallible;
 use std::ops::Range;
+use std::path::PathBuf;
 use std::{error, fmt, result};
 
 use crate::pattern::char::{Char, EscapeSequence};
@@ -9,9 +10,11 @@ use crate::pattern::regex::RegexHolder;
 use crate::pattern::symbols::{EXPR_END, EXPR_START, PIPE, RANGE_OF_LENGTH, RANGE_TO};
 use crate::pattern::utils::AnyString;
 
+#[derive(Debug, PartialEq, Clone)]
 pub struct Config {
     pub escape: char,
     pub separator: Separator,
+    pub working_dir: PathBuf,
 }
 
 #[cfg(test)]
@@ -20,6 +23,10 @@ impl Config {
         Self {
             escape: '%',
             separator: Separator::Regex("\\s+".into()),
+            #[cfg(unix)]
+            working_dir: PathBuf::from("/work"),
+            #[cfg(windows)]
+            working_dir: PathBuf::from("C:\\work"),
         }
     }
 }
@@ -77,12 +84,29 @@ impl fmt::Display for Error {
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ErrorKind {
+    BindingInsideNestedExpr,
+    DateFormatInvalid(String),
+    ExpectedAudioTag(Option<Char>),
+    ExpectedCaseStyle(Option<Char>),
+    ExpectedConditional,
+    ExpectedDateSource(Option<Char>),
     ExpectedDelimiterChar,
+    ExpectedExifTag,
+    ExpectedExprEndAfterBinding,
     ExpectedFieldSeparator,
     ExpectedFilter,
     ExpectedNumber,
     ExpectedFilterOrExprEnd,
+    ExpectedGlobFilter(Option<Char>),
+    ExpectedHashAlgorithm(Option<Char>),
+    ExpectedLookupTable,
+    ExpectedMetadataAttribute(Option<Char>),
+    ExpectedNormalizationForm(Option<Char>),
+    ExpectedNumberFormat(Option<Char>),
+    ExpectedOperand,
+    ExpectedOperator(Option<Char>),
     ExpectedPipeOrExprEnd,
+    ExpectedPredicate(Option<Char>),
     ExpectedRange,
     ExpectedRangeDelimiter(Option<Char>),
     ExpectedRangeLength,
@@ -90,10 +114,18 @@ pub enum ErrorKind {
     ExpectedRegexMatcher,
     ExpectedRegexSwitch,
     ExpectedRepetition,
+    ExpectedSanitizeProfile(Option<Char>),
     ExpectedSubstitution,
     ExprStartInsideExpr,
+    GlobInvalid(AnyString),
+    HashLengthZero,
     IndexZero,
     IntegerOverflow(String),
+    LookupTableFormatUnknown(String),
+    LookupTableInvalid(AnyString),
+    LookupWithNestedExpr,
+    NestedExprTooDeep(usize),
+    NumberInvalid(String),
     PaddingPrefixInvalid(char, Option<Char>),
     PipeOutsideExpr,
     RangeInvalid(String),
@@ -106,11 +138,14 @@ pub enum ErrorKind {
     UnmatchedExprEnd,
     UnmatchedExprStart,
     UnterminatedEscapeSequence(char),
+    VariableAlreadyDefined(String),
+    VariableUndefined(String),
 }
 
 #[derive(PartialEq, Debug)]
 pub enum ErrorHint {
     RegexSyntax,
+    DateFormatSyntax,
     PatternSyntax,
     FilterUsage,
 }
@@ -118,12 +153,29 @@ pub enum ErrorHint {
 impl ErrorKind {
     pub fn hint(&self) -> Option<ErrorHint> {
         match self {
+            Self::BindingInsideNestedExpr => Some(ErrorHint::PatternSyntax),
+            Self::DateFormatInvalid(_) => Some(ErrorHint::DateFormatSyntax),
+            Self::ExpectedAudioTag(_) => Some(ErrorHint::FilterUsage),
+            Self::ExpectedCaseStyle(_) => Some(ErrorHint::FilterUsage),
+            Self::ExpectedConditional => Some(ErrorHint::FilterUsage),
+            Self::ExpectedDateSource(_) => Some(ErrorHint::FilterUsage),
             Self::ExpectedDelimiterChar => Some(ErrorHint::FilterUsage),
+            Self::ExpectedExifTag => Some(ErrorHint::FilterUsage),
+            Self::ExpectedExprEndAfterBinding => Some(ErrorHint::PatternSyntax),
             Self::ExpectedFieldSeparator => Some(ErrorHint::FilterUsage),
             Self::ExpectedFilter => Some(ErrorHint::PatternSyntax),
             Self::ExpectedNumber => Some(ErrorHint::FilterUsage),
             Self::ExpectedFilterOrExprEnd => Some(ErrorHint::PatternSyntax),
+            Self::ExpectedGlobFilter(_) => Some(ErrorHint::FilterUsage),
+            Self::ExpectedHashAlgorithm(_) => Some(ErrorHint::FilterUsage),
+            Self::ExpectedLookupTable => Some(ErrorHint::FilterUsage),
+            Self::ExpectedMetadataAttribute(_) => Some(ErrorHint::FilterUsage),
+            Self::ExpectedNormalizationForm(_) => Some(ErrorHint::FilterUsage),
+            Self::ExpectedNumberFormat(_) => Some(ErrorHint::FilterUsage),
+            Self::ExpectedOperand => Some(ErrorHint::FilterUsage),
+            Self::ExpectedOperator(_) => Some(ErrorHint::FilterUsage),
             Self::ExpectedPipeOrExprEnd => Some(ErrorHint::PatternSyntax),
+            Self::ExpectedPredicate(_) => Some(ErrorHint::FilterUsage),
             Self::ExpectedRange => Some(ErrorHint::FilterUsage),
             Self::ExpectedRangeDelimiter(_) => Some(ErrorHint::FilterUsage),
             Self::ExpectedRangeLength => Some(ErrorHint::FilterUsage),
@@ -131,10 +183,18 @@ impl ErrorKind {
             Self::ExpectedRegexMatcher => Some(ErrorHint::FilterUsage),
             Self::ExpectedRegexSwitch => Some(ErrorHint::FilterUsage),
             Self::ExpectedRepetition => Some(ErrorHint::FilterUsage),
+            Self::ExpectedSanitizeProfile(_) => Some(ErrorHint::FilterUsage),
             Self::ExpectedSubstitution => Some(ErrorHint::FilterUsage),
             Self::ExprStartInsideExpr => Some(ErrorHint::PatternSyntax),
+            Self::GlobInvalid(_) => Some(ErrorHint::FilterUsage),
+            Self::HashLengthZero => Some(ErrorHint::FilterUsage),
             Self::IndexZero => Some(ErrorHint::FilterUsage),
             Self::IntegerOverflow(_) => None,
+            Self::LookupTableFormatUnknown(_) => Some(ErrorHint::FilterUsage),
+            Self::LookupTableInvalid(_) => None,
+            Self::LookupWithNestedExpr => Some(ErrorHint::FilterUsage),
+            Self::NestedExprTooDeep(_) => Some(ErrorHint::PatternSyntax),
+            Self::NumberInvalid(_) => Some(ErrorHint::FilterUsage),
             Self::PaddingPrefixInvalid(_, _) => Some(ErrorHint::FilterUsage),
             Self::PipeOutsideExpr => Some(ErrorHint::PatternSyntax),
             Self::RangeInvalid(_) => Some(ErrorHint::FilterUsage),
@@ -147,6 +207,8 @@ impl ErrorKind {
             Self::UnmatchedExprEnd => Some(ErrorHint::PatternSyntax),
             Self::UnmatchedExprStart => Some(ErrorHint::PatternSyntax),
             Self::UnterminatedEscapeSequence(_) => Some(ErrorHint::PatternSyntax),
+            Self::VariableAlreadyDefined(_) => Some(ErrorHint::PatternSyntax),
+            Self::VariableUndefined(_) => Some(ErrorHint::PatternSyntax),
         }
     }
 }
@@ -160,16 +222,85 @@ impl From<Infallible> for ErrorKind {
 impl fmt::Display for ErrorKind {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Self::BindingInsideNestedExpr => {
+                write!(formatter, "Nested expression cannot be bound to a variable")
+            }
+            Self::DateFormatInvalid(value) => {
+                write!(formatter, "Invalid date format '{}'", escape_str(value))
+            }
+            Self::ExpectedAudioTag(None) => write!(formatter, "Expected audio tag"),
+            Self::ExpectedAudioTag(Some(char)) => {
+                write!(formatter, "Expected audio tag but got {}", char)
+            }
+            Self::ExpectedCaseStyle(None) => write!(formatter, "Expected case style"),
+            Self::ExpectedCaseStyle(Some(char)) => {
+                write!(formatter, "Expected case style but got {}", char)
+            }
+            Self::ExpectedConditional => {
+                write!(formatter, "Expected conditional ':P:T:E' or ':P:T'")
+            }
+            Self::ExpectedDateSource(None) => write!(formatter, "Expected date source"),
+            Self::ExpectedDateSource(Some(char)) => {
+                write!(formatter, "Expected date source but got {}", char)
+            }
             Self::ExpectedDelimiterChar => write!(formatter, "Expected delimiter character"),
+            Self::ExpectedExifTag => write!(formatter, "Expected EXIF tag name"),
+            Self::ExpectedExprEndAfterBinding => {
+                write!(
+                    formatter,
+                    "Expected closing '{}' after variable binding",
+                    EXPR_END
+                )
+            }
             Self::ExpectedFieldSeparator => write!(formatter, "Expected field separator"),
             Self::ExpectedFilter => write!(formatter, "Expected filter after '{}'", PIPE),
             Self::ExpectedNumber => write!(formatter, "Expected number"),
             Self::ExpectedFilterOrExprEnd => {
                 write!(formatter, "Expected filter or closing '{}'", EXPR_END)
             }
+            Self::ExpectedGlobFilter(None) => write!(formatter, "Expected glob filter"),
+            Self::ExpectedGlobFilter(Some(char)) => {
+                write!(formatter, "Expected glob filter but got {}", char)
+            }
+            Self::ExpectedHashAlgorithm(None) => write!(formatter, "Expected hash algorithm"),
+            Self::ExpectedHashAlgorithm(Some(char)) => {
+                write!(formatter, "Expected hash algorithm but got {}", char)
+            }
+            Self::ExpectedLookupTable => write!(formatter, "Expected lookup table path"),
+            Self::ExpectedMetadataAttribute(None) => {
+                write!(formatter, "Expected file metadata attribute")
+            }
+            Self::ExpectedMetadataAttribute(Some(char)) => {
+                write!(
+                    formatter,
+                    "Expected file metadata attribute but got {}",
+                    char
+                )
+            }
+            Self::ExpectedNormalizationForm(None) => {
+                write!(formatter, "Expected normalization form")
+            }
+            Self::ExpectedNormalizationForm(Some(char)) => {
+                write!(formatter, "Expected normalization form but got {}", char)
+            }
+            Self::ExpectedNumberFormat(None) => write!(formatter, "Expected number format"),
+            Self::ExpectedNumberFormat(Some(char)) => {
+                write!(formatter, "Expected number format but got {}", char)
+            }
+            Self::ExpectedOperand => write!(formatter, "Expected arithmetic operand"),
+            Self::ExpectedOperator(None) => {
+                write!(formatter, "Expected arithmetic operator")
+            }
+            Self::ExpectedOperator(Some(char)) => {
+                write!(formatter, "Expected arithmetic operator but got {}", char)
+            }
             Self::ExpectedPipeOrExprEnd => {
                 write!(formatter, "Expected '{}' or closing '{}'", PIPE, EXPR_END)
             }
+            Self::ExpectedPredicate(None) => write!(formatter, "Expected predicate"),
+            Self::ExpectedPredicate(Some(char)) => {
+                write!(formatter, "Expected predicate but got {}", char)
+            }
             Self::ExpectedRange => write!(
                 formatter,
                 "Expected range 'A{}B', 'A{}', 'A' or 'A{}B'",
@@ -203,14 +334,41 @@ impl fmt::Display for ErrorKind {
             Self::ExpectedRepetition => {
                 write!(formatter, "Expected repetition 'N:V' or 'N'")
             }
+            Self::ExpectedSanitizeProfile(None) => write!(formatter, "Expected sanitize profile"),
+            Self::ExpectedSanitizeProfile(Some(char)) => {
+                write!(formatter, "Expected sanitize profile but got {}", char)
+            }
             Self::ExpectedSubstitution => write!(formatter, "Expected substitution ':A:B' or ':A'"),
             Self::ExprStartInsideExpr => {
-                write!(formatter, "Unescaped '{}' inside expression", EXPR_START)
+                write!(formatter, "Expected filter before nested '{}'", EXPR_START)
             }
+            Self::GlobInvalid(value) => write!(formatter, "Invalid glob pattern '{}'", value),
+            Self::HashLengthZero => write!(formatter, "Hash length must be greater than 0"),
             Self::IndexZero => write!(formatter, "Indices start from 1, not 0"),
             Self::IntegerOverflow(max) => {
                 write!(formatter, "Cannot parse value greater than {}", max)
             }
+            Self::LookupTableFormatUnknown(path) => write!(
+                formatter,
+                "Lookup table '{}' does not have csv, tsv or json extension",
+                escape_str(path)
+            ),
+            Self::LookupTableInvalid(reason) => {
+                write!(formatter, "Cannot load lookup table: {}", reason)
+            }
+            Self::LookupWithNestedExpr => {
+                write!(formatter, "Lookup table cannot contain nested expressions")
+            }
+            Self::NestedExprTooDeep(max) => {
+                write!(
+                    formatter,
+                    "Nested expressions cannot be deeper than {} levels",
+                    max
+                )
+            }
+            Self::NumberInvalid(value) => {
+                write!(formatter, "Invalid number '{}'", escape_str(value))
+            }
             Self::PaddingPrefixInvalid(fixed_prefix, None) => {
                 write!(formatter, "Expected '{}' prefix or number", fixed_prefix)
             }
@@ -268,6 +426,10 @@ impl fmt::Display for ErrorKind {
                     escape_char(*escape)
                 )
             }
+            Self::VariableAlreadyDefined(name) => {
+                write!(formatter, "Variable '{}' is already defined", name)
+            }
+            Self::VariableUndefined(name) => write!(formatter, "Undefined variable '{}'", name),
         }
     }
 }
@@ -320,45 +482,99 @@ mod tests {
         type E = ErrorKind;
         type H = ErrorHint;
 
-        #[test_case(E::ExpectedDelimiterChar,                     Some(H::FilterUsage)   ; "expected delimiter char")]
-        #[test_case(E::ExpectedFieldSeparator,                    Some(H::FilterUsage)   ; "expected field separator")]
-        #[test_case(E::ExpectedFilter,                            Some(H::PatternSyntax) ; "expected filter")]
-        #[test_case(E::ExpectedNumber,                            Some(H::FilterUsage)   ; "expected number")]
-        #[test_case(E::ExpectedFilterOrExprEnd,                   Some(H::PatternSyntax) ; "expected filter or expr end")]
-        #[test_case(E::ExpectedPipeOrExprEnd,                     Some(H::PatternSyntax) ; "expected pipe or expr end")]
-        #[test_case(E::ExpectedRange,                             Some(H::FilterUsage)   ; "expected range")]
-        #[test_case(E::ExpectedRangeDelimiter(None),              Some(H::FilterUsage)   ; "expected delimiter got none")]
-        #[test_case(E::ExpectedRangeLength,                       Some(H::FilterUsage)   ; "expected range length")]
-        #[test_case(E::ExpectedRegex,                             Some(H::FilterUsage)   ; "expected regex")]
-        #[test_case(E::ExpectedRegexMatcher,                      Some(H::FilterUsage)   ; "expected regex matcher")]
-        #[test_case(E::ExpectedRegexSwitch,                       Some(H::FilterUsage)   ; "expected regex switch")]
-        #[test_case(E::ExpectedRepetition,                        Some(H::FilterUsage)   ; "expected repetition")]
-        #[test_case(E::ExpectedSubstitution,                      Some(H::FilterUsage)   ; "expected substitution")]
-        #[test_case(E::ExprStartInsideExpr,                       Some(H::PatternSyntax) ; "expr start inside expr")]
-        #[test_case(E::IndexZero,                                 Some(H::FilterUsage)   ; "index zero")]
-        #[test_case(E::IntegerOverflow("255".into()),             None                   ; "integer overflow")]
-        #[test_case(E::PaddingPrefixInvalid('<', None),           Some(H::FilterUsage)   ; "padding prefix missing")]
-        #[test_case(E::PipeOutsideExpr,                           Some(H::PatternSyntax) ; "pipe outside expr")]
-        #[test_case(E::RangeInvalid("abc".into()),                Some(H::FilterUsage)   ; "range invalid")]
-        #[test_case(E::RangeStartOverEnd("2".into(), "1".into()), Some(H::FilterUsage)   ; "range start over end")]
-        #[test_case(E::RegexInvalid("abc".into()),                Some(H::RegexSyntax)   ; "regex invalid")]
-        #[test_case(E::RegexSwitchWithoutMatcher('_'.into(), 0),  Some(H::FilterUsage)   ; "regex switch without matcher")]
-        #[test_case(E::SubstitutionWithoutTarget('_'.into()),     Some(H::FilterUsage)   ; "substitution without target")]
-        #[test_case(E::UnknownEscapeSequence(['%', 'x']),         Some(H::PatternSyntax) ; "unknown escape sequence" )]
-        #[test_case(E::UnknownFilter('x'.into()),                 Some(H::FilterUsage)   ; "unknown filter")]
-        #[test_case(E::UnmatchedExprEnd,                          Some(H::PatternSyntax) ; "unmatched expr end")]
-        #[test_case(E::UnmatchedExprStart,                        Some(H::PatternSyntax) ; "unmatched expr start")]
-        #[test_case(E::UnterminatedEscapeSequence('%'),           Some(H::PatternSyntax) ; "unterminated escape sequence")]
+        #[test_case(E::BindingInsideNestedExpr,                   Some(H::PatternSyntax)    ; "binding inside nested expr")]
+        #[test_case(E::DateFormatInvalid("%Q".into()),            Some(H::DateFormatSyntax) ; "date format invalid")]
+        #[test_case(E::ExpectedAudioTag(None),                    Some(H::FilterUsage)      ; "expected audio tag")]
+        #[test_case(E::ExpectedCaseStyle(None),                   Some(H::FilterUsage)      ; "expected case style")]
+        #[test_case(E::ExpectedConditional,                       Some(H::FilterUsage)      ; "expected conditional")]
+        #[test_case(E::ExpectedDateSource(None),                  Some(H::FilterUsage)      ; "expected date source")]
+        #[test_case(E::ExpectedDelimiterChar,                     Some(H::FilterUsage)      ; "expected delimiter char")]
+        #[test_case(E::ExpectedExifTag,                           Some(H::FilterUsage)      ; "expected exif tag")]
+        #[test_case(E::ExpectedExprEndAfterBinding,               Some(H::PatternSyntax)    ; "expected expr end after binding")]
+        #[test_case(E::ExpectedFieldSeparator,                    Some(H::FilterUsage)      ; "expected field separator")]
+        #[test_case(E::ExpectedFilter,                            Some(H::PatternSyntax)    ; "expected filter")]
+        #[test_case(E::ExpectedNumber,                            Some(H::FilterUsage)      ; "expected number")]
+        #[test_case(E::ExpectedFilterOrExprEnd,                   Some(H::PatternSyntax)    ; "expected filter or expr end")]
+        #[test_case(E::ExpectedGlobFilter(None),                  Some(H::FilterUsage)      ; "expected glob filter")]
+        #[test_case(E::ExpectedHashAlgorithm(None),               Some(H::FilterUsage)      ; "expected hash algorithm")]
+        #[test_case(E::ExpectedLookupTable,                       Some(H::FilterUsage)      ; "expected lookup table")]
+        #[test_case(E::ExpectedMetadataAttribute(None),           Some(H::FilterUsage)      ; "expected metadata attribute")]
+        #[test_case(E::ExpectedNormalizationForm(None),           Some(H::FilterUsage)      ; "expected normalization form")]
+        #[test_case(E::ExpectedNumberFormat(None),                Some(H::FilterUsage)      ; "expected number format")]
+        #[test_case(E::ExpectedOperand,                           Some(H::FilterUsage)      ; "expected operand")]
+        #[test_case(E::ExpectedOperator(None),                    Some(H::FilterUsage)      ; "expected operator")]
+        #[test_case(E::ExpectedPipeOrExprEnd,                     Some(H::PatternSyntax)    ; "expected pipe or expr end")]
+        #[test_case(E::ExpectedPredicate(None),                   Some(H::FilterUsage)      ; "expected predicate")]
+        #[test_case(E::ExpectedRange,                             Some(H::FilterUsage)      ; "expected range")]
+        #[test_case(E::ExpectedRangeDelimiter(None),              Some(H::FilterUsage)      ; "expected delimiter got none")]
+        #[test_case(E::ExpectedRangeLength,                       Some(H::FilterUsage)      ; "expected range length")]
+        #[test_case(E::ExpectedRegex,                             Some(H::FilterUsage)      ; "expected regex")]
+        #[test_case(E::ExpectedRegexMatcher,                      Some(H::FilterUsage)      ; "expected regex matcher")]
+        #[test_case(E::ExpectedRegexSwitch,                       Some(H::FilterUsage)      ; "expected regex switch")]
+        #[test_case(E::ExpectedRepetition,                        Some(H::FilterUsage)      ; "expected repetition")]
+        #[test_case(E::ExpectedSanitizeProfile(None),             Some(H::FilterUsage)      ; "expected sanitize profile")]
+        #[test_case(E::ExpectedSubstitution,                      Some(H::FilterUsage)      ; "expected substitution")]
+        #[test_case(E::ExprStartInsideExpr,                       Some(H::PatternSyntax)    ; "expr start inside expr")]
+        #[test_case(E::GlobInvalid("abc".into()),                 Some(H::FilterUsage)      ; "glob invalid")]
+        #[test_case(E::HashLengthZero,                            Some(H::FilterUsage)      ; "hash length zero")]
+        #[test_case(E::IndexZero,                                 Some(H::FilterUsage)      ; "index zero")]
+        #[test_case(E::IntegerOverflow("255".into()),             None                      ; "integer overflow")]
+        #[test_case(E::LookupTableFormatUnknown("a.txt".into()),  Some(H::FilterUsage)      ; "lookup table format unknown")]
+        #[test_case(E::LookupTableInvalid(AnyString::any()),      None                      ; "lookup table invalid")]
+        #[test_case(E::LookupWithNestedExpr,                      Some(H::FilterUsage)      ; "lookup with nested expr")]
+        #[test_case(E::NestedExprTooDeep(64),                     Some(H::PatternSyntax)    ; "nested expr too deep")]
+        #[test_case(E::NumberInvalid("1x".into()),                Some(H::FilterUsage)      ; "number invalid")]
+        #[test_case(E::PaddingPrefixInvalid('<', None),           Some(H::FilterUsage)      ; "padding prefix missing")]
+        #[test_case(E::PipeOutsideExpr,                           Some(H::PatternSyntax)    ; "pipe outside expr")]
+        #[test_case(E::RangeInvalid("abc".into()),                Some(H::FilterUsage)      ; "range invalid")]
+        #[test_case(E::RangeStartOverEnd("2".into(), "1".into()), Some(H::FilterUsage)      ; "range start over end")]
+        #[test_case(E::RegexInvalid("abc".into()),                Some(H::RegexSyntax)      ; "regex invalid")]
+        #[test_case(E::RegexSwitchWithoutMatcher('_'.into(), 0),  Some(H::FilterUsage)      ; "regex switch without matcher")]
+        #[test_case(E::SubstitutionWithoutTarget('_'.into()),     Some(H::FilterUsage)      ; "substitution without target")]
+        #[test_case(E::UnknownEscapeSequence(['%', 'x']),         Some(H::PatternSyntax)    ; "unknown escape sequence" )]
+        #[test_case(E::UnknownFilter('x'.into()),                 Some(H::FilterUsage)      ; "unknown filter")]
+        #[test_case(E::UnmatchedExprEnd,                          Some(H::PatternSyntax)    ; "unmatched expr end")]
+        #[test_case(E::UnmatchedExprStart,                        Some(H::PatternSyntax)    ; "unmatched expr start")]
+        #[test_case(E::UnterminatedEscapeSequence('%'),           Some(H::PatternSyntax)    ; "unterminated escape sequence")]
+        #[test_case(E::VariableAlreadyDefined("x".into()),        Some(H::PatternSyntax)    ; "variable already defined")]
+        #[test_case(E::VariableUndefined("x".into()),             Some(H::PatternSyntax)    ; "variable undefined")]
         fn hint(kind: ErrorKind, hint: Option<ErrorHint>) {
             assert_eq!(kind.hint(), hint);
         }
 
+        #[test_case(E::BindingInsideNestedExpr,                     "Nested expression cannot be bound to a variable"                   ; "binding inside nested expr")]
+        #[test_case(E::DateFormatInvalid("%Q".into()),              "Invalid date format '%Q'"                                          ; "date format invalid")]
+        #[test_case(E::ExpectedAudioTag(None),                      "Expected audio tag"                                                ; "expected audio tag got none")]
+        #[test_case(E::ExpectedAudioTag(Some('x'.into())),          "Expected audio tag but got 'x'"                                    ; "expected audio tag got invalid")]
+        #[test_case(E::ExpectedCaseStyle(None),                     "Expected case style"                                               ; "expected case style got none")]
+        #[test_case(E::ExpectedCaseStyle(Some('x'.into())),         "Expected case style but got 'x'"                                   ; "expected case style got invalid")]
+        #[test_case(E::ExpectedConditional,                         "Expected conditional ':P:T:E' or ':P:T'"                           ; "expected conditional")]
+        #[test_case(E::ExpectedDateSource(None),                    "Expected date source"                                              ; "expected date source got none")]
+        #[test_case(E::ExpectedDateSource(Some('x'.into())),        "Expected date source but got 'x'"                                  ; "expected date source got invalid")]
         #[test_case(E::ExpectedDelimiterChar,                       "Expected delimiter character"                                      ; "expected delimiter character")]
+        #[test_case(E::ExpectedExifTag,                             "Expected EXIF tag name"                                            ; "expected exif tag")]
+        #[test_case(E::ExpectedExprEndAfterBinding,                 "Expected closing '}' after variable binding"                       ; "expected expr end after binding")]
         #[test_case(E::ExpectedFieldSeparator,                      "Expected field separator"                                          ; "expected field separator")]
         #[test_case(E::ExpectedFilter,                              "Expected filter after '|'"                                         ; "expected filter")]
         #[test_case(E::ExpectedNumber,                              "Expected number"                                                   ; "expected number")]
         #[test_case(E::ExpectedFilterOrExprEnd,                     "Expected filter or closing '}'"                                    ; "expected filter or expr end")]
+        #[test_case(E::ExpectedGlobFilter(None),                    "Expected glob filter"                                              ; "expected glob filter got none")]
+        #[test_case(E::ExpectedGlobFilter(Some('x'.into())),        "Expected glob filter but got 'x'"                                  ; "expected glob filter got invalid")]
+        #[test_case(E::ExpectedHashAlgorithm(None),                 "Expected hash algorithm"                                           ; "expected hash algorithm got none")]
+        #[test_case(E::ExpectedHashAlgorithm(Some('x'.into())),     "Expected hash algorithm but got 'x'"                               ; "expected hash algorithm got invalid")]
+        #[test_case(E::ExpectedLookupTable,                         "Expected lookup table path"                                        ; "expected lookup table")]
+        #[test_case(E::ExpectedMetadataAttribute(None),             "Expected file metadata attribute"                                  ; "expected metadata attribute got none")]
+        #[test_case(E::ExpectedMetadataAttribute(Some('x'.into())), "Expected file metadata attribute but got 'x'"                      ; "expected metadata attribute got invalid")]
+        #[test_case(E::ExpectedNormalizationForm(None),             "Expected normalization form"                                       ; "expected normalization form got none")]
+        #[test_case(E::ExpectedNormalizationForm(Some('x'.into())), "Expected normalization form but got 'x'"                           ; "expected normalization form got invalid")]
+        #[test_case(E::ExpectedNumberFormat(None),                  "Expected number format"                                            ; "expected number format got none")]
+        #[test_case(E::ExpectedNumberFormat(Some('x'.into())),      "Expected number format but got 'x'"                                ; "expected number format got invalid")]
+        #[test_case(E::ExpectedOperand,                             "Expected arithmetic operand"                                       ; "expected operand")]
+        #[test_case(E::ExpectedOperator(None),                      "Expected arithmetic operator"                                      ; "expected operator got none")]
+        #[test_case(E::ExpectedOperator(Some('x'.into())),          "Expected arithmetic operator but got 'x'"                          ; "expected operator got invalid")]
         #[test_case(E::ExpectedPipeOrExprEnd,                       "Expected '|' or closing '}'"                                       ; "expected pipe or expr end")]
+        #[test_case(E::ExpectedPredicate(None),                     "Expected predicate"                                                ; "expected predicate got none")]
+        #[test_case(E::ExpectedPredicate(Some('x'.into())),         "Expected predicate but got 'x'"                                    ; "expected predicate got invalid")]
         #[test_case(E::ExpectedRange,                               "Expected range 'A-B', 'A-', 'A' or 'A+B'"                          ; "expected range")]
         #[test_case(E::ExpectedRangeDelimiter(None),                "Expected range delimiter '-'"                                      ; "expected delimiter got none")]
         #[test_case(E::ExpectedRangeDelimiter(Some('x'.into())),    "Expected range delimiter '-' but got 'x'"                          ; "expected delimiter got invalid")]
@@ -367,10 +583,19 @@ mod tests {
         #[test_case(E::ExpectedRegexMatcher,                        "Expected regular expression matcher 'A:X', 'A-:X' or  'A-B:X'"     ; "expected regex matcher")]
         #[test_case(E::ExpectedRegexSwitch,                         "Expected regular expression switch ':X1:Y1:...:Xn:Yn:D'"           ; "expected regex switch")]
         #[test_case(E::ExpectedRepetition,                          "Expected repetition 'N:V' or 'N'"                                  ; "expected repetition")]
+        #[test_case(E::ExpectedSanitizeProfile(None),               "Expected sanitize profile"                                         ; "expected sanitize profile got none")]
+        #[test_case(E::ExpectedSanitizeProfile(Some('x'.into())),   "Expected sanitize profile but got 'x'"                             ; "expected sanitize profile got invalid")]
         #[test_case(E::ExpectedSubstitution,                        "Expected substitution ':A:B' or ':A'"                              ; "expected substitution")]
-        #[test_case(E::ExprStartInsideExpr,                         "Unescaped '{' inside expression"                                   ; "expr start inside expr")]
+        #[test_case(E::ExprStartInsideExpr,                         "Expected filter before nested '{'"                                 ; "expr start inside expr")]
+        #[test_case(E::GlobInvalid("abc".into()),                   "Invalid glob pattern 'abc'"                                        ; "glob invalid")]
+        #[test_case(E::HashLengthZero,                              "Hash length must be greater than 0"                                ; "hash length zero")]
         #[test_case(E::IndexZero,                                   "Indices start from 1, not 0"                                       ; "index zero")]
         #[test_case(E::IntegerOverflow("255".into()),               "Cannot parse value greater than 255"                               ; "integer overflow")]
+        #[test_case(E::LookupTableFormatUnknown("a.txt".into()),    "Lookup table 'a.txt' does not have csv, tsv or json extension"     ; "lookup table format unknown")]
+        #[test_case(E::LookupTableInvalid("abc".into()),            "Cannot load lookup table: abc"                                     ; "lookup table invalid")]
+        #[test_case(E::LookupWithNestedExpr,                        "Lookup table cannot contain nested expressions"                    ; "lookup with nested expr")]
+        #[test_case(E::NestedExprTooDeep(64),                       "Nested expressions cannot be deeper than 64 levels"                ; "nested expr too deep")]
+        #[test_case(E::NumberInvalid("1x".into()),                  "Invalid number '1x'"                                               ; "number invalid")]
         #[test_case(E::PaddingPrefixInvalid('<', None),             "Expected '<' prefix or number"                                     ; "padding prefix missing")]
         #[test_case(E::PaddingPrefixInvalid('<', Some('x'.into())), "Expected '<' prefix or number but got 'x'"                         ; "padding prefix invalid")]
         #[test_case(E::PipeOutsideExpr,                             "Unescaped '|' outside expression"                                  ; "pipe outside expr")]
@@ -384,6 +609,8 @@ mod tests {
         #[test_case(E::UnmatchedExprEnd,                            "No matching '{' before expression end"                             ; "unmatched expr end")]
         #[test_case(E::UnmatchedExprStart,                          "No matching '}' after expression start"                            ; "unmatched expr start")]
         #[test_case(E::UnterminatedEscapeSequence('%'),             "Unterminated escape sequence '%'"                                  ; "unterminated escape sequence")]
+        #[test_case(E::VariableAlreadyDefined("x".into()),          "Variable 'x' is already defined"                                   ; "variable already defined")]
+        #[test_case(E::VariableUndefined("x".into()),               "Undefined variable 'x'"                                            ; "variable undefined")]
         fn display(kind: ErrorKind, result: &str) {
             assert_eq!(kind.to_string(), result);
         }
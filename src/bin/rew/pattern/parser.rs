@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fmt;
 
 use crate::pattern::char::{AsChar, Char, Chars};
@@ -7,14 +8,21 @@ use crate::pattern::filter::Filter;
 use crate::pattern::lexer::{Lexer, ParsedToken, Token};
 use crate::pattern::parse::{Config, Error, ErrorKind, Parsed, Result};
 use crate::pattern::reader::Reader;
+use crate::pattern::template::{Expression, ParsedExpression, Part, Template};
+use crate::pattern::variable::{find_binding, Binding};
+
+// Each nesting level is a recursive call, deep patterns would overflow the stack
+const MAX_NESTED_EXPR_DEPTH: usize = 64;
 
 pub type ParsedFilter = Parsed<Filter>;
+pub type ParsedBinding = Parsed<Binding>;
 pub type ParsedItem = Parsed<Item>;
 
 #[derive(Debug, PartialEq)]
 pub enum Item {
     Constant(String),
     Expression(Vec<ParsedFilter>),
+    Binding(Vec<ParsedFilter>, ParsedBinding),
 }
 
 impl fmt::Display for Item {
@@ -30,14 +38,32 @@ impl fmt::Display for Item {
             Self::Expression(filters) => {
                 write!(formatter, "Expression with {} filters", filters.len())
             }
+            Self::Binding(filters, binding) if filters.len() == 1 => write!(
+                formatter,
+                "Expression with a filter bound to variable '{}'",
+                binding.value.0
+            ),
+            Self::Binding(filters, binding) => write!(
+                formatter,
+                "Expression with {} filters bound to variable '{}'",
+                filters.len(),
+                binding.value.0
+            ),
         }
     }
 }
 
+enum Segment {
+    Filter(ParsedFilter),
+    Binding(ParsedBinding),
+}
+
 pub struct Parser<'a> {
     lexer: Lexer,
     token: Option<ParsedToken>,
     config: &'a Config,
+    variables: HashSet<String>,
+    depth: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -46,6 +72,8 @@ impl<'a> Parser<'a> {
             lexer: Lexer::new(input, config.escape),
             token: None,
             config,
+            variables: HashSet::new(),
+            depth: 0,
         }
     }
 
@@ -97,23 +125,40 @@ impl<'a> Parser<'a> {
 
     fn parse_expression(&mut self) -> Result<Option<ParsedItem>> {
         let start = self.token_range().start;
-        let filters = self.parse_filters()?;
+        let (filters, binding) = self.parse_filters()?;
         let end = self.token_range().end;
 
+        let value = if let Some(binding) = binding {
+            // Variable is defined after its expression, so the expression cannot reference itself
+            if !self.variables.insert(binding.value.0.clone()) {
+                return Err(Error {
+                    kind: ErrorKind::VariableAlreadyDefined(binding.value.0),
+                    range: binding.range,
+                });
+            }
+            Item::Binding(filters, binding)
+        } else {
+            Item::Expression(filters)
+        };
+
         Ok(Some(Parsed {
-            value: Item::Expression(filters),
+            value,
             range: start..end,
         }))
     }
 
-    fn parse_filters(&mut self) -> Result<Vec<ParsedFilter>> {
+    fn parse_filters(&mut self) -> Result<(Vec<ParsedFilter>, Option<ParsedBinding>)> {
         let mut filters: Vec<ParsedFilter> = Vec::new();
+        let mut binding = None;
         self.fetch_token()?;
 
         while let Some(token) = &self.token {
             match &token.value {
-                Token::Raw(raw) => {
-                    filters.push(self.parse_filter(raw, &token.range)?);
+                Token::Raw(_) => {
+                    // The first segment is always a filter
+                    if let Segment::Filter(filter) = self.parse_segment(false)? {
+                        filters.push(filter);
+                    }
                 }
                 Token::Pipe => {
                     if filters.is_empty() {
@@ -121,13 +166,23 @@ impl<'a> Parser<'a> {
                             kind: ErrorKind::ExpectedFilterOrExprEnd,
                             range: token.range.clone(),
                         });
+                    } else if binding.is_some() {
+                        return Err(Error {
+                            kind: ErrorKind::ExpectedExprEndAfterBinding,
+                            range: token.range.clone(),
+                        });
                     } else {
                         let position = self.token_range().end;
                         self.fetch_token()?;
 
                         if let Some(token) = &self.token {
-                            if let Token::Raw(raw) = &token.value {
-                                filters.push(self.parse_filter(raw, &token.range)?)
+                            if let Token::Raw(_) = &token.value {
+                                match self.parse_segment(true)? {
+                                    Segment::Filter(filter) => filters.push(filter),
+                                    Segment::Binding(segment_binding) => {
+                                        binding = Some(segment_binding)
+                                    }
+                                }
                             } else {
                                 return Err(Error {
                                     kind: ErrorKind::ExpectedFilter,
@@ -152,14 +207,60 @@ impl<'a> Parser<'a> {
                     break;
                 }
             }
+        }
+
+        Ok((filters, binding))
+    }
+
+    // Reads raw characters and nested expressions until the end of filter or binding
+    fn parse_segment(&mut self, binding_allowed: bool) -> Result<Segment> {
+        let start = self.token_range().start;
+        let mut end = start;
+        let mut parts = Vec::new();
+
+        while let Some(token) = &self.token {
+            match &token.value {
+                Token::Raw(raw) => {
+                    parts.push(Part::Chars(raw.clone()));
+                    end = token.range.end;
+                }
+                Token::ExprStart => {
+                    let expression = self.parse_nested_expression()?;
+                    end = expression.range.end;
+                    parts.push(Part::Expression(expression));
+                }
+                Token::Pipe | Token::ExprEnd => break,
+            }
             self.fetch_token()?;
         }
 
-        Ok(filters)
+        let range = start..end;
+
+        if let [Part::Chars(chars)] = parts.as_slice() {
+            if let Some(name_start) = find_binding(chars).filter(|_| binding_allowed) {
+                return Ok(Segment::Binding(Parsed {
+                    value: Binding(Chars::from(&chars[name_start..]).to_string()),
+                    range,
+                }));
+            }
+        }
+
+        let filter = match parts.pop() {
+            Some(Part::Chars(chars)) if parts.is_empty() => {
+                self.parse_static_filter(chars, range)?
+            }
+            Some(part) => {
+                parts.push(part);
+                self.parse_template_filter(parts, range)?
+            }
+            None => unreachable!("Filter should start with raw characters"),
+        };
+
+        Ok(Segment::Filter(filter))
     }
 
-    fn parse_filter(&self, chars: &[Char], range: &ErrorRange) -> Result<ParsedFilter> {
-        let mut reader = Reader::new(Vec::from(chars));
+    fn parse_static_filter(&self, chars: Vec<Char>, range: ErrorRange) -> Result<ParsedFilter> {
+        let mut reader = Reader::new(chars);
 
         let filter = Filter::parse(&mut reader, self.config).map_err(|mut error| {
             let start = range.start + error.range.start;
@@ -179,10 +280,83 @@ impl<'a> Parser<'a> {
                 range: start..end,
             })
         } else {
-            Ok(Parsed {
+            let mut filter = Parsed {
                 value: filter,
-                range: range.clone(),
+                range,
+            };
+
+            if let Filter::Conditional(conditional) = &mut filter.value {
+                conditional.shift_ranges(filter.range.start);
+            }
+
+            self.check_variables(&filter)?;
+            Ok(filter)
+        }
+    }
+
+    // Nested expressions were already validated, the rest is parsed during evaluation
+    fn parse_template_filter(&self, parts: Vec<Part>, range: ErrorRange) -> Result<ParsedFilter> {
+        let name_end = match parts.first() {
+            Some(Part::Chars(chars)) => range.start + chars[0].len_utf8(),
+            _ => range.start,
+        };
+
+        match Template::new(parts, self.config) {
+            Ok(template) => Ok(Parsed {
+                value: Filter::Template(template),
+                range,
+            }),
+            Err(kind) => Err(Error {
+                kind,
+                range: range.start..name_end,
+            }),
+        }
+    }
+
+    fn parse_nested_expression(&mut self) -> Result<ParsedExpression> {
+        let expr_start_range = self.token_range().clone();
+
+        if self.depth == MAX_NESTED_EXPR_DEPTH {
+            return Err(Error {
+                kind: ErrorKind::NestedExprTooDeep(MAX_NESTED_EXPR_DEPTH),
+                range: expr_start_range,
+            });
+        }
+
+        self.depth += 1;
+        let (filters, binding) = self.parse_filters()?;
+        self.depth -= 1;
+
+        if let Some(binding) = binding {
+            return Err(Error {
+                kind: ErrorKind::BindingInsideNestedExpr,
+                range: binding.range,
+            });
+        }
+
+        if let Some(Token::ExprEnd) = self.token_value() {
+            Ok(Parsed {
+                value: Expression(filters),
+                range: expr_start_range.start..self.token_range().end,
             })
+        } else {
+            Err(Error {
+                kind: ErrorKind::UnmatchedExprStart,
+                range: expr_start_range,
+            })
+        }
+    }
+
+    fn check_variables(&self, filter: &ParsedFilter) -> Result<()> {
+        match &filter.value {
+            Filter::Variable(name) if !self.variables.contains(name) => Err(Error {
+                kind: ErrorKind::VariableUndefined(name.clone()),
+                range: filter.range.clone(),
+            }),
+            Filter::Conditional(conditional) => conditional
+                .filters()
+                .try_for_each(|filter| self.check_variables(filter)),
+            _ => Ok(()),
         }
     }
 
@@ -206,10 +380,12 @@ mod tests {
 
     use super::*;
 
-    #[test_case(Item::Constant("abc".into()),     "Constant 'abc'"            ; "constant")]
-    #[test_case(Item::Expression(Vec::new()),     "Empty expression"          ; "empty expr")]
-    #[test_case(Item::Expression(vec![f()]),      "Expression with a filter"  ; "expr single filter")]
-    #[test_case(Item::Expression(vec![f(), f()]), "Expression with 2 filters" ; "expr multiple filters")]
+    #[test_case(Item::Constant("abc".into()),       "Constant 'abc'"                                  ; "constant")]
+    #[test_case(Item::Expression(Vec::new()),       "Empty expression"                                ; "empty expr")]
+    #[test_case(Item::Expression(vec![f()]),        "Expression with a filter"                        ; "expr single filter")]
+    #[test_case(Item::Expression(vec![f(), f()]),   "Expression with 2 filters"                       ; "expr multiple filters")]
+    #[test_case(Item::Binding(vec![f()], b()),      "Expression with a filter bound to variable 'x'"  ; "binding single filter")]
+    #[test_case(Item::Binding(vec![f(), f()], b()), "Expression with 2 filters bound to variable 'x'" ; "binding multiple filters")]
     fn item_display(item: Item, result: &str) {
         assert_eq!(item.to_string(), result);
     }
@@ -218,6 +394,10 @@ mod tests {
         Parsed::from(Filter::Trim)
     }
 
+    fn b() -> ParsedBinding {
+        Parsed::from(Binding("x".into()))
+    }
+
     mod parse {
         use test_case::test_case;
 
@@ -227,19 +407,29 @@ mod tests {
         use crate::pattern::replace::Substitution;
         use crate::pattern::substr::CharIndexRange;
 
-        #[test_case("|",      0..1, ErrorKind::PipeOutsideExpr                           ; "pipe outside expr")]
-        #[test_case("}",      0..1, ErrorKind::UnmatchedExprEnd                          ; "unmatched expr end")]
-        #[test_case("{",      0..1, ErrorKind::UnmatchedExprStart                        ; "unmatched expr start")]
-        #[test_case("{|",     1..2, ErrorKind::ExpectedFilterOrExprEnd                   ; "filter after expr start")]
-        #[test_case("{f",     0..1, ErrorKind::UnmatchedExprStart                        ; "missing pipe or expr end")]
-        #[test_case("{f{",    2..3, ErrorKind::ExprStartInsideExpr                       ; "expr start after filter")]
-        #[test_case("{ff",    2..3, ErrorKind::ExpectedPipeOrExprEnd                     ; "filter after filter")]
-        #[test_case("{f|",    3..3, ErrorKind::ExpectedFilter                            ; "missing filter after pipe")]
-        #[test_case("{f||",   3..4, ErrorKind::ExpectedFilter                            ; "pipe after pipe")]
-        #[test_case("{f|}",   3..4, ErrorKind::ExpectedFilter                            ; "expr end after pipe")]
-        #[test_case("{f|f",   0..1, ErrorKind::UnmatchedExprStart                        ; "missing pipe or expr end 2")]
-        #[test_case("{f|ff",  4..5, ErrorKind::ExpectedPipeOrExprEnd                     ; "filter after filter 2")]
-        #[test_case("{#2-1}", 2..5, ErrorKind::RangeStartOverEnd("2".into(), "1".into()) ; "invalid filter")]
+        #[test_case("|",                0..1,   ErrorKind::PipeOutsideExpr                           ; "pipe outside expr")]
+        #[test_case("}",                0..1,   ErrorKind::UnmatchedExprEnd                          ; "unmatched expr end")]
+        #[test_case("{",                0..1,   ErrorKind::UnmatchedExprStart                        ; "unmatched expr start")]
+        #[test_case("{|",               1..2,   ErrorKind::ExpectedFilterOrExprEnd                   ; "filter after expr start")]
+        #[test_case("{f",               0..1,   ErrorKind::UnmatchedExprStart                        ; "missing pipe or expr end")]
+        #[test_case("{{",               1..2,   ErrorKind::ExprStartInsideExpr                       ; "expr start after expr start")]
+        #[test_case("{f{",              2..3,   ErrorKind::UnmatchedExprStart                        ; "unmatched nested expr start")]
+        #[test_case("{Y{f}}",           1..2,   ErrorKind::UnknownFilter(Char::Raw('Y'))             ; "unknown filter with nested expr")]
+        #[test_case("{r:{f|}}",         6..7,   ErrorKind::ExpectedFilter                            ; "invalid nested expr")]
+        #[test_case("{r:{$x}}",         4..6,   ErrorKind::VariableUndefined("x".into())             ; "undefined variable in nested expr")]
+        #[test_case("{r:{f|as x}}",     6..10,  ErrorKind::BindingInsideNestedExpr                   ; "binding inside nested expr")]
+        #[test_case("{ff",              2..3,   ErrorKind::ExpectedPipeOrExprEnd                     ; "filter after filter")]
+        #[test_case("{f|",              3..3,   ErrorKind::ExpectedFilter                            ; "missing filter after pipe")]
+        #[test_case("{f||",             3..4,   ErrorKind::ExpectedFilter                            ; "pipe after pipe")]
+        #[test_case("{f|}",             3..4,   ErrorKind::ExpectedFilter                            ; "expr end after pipe")]
+        #[test_case("{f|f",             0..1,   ErrorKind::UnmatchedExprStart                        ; "missing pipe or expr end 2")]
+        #[test_case("{f|ff",            4..5,   ErrorKind::ExpectedPipeOrExprEnd                     ; "filter after filter 2")]
+        #[test_case("{#2-1}",           2..5,   ErrorKind::RangeStartOverEnd("2".into(), "1".into()) ; "invalid filter")]
+        #[test_case("{$x}",             1..3,   ErrorKind::VariableUndefined("x".into())             ; "undefined variable")]
+        #[test_case("{$x|as x}",        1..3,   ErrorKind::VariableUndefined("x".into())             ; "self referencing variable")]
+        #[test_case("{f|q:z::q/e/$x}",  12..14, ErrorKind::VariableUndefined("x".into())             ; "undefined variable in conditional")]
+        #[test_case("{f|as x}{f|as x}", 11..15, ErrorKind::VariableAlreadyDefined("x".into())        ; "variable already defined")]
+        #[test_case("{f|as x|f}",       7..8,   ErrorKind::ExpectedExprEndAfterBinding               ; "pipe after binding")]
         fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
             assert_eq!(
                 Parser::new(input, &Config::fixture()).parse_items(),
@@ -247,12 +437,35 @@ mod tests {
             );
         }
 
+        #[test_case(64,   true  ; "max depth")]
+        #[test_case(65,   false ; "over max depth")]
+        #[test_case(2000, false ; "far over max depth")]
+        fn nested_depth(depth: usize, valid: bool) {
+            let input = format!("{}{{f}}{}", "{r:".repeat(depth), "}".repeat(depth));
+            let result = Parser::new(&input, &Config::fixture()).parse_items();
+
+            if valid {
+                assert!(result.is_ok());
+            } else {
+                assert_eq!(
+                    result,
+                    Err(Error {
+                        kind: ErrorKind::NestedExprTooDeep(MAX_NESTED_EXPR_DEPTH),
+                        range: 195..196,
+                    })
+                );
+            }
+        }
+
         #[test_case("",                          Vec::new()              ; "empty ")]
         #[test_case("a",                         constant()              ; "constant ")]
         #[test_case("{}",                        empty_expr()            ; "empty expr ")]
         #[test_case("{f}",                       expr_single_filter()    ; "expr single filter ")]
         #[test_case("{e|t|#1-3}",                expr_multiple_filters() ; "expr multiple filters ")]
         #[test_case("image_{c|<3:0}.{e|v|r_e}2", complex_pattern()       ; "complex pattern ")]
+        #[test_case("{f|as x}-{$x}",             variable_pattern()      ; "variable pattern ")]
+        #[test_case("{r:_:{d}|as y}",            nested_pattern()        ; "nested pattern ")]
+        #[test_case("{r:a:x as y}",              literal_binding()       ; "literal binding ")]
         fn ok(input: &str, output: Vec<ParsedItem>) {
             assert_eq!(
                 Parser::new(input, &Config::fixture()).parse_items(),
@@ -356,5 +569,73 @@ mod tests {
                 },
             ]
         }
+
+        fn variable_pattern() -> Vec<ParsedItem> {
+            vec![
+                Parsed {
+                    value: Item::Binding(
+                        vec![Parsed {
+                            value: Filter::FileName,
+                            range: 1..2,
+                        }],
+                        Parsed {
+                            value: Binding("x".into()),
+                            range: 3..7,
+                        },
+                    ),
+                    range: 0..8,
+                },
+                Parsed {
+                    value: Item::Constant("-".into()),
+                    range: 8..9,
+                },
+                Parsed {
+                    value: Item::Expression(vec![Parsed {
+                        value: Filter::Variable("x".into()),
+                        range: 10..12,
+                    }]),
+                    range: 9..13,
+                },
+            ]
+        }
+
+        fn nested_pattern() -> Vec<ParsedItem> {
+            let parts = vec![
+                Part::Chars("r:_:".chars().map(Char::Raw).collect()),
+                Part::Expression(Parsed {
+                    value: Expression(vec![Parsed {
+                        value: Filter::ParentDirectory,
+                        range: 6..7,
+                    }]),
+                    range: 5..8,
+                }),
+            ];
+            vec![Parsed {
+                value: Item::Binding(
+                    vec![Parsed {
+                        value: Filter::Template(Template::new(parts, &Config::fixture()).unwrap()),
+                        range: 1..8,
+                    }],
+                    Parsed {
+                        value: Binding("y".into()),
+                        range: 9..13,
+                    },
+                ),
+                range: 0..14,
+            }]
+        }
+
+        fn literal_binding() -> Vec<ParsedItem> {
+            vec![Parsed {
+                value: Item::Expression(vec![Parsed {
+                    value: Filter::ReplaceFirst(Substitution {
+                        target: "a".into(),
+                        replacement: "x as y".into(),
+                    }),
+                    range: 1..11,
+                }]),
+                range: 0..12,
+            }]
+        }
     }
 }
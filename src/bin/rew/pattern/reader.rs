@@ -52,11 +52,11 @@ impl<T: AsChar> Reader<T> {
         self.peek().map(T::as_char)
     }
 
-    pub fn peek_to_end(&self) -> Chars<T> {
+    pub fn peek_to_end(&self) -> Chars<'_, T> {
         self.peek_to_end_at(self.index)
     }
 
-    fn peek_to_end_at(&self, index: usize) -> Chars<T> {
+    fn peek_to_end_at(&self, index: usize) -> Chars<'_, T> {
         Chars::from(&self.chars[index..])
     }
 
@@ -80,15 +80,15 @@ impl<T: AsChar> Reader<T> {
         }
     }
 
-    pub fn read_to_end(&mut self) -> Chars<T> {
+    pub fn read_to_end(&mut self) -> Chars<'_, T> {
         let index = self.index;
         self.seek_to_end();
         self.peek_to_end_at(index)
     }
 
-    pub fn read_until(&mut self, delimiter: &T) -> Chars<T> {
+    pub fn read_until(&mut self, delimiter: &T) -> Chars<'_, T> {
         for i in self.index..self.chars.len() {
-            if self.chars[i].as_char() == delimiter.as_char() {
+            if !self.chars[i].is_literal() && self.chars[i].as_char() == delimiter.as_char() {
                 let index = self.index;
                 self.seek_to(i + 1);
                 return Chars::from(&self.chars[index..i]);
@@ -236,6 +236,14 @@ mod tests {
         assert_eq!(reader.position(), position);
     }
 
+    #[test]
+    fn read_until_skips_literal() {
+        let chars = [Char::Literal(':'), Char::Raw(':'), Char::Raw('a')];
+        let mut reader = Reader::new(chars.to_vec());
+        assert_eq!(reader.read_until(&':'.into()), chars[..1].into());
+        assert_eq!(reader.position(), 2);
+    }
+
     fn make_reader_at(index: usize) -> Reader<Char> {
         let mut reader = Reader::new(CHARS.into());
         if index > 0 {
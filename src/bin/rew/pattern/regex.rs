@@ -1,12 +1,13 @@
 use std::borrow::Cow;
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use std::fmt;
 use std::ops::Deref;
 
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::pattern::char::Char;
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::glob::{escape_glob_char, GlobHolder};
 use crate::pattern::parse::ErrorKind::ExpectedRegexMatcher;
 use crate::pattern::parse::{Error, ErrorKind, Result};
 use crate::pattern::range::{Range, RangeType};
@@ -17,7 +18,7 @@ lazy_static! {
     static ref CAPTURE_GROUP_VAR_REGEX: Regex = Regex::new(r"\$(\d+)").unwrap();
 }
 
-pub fn add_capture_group_brackets(string: &str) -> Cow<str> {
+pub fn add_capture_group_brackets(string: &str) -> Cow<'_, str> {
     if string.contains('$') {
         CAPTURE_GROUP_VAR_REGEX.replace_all(string, r"$${${1}}")
     } else {
@@ -25,11 +26,62 @@ pub fn add_capture_group_brackets(string: &str) -> Cow<str> {
     }
 }
 
+// Characters inserted from nested expressions always match only themselves
+pub fn pattern_from_chars(chars: &[Char], syntax: Syntax) -> String {
+    let mut pattern = String::new();
+
+    for char in chars {
+        match (char, syntax) {
+            (Char::Literal(value), Syntax::Regex) => {
+                pattern.push_str(&regex::escape(value.encode_utf8(&mut [0; 4])))
+            }
+            (Char::Literal(value), Syntax::Glob) => pattern.push_str(&escape_glob_char(*value)),
+            (char, _) => pattern.push(char.as_char()),
+        }
+    }
+
+    pattern
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Syntax {
+    Regex,
+    Glob,
+}
+
+impl Syntax {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Regex => "Regular expression",
+            Self::Glob => "Glob",
+        }
+    }
+}
+
+impl fmt::Display for Syntax {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Regex => write!(formatter, "regular expression"),
+            Self::Glob => write!(formatter, "glob"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct RegexHolder(pub Regex);
+pub struct RegexHolder {
+    pub regex: Regex,
+    pub glob: Option<String>,
+}
 
 impl RegexHolder {
-    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+    pub fn new(value: String, syntax: Syntax) -> std::result::Result<Self, ErrorKind> {
+        match syntax {
+            Syntax::Regex => Self::try_from(value),
+            Syntax::Glob => GlobHolder::try_from(value).map(Self::from),
+        }
+    }
+
+    pub fn parse(reader: &mut Reader<Char>, syntax: Syntax) -> Result<Self> {
         let value_start = reader.position();
         let value = reader.read_to_end();
 
@@ -39,18 +91,41 @@ impl RegexHolder {
                 range: value_start..value_start,
             })
         } else {
-            value.to_string().try_into().map_err(|kind| Error {
+            Self::new(pattern_from_chars(&value, syntax), syntax).map_err(|kind| Error {
                 kind,
                 range: value_start..reader.position(),
             })
         }
     }
+
+    pub fn syntax(&self) -> Syntax {
+        if self.glob.is_some() {
+            Syntax::Glob
+        } else {
+            Syntax::Regex
+        }
+    }
 }
 
 #[cfg(test)]
 impl From<&str> for RegexHolder {
     fn from(value: &str) -> Self {
-        Self(Regex::new(value).unwrap())
+        Self::from(Regex::new(value).unwrap())
+    }
+}
+
+impl From<Regex> for RegexHolder {
+    fn from(regex: Regex) -> Self {
+        Self { regex, glob: None }
+    }
+}
+
+impl From<GlobHolder> for RegexHolder {
+    fn from(holder: GlobHolder) -> Self {
+        Self {
+            regex: holder.regex,
+            glob: Some(holder.glob),
+        }
     }
 }
 
@@ -59,7 +134,7 @@ impl TryFrom<String> for RegexHolder {
 
     fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
         match Regex::new(&value) {
-            Ok(regex) => Ok(Self(regex)),
+            Ok(regex) => Ok(Self::from(regex)),
             Err(error) => Err(ErrorKind::RegexInvalid(AnyString(error.to_string()))),
         }
     }
@@ -69,19 +144,22 @@ impl Deref for RegexHolder {
     type Target = Regex;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.regex
     }
 }
 
 impl PartialEq for RegexHolder {
     fn eq(&self, other: &Self) -> bool {
-        self.0.as_str() == other.0.as_str()
+        self.regex.as_str() == other.regex.as_str() && self.glob == other.glob
     }
 }
 
 impl fmt::Display for RegexHolder {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        self.0.fmt(formatter)
+        match &self.glob {
+            Some(glob) => glob.fmt(formatter),
+            None => self.regex.fmt(formatter),
+        }
     }
 }
 
@@ -116,11 +194,11 @@ pub struct RegexMatcher {
 }
 
 impl RegexMatcher {
-    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+    pub fn parse(reader: &mut Reader<Char>, syntax: Syntax) -> Result<Self> {
         if reader.peek().is_some() {
             let range = Range::parse(reader)?;
             if reader.read().is_some() {
-                let regex = RegexHolder::parse(reader)?;
+                let regex = RegexHolder::parse(reader, syntax)?;
                 Ok(Self { range, regex })
             } else {
                 Err(Error {
@@ -196,11 +274,55 @@ mod tests {
         assert_eq!(super::add_capture_group_brackets(input), output)
     }
 
+    mod syntax {
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case(Syntax::Regex, "Regular expression" ; "regex")]
+        #[test_case(Syntax::Glob,  "Glob"               ; "glob")]
+        fn title(syntax: Syntax, result: &str) {
+            assert_eq!(syntax.title(), result);
+        }
+
+        #[test_case(Syntax::Regex, "regular expression" ; "regex")]
+        #[test_case(Syntax::Glob,  "glob"               ; "glob")]
+        fn display(syntax: Syntax, result: &str) {
+            assert_eq!(syntax.to_string(), result);
+        }
+    }
+
     mod regex_holder {
         use test_case::test_case;
 
         use super::*;
 
+        mod new {
+            use super::*;
+
+            #[test]
+            fn regex() {
+                let holder = RegexHolder::new(String::from("a.c"), Syntax::Regex).unwrap();
+                assert_eq!(holder.syntax(), Syntax::Regex);
+                assert_eq!(holder.as_str(), "a.c");
+            }
+
+            #[test]
+            fn glob() {
+                let holder = RegexHolder::new(String::from("*.c"), Syntax::Glob).unwrap();
+                assert_eq!(holder.syntax(), Syntax::Glob);
+                assert_eq!(holder.as_str(), r"^(.*)\.c$");
+            }
+
+            #[test]
+            fn glob_err() {
+                assert_eq!(
+                    RegexHolder::new(String::from("[z-a]"), Syntax::Glob),
+                    Err(ErrorKind::GlobInvalid(AnyString::any()))
+                );
+            }
+        }
+
         mod try_from {
             use test_case::test_case;
 
@@ -234,7 +356,7 @@ mod tests {
             #[test_case("[0-9", 0..4, ErrorKind::RegexInvalid(AnyString::any()) ; "invalid")]
             fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
                 assert_eq!(
-                    RegexHolder::parse(&mut Reader::from(input)),
+                    RegexHolder::parse(&mut Reader::from(input), Syntax::Regex),
                     Err(Error { kind, range })
                 );
             }
@@ -242,10 +364,18 @@ mod tests {
             #[test]
             fn ok() {
                 assert_eq!(
-                    RegexHolder::parse(&mut Reader::from("[0-9]")),
+                    RegexHolder::parse(&mut Reader::from("[0-9]"), Syntax::Regex),
                     Ok("[0-9]".into())
                 );
             }
+
+            #[test]
+            fn ok_glob() {
+                assert_eq!(
+                    RegexHolder::parse(&mut Reader::from("*.txt"), Syntax::Glob),
+                    Ok(GlobHolder::from("*.txt").into())
+                );
+            }
         }
 
         #[test_case("",       "",       true  ; "empty")]
@@ -256,8 +386,17 @@ mod tests {
         }
 
         #[test]
-        fn display() {
-            assert_eq!(RegexHolder::from("[a-z]+").to_string(), "[a-z]+");
+        fn partial_eq_glob() {
+            assert_ne!(
+                RegexHolder::from(GlobHolder::from("*")),
+                RegexHolder::from("^(.*)$")
+            );
+        }
+
+        #[test_case(RegexHolder::from("[a-z]+"),            "[a-z]+" ; "regex")]
+        #[test_case(RegexHolder::from(GlobHolder::from("*.txt")), "*.txt"  ; "glob")]
+        fn display(holder: RegexHolder, result: &str) {
+            assert_eq!(holder.to_string(), result);
         }
     }
 
@@ -322,7 +461,7 @@ mod tests {
             #[test_case("2-3:[a-z]+", 1, Some(3), "[a-z]+" ; "start below end")]
             fn ok(input: &str, start: usize, end: Option<usize>, regex: &str) {
                 assert_eq!(
-                    RegexMatcher::parse(&mut Reader::from(input)),
+                    RegexMatcher::parse(&mut Reader::from(input), Syntax::Regex),
                     Ok(RegexMatcher {
                         range: Range::new(start, end),
                         regex: regex.into()
@@ -337,7 +476,7 @@ mod tests {
             #[test_case("1:[0-9", 2..6, ErrorKind::RegexInvalid(AnyString::any()) ; "nonempty range invalid regex")]
             fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
                 assert_eq!(
-                    RegexMatcher::parse(&mut Reader::from(input)),
+                    RegexMatcher::parse(&mut Reader::from(input), Syntax::Regex),
                     Err(Error { kind, range })
                 );
             }
@@ -1,11 +1,10 @@
-use std::convert::{TryFrom, TryInto};
 use std::fmt;
 
-use crate::pattern::char::Char;
+use crate::pattern::char::{Char, Chars};
 use crate::pattern::escape::escape_str;
 use crate::pattern::parse::{Error, ErrorKind, Result};
 use crate::pattern::reader::Reader;
-use crate::pattern::regex::{add_capture_group_brackets, RegexHolder};
+use crate::pattern::regex::{add_capture_group_brackets, pattern_from_chars, RegexHolder, Syntax};
 use crate::pattern::utils::Empty;
 
 #[derive(Debug, PartialEq)]
@@ -34,8 +33,11 @@ impl EmptySubstitution {
     }
 }
 
-impl<E: Into<ErrorKind>, T: TryFrom<String, Error = E>> Substitution<T> {
-    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+impl<T> Substitution<T> {
+    fn parse_with<F>(reader: &mut Reader<Char>, make_target: F) -> Result<Self>
+    where
+        F: FnOnce(&[Char]) -> std::result::Result<T, ErrorKind>,
+    {
         if let Some(delimiter) = reader.read().cloned() {
             let target_start = reader.position();
             let target = reader.read_until(&delimiter);
@@ -48,13 +50,13 @@ impl<E: Into<ErrorKind>, T: TryFrom<String, Error = E>> Substitution<T> {
                 });
             }
 
-            match target.to_string().try_into() {
+            match make_target(&target) {
                 Ok(target) => Ok(Self {
                     target,
                     replacement: reader.read_to_end().to_string(),
                 }),
-                Err(error) => Err(Error {
-                    kind: error.into(),
+                Err(kind) => Err(Error {
+                    kind,
                     range: target_start..target_end,
                 }),
             }
@@ -68,6 +70,10 @@ impl<E: Into<ErrorKind>, T: TryFrom<String, Error = E>> Substitution<T> {
 }
 
 impl StringSubstitution {
+    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+        Self::parse_with(reader, |target| Ok(Chars::from(target).to_string()))
+    }
+
     pub fn replace_first(&self, value: &str) -> String {
         value.replacen(&self.target, &self.replacement, 1)
     }
@@ -78,6 +84,12 @@ impl StringSubstitution {
 }
 
 impl RegexSubstitution {
+    pub fn parse(reader: &mut Reader<Char>, syntax: Syntax) -> Result<Self> {
+        Self::parse_with(reader, |target| {
+            RegexHolder::new(pattern_from_chars(target, syntax), syntax)
+        })
+    }
+
     pub fn replace_first(&self, value: &str) -> String {
         let replacement = add_capture_group_brackets(&self.replacement);
         self.target.replace(value, replacement.as_ref()).to_string()
@@ -272,7 +284,7 @@ mod tests {
             #[test_case("/[0-9+/def", 1..6, ErrorKind::RegexInvalid(AnyString::any())        ; "invalid regex")]
             fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
                 assert_eq!(
-                    RegexSubstitution::parse(&mut Reader::from(input)),
+                    RegexSubstitution::parse(&mut Reader::from(input), Syntax::Regex),
                     Err(Error { kind, range })
                 );
             }
@@ -281,9 +293,10 @@ mod tests {
             #[test_case("/(\\d+)/",     "(\\d+)", ""     ; "empty replacement")]
             #[test_case("/(\\d+)/_$1_", "(\\d+)", "_$1_" ; "nonempty replacement")]
             #[test_case("/(\\d+)//$1/", "(\\d+)", "/$1/" ; "replacement containing delimiter")]
+            #[test_case("*b*X",         "b",      "X"    ; "star delimiter")]
             fn ok(input: &str, target: &str, replacement: &str) {
                 assert_eq!(
-                    RegexSubstitution::parse(&mut Reader::from(input)),
+                    RegexSubstitution::parse(&mut Reader::from(input), Syntax::Regex),
                     Ok(Substitution {
                         target: target.into(),
                         replacement: replacement.into(),
@@ -0,0 +1,284 @@
+use std::fmt;
+use std::path::is_separator;
+
+use unidecode::unidecode;
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::parse;
+use crate::pattern::reader::Reader;
+
+const MAX_NAME_BYTES: usize = 255;
+const WINDOWS_RESERVED_CHARS: &str = "<>:\"\\|?*";
+const FAT32_RESERVED_CHARS: &str = "+,;=[]";
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+#[derive(Debug, PartialEq)]
+pub enum Profile {
+    Posix,
+    Windows,
+    Fat32,
+    Portable,
+    Slug,
+}
+
+impl Profile {
+    pub fn parse(reader: &mut Reader<Char>) -> parse::Result<Self> {
+        let position = reader.position();
+        let char = reader.read();
+
+        match char.map(Char::as_char) {
+            Some('u') => Ok(Self::Posix),
+            Some('w') => Ok(Self::Windows),
+            Some('f') => Ok(Self::Fat32),
+            Some('p') => Ok(Self::Portable),
+            Some('s') => Ok(Self::Slug),
+            _ => Err(parse::Error {
+                kind: parse::ErrorKind::ExpectedSanitizeProfile(char.cloned()),
+                range: position..reader.position(),
+            }),
+        }
+    }
+
+    // Directory separators are kept, so a whole path can be sanitized at once
+    pub fn apply(&self, value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        let mut start = 0;
+
+        for (index, char) in value.char_indices() {
+            if is_separator(char) {
+                result.push_str(&self.sanitize_name(&value[start..index]));
+                result.push(char);
+                start = index + char.len_utf8();
+            }
+        }
+
+        result.push_str(&self.sanitize_name(&value[start..]));
+        result
+    }
+
+    fn sanitize_name(&self, name: &str) -> String {
+        if name.is_empty() || (*self != Self::Slug && (name == "." || name == "..")) {
+            return name.into();
+        }
+
+        let name = match self {
+            Self::Portable | Self::Slug => unidecode(name),
+            _ => name.into(),
+        };
+
+        let replacement = self.replacement();
+        let mut result = String::with_capacity(name.len());
+        let mut replaced = false;
+
+        for char in name.chars() {
+            if !self.is_allowed(char) {
+                // Replacement of multiple consecutive chars is collapsed into one
+                if !result.ends_with(replacement) {
+                    result.push(replacement);
+                }
+                replaced = true;
+            } else if char != replacement || !replaced {
+                result.push(if *self == Self::Slug {
+                    char.to_ascii_lowercase()
+                } else {
+                    char
+                });
+                replaced = false;
+            }
+        }
+
+        let mut result = self.trim(&result).to_string();
+
+        if result.len() > MAX_NAME_BYTES {
+            result = self
+                .trim(&truncate_name(&result, MAX_NAME_BYTES))
+                .to_string();
+        }
+
+        if result.is_empty() && *self != Self::Slug {
+            result.push(replacement);
+        }
+
+        if self.has_reserved_names() {
+            let stem_end = result.find('.').unwrap_or(result.len());
+            let stem = &result[..stem_end];
+
+            if WINDOWS_RESERVED_NAMES
+                .iter()
+                .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+            {
+                result.insert(stem_end, replacement);
+            }
+        }
+
+        result
+    }
+
+    fn is_allowed(&self, char: char) -> bool {
+        match self {
+            Self::Posix => !char.is_control(),
+            Self::Windows => !char.is_control() && !WINDOWS_RESERVED_CHARS.contains(char),
+            Self::Fat32 => {
+                !char.is_control()
+                    && !WINDOWS_RESERVED_CHARS.contains(char)
+                    && !FAT32_RESERVED_CHARS.contains(char)
+            }
+            Self::Portable => char.is_ascii_alphanumeric() || "._-".contains(char),
+            Self::Slug => char.is_ascii_alphanumeric(),
+        }
+    }
+
+    fn trim<'a>(&self, name: &'a str) -> &'a str {
+        match self {
+            Self::Posix => name,
+            // Windows silently strips trailing dots and spaces
+            Self::Windows | Self::Fat32 => name.trim_end_matches(&['.', ' '][..]),
+            // Leading dash would be interpreted as an option by most commands
+            Self::Portable => name
+                .trim_start_matches('-')
+                .trim_end_matches(&['.', ' '][..]),
+            Self::Slug => name.trim_matches('-'),
+        }
+    }
+
+    fn replacement(&self) -> char {
+        match self {
+            Self::Slug => '-',
+            _ => '_',
+        }
+    }
+
+    fn has_reserved_names(&self) -> bool {
+        matches!(self, Self::Windows | Self::Fat32 | Self::Portable)
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Posix => write!(formatter, "POSIX"),
+            Self::Windows => write!(formatter, "Windows"),
+            Self::Fat32 => write!(formatter, "FAT32"),
+            Self::Portable => write!(formatter, "portable"),
+            Self::Slug => write!(formatter, "URL slug"),
+        }
+    }
+}
+
+// Extension is preserved when possible, only the base name is shortened
+fn truncate_name(name: &str, max_bytes: usize) -> String {
+    let extension = match name.rfind('.') {
+        Some(index) if index > 0 && name.len() - index < max_bytes => &name[index..],
+        _ => "",
+    };
+
+    let base = &name[..name.len() - extension.len()];
+    let mut base_end = max_bytes - extension.len();
+
+    while !base.is_char_boundary(base_end) {
+        base_end -= 1;
+    }
+
+    format!("{}{}", &base[..base_end], extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",  0..0, ErrorKind::ExpectedSanitizeProfile(None)             ; "none")]
+        #[test_case("x", 0..1, ErrorKind::ExpectedSanitizeProfile(Some('x'.into())) ; "invalid")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                Profile::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("u",  Profile::Posix    ; "posix")]
+        #[test_case("w",  Profile::Windows  ; "windows")]
+        #[test_case("f",  Profile::Fat32    ; "fat32")]
+        #[test_case("p",  Profile::Portable ; "portable")]
+        #[test_case("s",  Profile::Slug     ; "slug")]
+        #[test_case("wx", Profile::Windows  ; "chars after")]
+        fn ok(input: &str, profile: Profile) {
+            let mut reader = Reader::from(input);
+            assert_eq!(Profile::parse(&mut reader), Ok(profile));
+            assert_eq!(reader.position(), 1);
+        }
+    }
+
+    #[test_case(Profile::Posix,    "",                  ""                ; "posix empty")]
+    #[test_case(Profile::Posix,    "a<b>:c?.txt ",      "a<b>:c?.txt "    ; "posix allowed")]
+    #[test_case(Profile::Posix,    "a\nb\0c",           "a_b_c"           ; "posix control")]
+    #[test_case(Profile::Posix,    "dir/./../a\tb",     "dir/./../a_b"    ; "posix path")]
+    #[test_case(Profile::Windows,  "a<b>c:d\"e|f?g*h",  "a_b_c_d_e_f_g_h" ; "windows reserved")]
+    #[test_case(Profile::Windows,  "a<>b_c",            "a_b_c"           ; "windows collapsed")]
+    #[test_case(Profile::Windows,  "a_<b",              "a_b"             ; "windows collapsed existing")]
+    #[test_case(Profile::Windows,  "__init__.py",       "__init__.py"     ; "windows existing kept")]
+    #[test_case(Profile::Windows,  "name. . ",          "name"            ; "windows trailing")]
+    #[test_case(Profile::Windows,  "...",               "_"               ; "windows trailing only")]
+    #[test_case(Profile::Windows,  "con",               "con_"            ; "windows device")]
+    #[test_case(Profile::Windows,  "LPT1.tar.gz",       "LPT1_.tar.gz"    ; "windows device extension")]
+    #[test_case(Profile::Windows,  "CONSOLE.txt",       "CONSOLE.txt"     ; "windows device prefix")]
+    #[test_case(Profile::Windows,  "a?/b./c",           "a_/b/c"          ; "windows path")]
+    #[test_case(Profile::Fat32,    "a+b,c;d=e[f]g",     "a_b_c_d_e_f_g"   ; "fat32 reserved")]
+    #[test_case(Profile::Fat32,    "a<b.",              "a_b"             ; "fat32 windows rules")]
+    #[test_case(Profile::Portable, "Příliš žluťoučký",  "Prilis_zlutoucky" ; "portable transliterated")]
+    #[test_case(Profile::Portable, "a (1) & b.txt",     "a_1_b.txt"       ; "portable replaced")]
+    #[test_case(Profile::Portable, "--help",            "help"            ; "portable leading dash")]
+    #[test_case(Profile::Portable, "aux.c",             "aux_.c"          ; "portable device")]
+    #[test_case(Profile::Slug,     "Hello, World!",     "hello-world"     ; "slug")]
+    #[test_case(Profile::Slug,     "--Čau  světe--",    "cau-svete"       ; "slug trimmed")]
+    #[test_case(Profile::Slug,     "my.file_name.txt",  "my-file-name-txt" ; "slug dots")]
+    #[test_case(Profile::Slug,     "?!",                ""                ; "slug empty")]
+    #[test_case(Profile::Slug,     "A B/C D",           "a-b/c-d"         ; "slug path")]
+    fn apply(profile: Profile, input: &str, output: &str) {
+        assert_eq!(profile.apply(input), output);
+    }
+
+    #[test]
+    fn apply_long_name() {
+        let name = format!("{}.txt", "a".repeat(300));
+        let result = Profile::Posix.apply(&name);
+        assert_eq!(result, format!("{}.txt", "a".repeat(251)));
+    }
+
+    #[test]
+    fn apply_long_components() {
+        let name = format!("{}/{}", "a".repeat(256), "b".repeat(256));
+        let result = Profile::Windows.apply(&name);
+        assert_eq!(result, format!("{}/{}", "a".repeat(255), "b".repeat(255)));
+    }
+
+    #[test_case("a.txt",                         5, "a.txt"   ; "short")]
+    #[test_case("abcdef",                        4, "abcd"    ; "no extension")]
+    #[test_case("abcdef.txt",                    6, "ab.txt"  ; "extension")]
+    #[test_case("a.abcdef",                      4, "a.ab"    ; "long extension")]
+    #[test_case(".abcdef",                       4, ".abc"    ; "hidden")]
+    #[test_case("ččč.x",                         5, "č.x"     ; "char boundary")]
+    fn truncate(name: &str, max_bytes: usize, result: &str) {
+        assert_eq!(truncate_name(name, max_bytes), result);
+    }
+
+    #[test_case(Profile::Posix,    "POSIX"    ; "posix")]
+    #[test_case(Profile::Windows,  "Windows"  ; "windows")]
+    #[test_case(Profile::Fat32,    "FAT32"    ; "fat32")]
+    #[test_case(Profile::Portable, "portable" ; "portable")]
+    #[test_case(Profile::Slug,     "URL slug" ; "slug")]
+    fn display(profile: Profile, result: &str) {
+        assert_eq!(profile.to_string(), result);
+    }
+}
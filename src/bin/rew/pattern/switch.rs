@@ -1,12 +1,11 @@
 use std::borrow::Cow;
-use std::convert::TryInto;
 use std::fmt;
 
-use crate::pattern::char::{AsChar, Char};
+use crate::pattern::char::{AsChar, Char, Chars};
 use crate::pattern::escape::escape_str;
 use crate::pattern::parse::{Error, ErrorKind, Result};
 use crate::pattern::reader::Reader;
-use crate::pattern::regex::{add_capture_group_brackets, RegexHolder};
+use crate::pattern::regex::{add_capture_group_brackets, pattern_from_chars, RegexHolder, Syntax};
 
 #[derive(Debug, PartialEq)]
 pub struct RegexSwitch {
@@ -21,15 +20,14 @@ pub struct Case {
 }
 
 impl RegexSwitch {
-    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+    pub fn parse(reader: &mut Reader<Char>, syntax: Syntax) -> Result<Self> {
         if let Some(delimiter) = reader.read().cloned() {
             let mut cases = Vec::new();
 
             loop {
                 let value_start = reader.position();
-                let value = reader.read_until(&delimiter);
-                let value_end = value_start + value.len_utf8();
-                let value = value.to_string();
+                let value = reader.read_until(&delimiter).to_vec();
+                let value_end = value_start + Chars::from(&value[..]).len_utf8();
 
                 if reader.position() > value_end {
                     if value.is_empty() {
@@ -43,7 +41,8 @@ impl RegexSwitch {
                     }
 
                     // There was a delimiter after value
-                    let matcher = value.try_into().map_err(|kind| Error {
+                    let pattern = pattern_from_chars(&value, syntax);
+                    let matcher = RegexHolder::new(pattern, syntax).map_err(|kind| Error {
                         kind,
                         range: value_start..value_end,
                     })?;
@@ -53,7 +52,7 @@ impl RegexSwitch {
                 } else {
                     return Ok(RegexSwitch {
                         cases,
-                        default: value,
+                        default: Chars::from(&value[..]).to_string(),
                     });
                 }
             }
@@ -65,6 +64,13 @@ impl RegexSwitch {
         }
     }
 
+    // Cases cannot be mixed, switch without any case never matches anything
+    pub fn syntax(&self) -> Syntax {
+        self.cases
+            .first()
+            .map_or(Syntax::Regex, |case| case.matcher.syntax())
+    }
+
     pub fn eval<'a>(&'a self, value: &'a str) -> Cow<'a, str> {
         for case in &self.cases {
             if let Some(result) = case.matcher.find(value) {
@@ -111,7 +117,10 @@ impl fmt::Display for RegexSwitch {
 
 #[cfg(test)]
 mod tests {
+    use test_case::test_case;
+
     use super::*;
+    use crate::pattern::glob::GlobHolder;
 
     mod parse {
         use test_case::test_case;
@@ -127,7 +136,7 @@ mod tests {
         #[test_case(":[a-z]:Lo:[A-Z:", 10..14, ErrorKind::RegexInvalid(AnyString::any())           ; "matcher result invalid")]
         fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
             assert_eq!(
-                RegexSwitch::parse(&mut Reader::from(input)),
+                RegexSwitch::parse(&mut Reader::from(input), Syntax::Regex),
                 Err(Error { kind, range })
             );
         }
@@ -145,7 +154,7 @@ mod tests {
         #[test_case(":[a-z]:Lo:[A-Z]:Up:Mx", &[("[a-z]", "Lo"), ("[A-Z]", "Up")], "Mx" ; "matcher result matcher result default")]
         fn ok(input: &str, cases: &[(&str, &str)], default: &str) {
             assert_eq!(
-                RegexSwitch::parse(&mut Reader::from(input)),
+                RegexSwitch::parse(&mut Reader::from(input), Syntax::Regex),
                 Ok(RegexSwitch {
                     cases: cases
                         .iter()
@@ -160,6 +169,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_glob() {
+        assert_eq!(
+            RegexSwitch::parse(&mut Reader::from(":*.jpg:image"), Syntax::Glob),
+            Ok(RegexSwitch {
+                cases: vec![Case {
+                    matcher: GlobHolder::from("*.jpg").into(),
+                    result: "image".into(),
+                }],
+                default: String::new(),
+            })
+        );
+    }
+
+    #[test_case(Vec::new(),     Syntax::Regex ; "empty")]
+    #[test_case(vec!["[a-z]"],  Syntax::Regex ; "regex")]
+    #[test_case(vec!["*.jpg"],  Syntax::Glob  ; "glob")]
+    fn syntax(matchers: Vec<&str>, syntax: Syntax) {
+        assert_eq!(
+            RegexSwitch {
+                cases: matchers
+                    .into_iter()
+                    .map(|matcher| Case {
+                        matcher: RegexHolder::new(matcher.into(), syntax).unwrap(),
+                        result: String::new(),
+                    })
+                    .collect(),
+                default: String::new(),
+            }
+            .syntax(),
+            syntax
+        );
+    }
+
     mod eval {
         use test_case::test_case;
 
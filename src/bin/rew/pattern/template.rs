@@ -0,0 +1,196 @@
+use std::fmt;
+
+use crate::pattern::char::{AsChar, Char};
+use crate::pattern::filter::Filter;
+use crate::pattern::parse::{BaseResult, Config, ErrorKind, Parsed};
+use crate::pattern::parser::ParsedFilter;
+use crate::pattern::reader::Reader;
+
+pub type ParsedExpression = Parsed<Expression>;
+
+#[derive(Debug, PartialEq)]
+pub struct Expression(pub Vec<ParsedFilter>);
+
+impl fmt::Display for Expression {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.len() {
+            0 => write!(formatter, "Empty nested expression"),
+            1 => write!(formatter, "Nested expression with a filter"),
+            count => write!(formatter, "Nested expression with {} filters", count),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Part {
+    Chars(Vec<Char>),
+    Expression(ParsedExpression),
+}
+
+// Filter which can be parsed only after its nested expressions are evaluated
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+    config: Config,
+}
+
+impl Template {
+    pub fn new(parts: Vec<Part>, config: &Config) -> BaseResult<Self> {
+        // Filter name is the only thing known before expansion
+        if let Some(Part::Chars(chars)) = parts.first() {
+            // Table would be loaded again for each input value
+            if chars[0].as_char() == 'l' {
+                return Err(ErrorKind::LookupWithNestedExpr);
+            }
+
+            let mut reader = Reader::new(chars[..1].to_vec());
+
+            if let Err(error) = Filter::parse(&mut reader, config) {
+                if let ErrorKind::UnknownFilter(_) = error.kind {
+                    return Err(error.kind);
+                }
+            }
+        }
+
+        Ok(Self {
+            parts,
+            config: config.clone(),
+        })
+    }
+
+    pub fn expressions(&self) -> impl Iterator<Item = &ParsedExpression> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Expression(expression) => Some(expression),
+            Part::Chars(_) => None,
+        })
+    }
+
+    // Values of nested expressions are inserted as literal characters,
+    // they are never unescaped nor split by delimiters and always match only themselves.
+    pub fn expand(&self, values: &[String]) -> BaseResult<Filter> {
+        let mut chars = Vec::new();
+        let mut values = values.iter();
+
+        for part in &self.parts {
+            match part {
+                Part::Chars(part_chars) => chars.extend_from_slice(part_chars),
+                Part::Expression(_) => {
+                    if let Some(value) = values.next() {
+                        chars.extend(value.chars().map(Char::Literal));
+                    }
+                }
+            }
+        }
+
+        let mut reader = Reader::new(chars);
+        let filter = Filter::parse(&mut reader, &self.config).map_err(|error| error.kind)?;
+
+        if reader.peek().is_some() {
+            Err(ErrorKind::ExpectedPipeOrExprEnd)
+        } else {
+            Ok(filter)
+        }
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.expressions().count() {
+            1 => write!(formatter, "Filter with a nested expression"),
+            count => write!(formatter, "Filter with {} nested expressions", count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::pattern::replace::Substitution;
+
+    #[test_case(Vec::new(),     "Empty nested expression"           ; "empty")]
+    #[test_case(vec![f()],      "Nested expression with a filter"   ; "single filter")]
+    #[test_case(vec![f(), f()], "Nested expression with 2 filters"  ; "multiple filters")]
+    fn expression_display(filters: Vec<ParsedFilter>, result: &str) {
+        assert_eq!(Expression(filters).to_string(), result);
+    }
+
+    #[test]
+    fn new_unknown_filter() {
+        assert_eq!(
+            Template::new(vec![chars("-"), expr()], &Config::fixture()),
+            Err(ErrorKind::UnknownFilter(Char::Raw('-')))
+        );
+    }
+
+    #[test]
+    fn new_lookup() {
+        assert_eq!(
+            Template::new(vec![chars("l:t.csv:"), expr()], &Config::fixture()),
+            Err(ErrorKind::LookupWithNestedExpr)
+        );
+    }
+
+    #[test]
+    fn new_incomplete_filter() {
+        assert!(Template::new(vec![chars("r"), expr()], &Config::fixture()).is_ok());
+    }
+
+    #[test]
+    fn expressions() {
+        let template = template(vec![chars("r:"), expr(), chars(":"), expr()]);
+        assert_eq!(template.expressions().count(), 2);
+    }
+
+    #[test_case(vec![chars("r:"), expr()],              &["a"],      Ok(replace("a", ""))            ; "single")]
+    #[test_case(vec![chars("r:"), expr(), chars(":b")], &["a"],      Ok(replace("a", "b"))           ; "inside")]
+    #[test_case(vec![chars("r:a:"), expr(), expr()],    &["b", "c"], Ok(replace("a", "bc"))          ; "multiple")]
+    #[test_case(vec![chars("r:a:"), expr()],            &["%n"],     Ok(replace("a", "%n"))          ; "not unescaped")]
+    #[test_case(vec![chars("r:"), expr(), chars(":b")], &["a:c"],    Ok(replace("a:c", "b"))         ; "delimiter in value")]
+    #[test_case(vec![chars("s:"), expr(), chars(":b")], &["a(c"],    Ok(regex_replace(r"a\(c", "b")) ; "regex in value")]
+    #[test_case(vec![chars("r"), expr()],               &[""],       Err(E::ExpectedSubstitution)    ; "invalid")]
+    #[test_case(vec![chars("t"), expr()],               &["x"],      Err(E::ExpectedPipeOrExprEnd)   ; "remaining chars")]
+    fn expand(parts: Vec<Part>, values: &[&str], result: BaseResult<Filter>) {
+        let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        assert_eq!(template(parts).expand(&values), result);
+    }
+
+    #[test_case(vec![chars("r:"), expr()],          "Filter with a nested expression"   ; "single")]
+    #[test_case(vec![chars("r:"), expr(), expr()],  "Filter with 2 nested expressions"  ; "multiple")]
+    fn display(parts: Vec<Part>, result: &str) {
+        assert_eq!(template(parts).to_string(), result);
+    }
+
+    type E = ErrorKind;
+
+    fn f() -> ParsedFilter {
+        Parsed::from(Filter::Trim)
+    }
+
+    fn chars(value: &str) -> Part {
+        Part::Chars(value.chars().map(Char::Raw).collect())
+    }
+
+    fn expr() -> Part {
+        Part::Expression(Parsed::from(Expression(Vec::new())))
+    }
+
+    fn template(parts: Vec<Part>) -> Template {
+        Template::new(parts, &Config::fixture()).unwrap()
+    }
+
+    fn regex_replace(target: &str, replacement: &str) -> Filter {
+        Filter::RegexReplaceFirst(Substitution {
+            target: target.into(),
+            replacement: replacement.into(),
+        })
+    }
+
+    fn replace(target: &str, replacement: &str) -> Filter {
+        Filter::ReplaceFirst(Substitution {
+            target: target.into(),
+            replacement: replacement.into(),
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+
+use crate::pattern::char::Char;
+use crate::pattern::reader::Reader;
+
+const BINDING_KEYWORD: [char; 2] = ['a', 's'];
+
+#[derive(Debug, PartialEq)]
+pub struct Binding(pub String);
+
+impl fmt::Display for Binding {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Bind result to variable '{}'", self.0)
+    }
+}
+
+pub fn is_name_start(char: char) -> bool {
+    char.is_ascii_alphabetic() || char == '_'
+}
+
+fn is_name_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+pub fn parse_name(reader: &mut Reader<Char>) -> String {
+    let mut name = String::new();
+
+    while let Some(char) = reader.peek_char().filter(|char| is_name_char(*char)) {
+        name.push(char);
+        reader.seek();
+    }
+
+    name
+}
+
+// Checks whether a filter is an unescaped `as NAME` binding and returns index where the name starts.
+// It can never be a valid filter, because `a` filter has no parameters.
+pub fn find_binding(chars: &[Char]) -> Option<usize> {
+    let is_raw = |index: usize, test: &dyn Fn(char) -> bool| match chars.get(index) {
+        Some(Char::Raw(char)) => test(*char),
+        _ => false,
+    };
+
+    for (index, expected) in BINDING_KEYWORD.iter().enumerate() {
+        if !is_raw(index, &|char| char == *expected) {
+            return None;
+        }
+    }
+
+    let mut name_start = BINDING_KEYWORD.len();
+
+    while is_raw(name_start, &char::is_whitespace) {
+        name_start += 1;
+    }
+
+    if name_start == BINDING_KEYWORD.len() || !is_raw(name_start, &is_name_start) {
+        return None;
+    }
+
+    if (name_start..chars.len()).all(|index| is_raw(index, &is_name_char)) {
+        Some(name_start)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("a",        None    ; "no binding")]
+    #[test_case("as",       None    ; "missing name")]
+    #[test_case("as 1",     None    ; "invalid name")]
+    #[test_case("as x-y",   None    ; "invalid name char")]
+    #[test_case("asx",      None    ; "missing space after keyword")]
+    #[test_case("v as x",   None    ; "filter before keyword")]
+    #[test_case(" as x",    None    ; "space before keyword")]
+    #[test_case("as x",     Some(3) ; "binding")]
+    #[test_case("as \tx_1", Some(4) ; "more white-spaces")]
+    fn find(input: &str, result: Option<usize>) {
+        let chars: Vec<Char> = input.chars().map(Char::Raw).collect();
+        assert_eq!(find_binding(&chars), result);
+    }
+
+    #[test]
+    fn find_escaped() {
+        let chars = vec![
+            Char::Raw('a'),
+            Char::Raw('s'),
+            Char::Escaped('\t', ['%', 't']),
+            Char::Raw('x'),
+        ];
+        assert_eq!(find_binding(&chars), None);
+    }
+
+    #[test_case("",       ""     ; "empty")]
+    #[test_case("abc",    "abc"  ; "name")]
+    #[test_case("a_1|b",  "a_1"  ; "name then other")]
+    #[test_case("-",      ""     ; "invalid")]
+    fn parse(input: &str, result: &str) {
+        assert_eq!(parse_name(&mut Reader::from(input)), result);
+    }
+
+    #[test]
+    fn binding_display() {
+        assert_eq!(
+            Binding("abc".into()).to_string(),
+            "Bind result to variable 'abc'"
+        );
+    }
+}
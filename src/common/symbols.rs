@@ -1,2 +1,5 @@
 pub const DIFF_IN: char = '<';
 pub const DIFF_OUT: char = '>';
+pub const JOURNAL_DIR: char = '+';
+pub const JOURNAL_BACKUP: char = '~';
+pub const JOURNAL_OVERWRITE: char = '!';
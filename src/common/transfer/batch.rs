@@ -0,0 +1,340 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use fs_extra::error::{Error, Result};
+use same_file::is_same_file;
+use termcolor::StandardStreamLock;
+
+use crate::output::write_error;
+use crate::run::{Io, EXIT_CODE_IO_ERROR, EXIT_CODE_OK};
+use crate::transfer::fs::{
+    check_overwrite, check_types, find_missing_dirs, make_temp_path, remove_path, rename_path,
+    CopyProgress, FileType, Overwrite, OverwritePolicy, SymlinkMode, TransferMode,
+};
+use crate::transfer::journal::{check_journal_path, Journal, JournalEntry};
+use crate::transfer::output::TransferLog;
+use crate::transfer::progress::Progress;
+use crate::transfer::prompt::Prompt;
+use crate::transfer::report::{TransferReport, TransferStats, TransferStatus};
+use crate::transfer::run::TransferOptions;
+use crate::transfer::undo::Undo;
+
+// Missing parent directories are present only if the transfer is recorded
+pub enum Preparation {
+    Skipped,
+    Ready(Option<Vec<PathBuf>>),
+    Failed(Option<Vec<PathBuf>>, Error),
+}
+
+// Everything around transfers of individual paths (overwrite policy, logging, progress, report, journal, error handling).
+pub struct TransferBatch<'a, O: TransferOptions> {
+    options: &'a O,
+    io: &'a Io,
+    mode: TransferMode,
+    log: TransferLog<StandardStreamLock<'a>>,
+    journal: Option<Journal<BufWriter<File>>>,
+    overwrite_policy: OverwritePolicy,
+    prompt: Prompt,
+    progress: Option<Progress>,
+    report: Option<TransferReport<StandardStreamLock<'a>>>,
+    completed_entries: Vec<JournalEntry>,
+    temp_paths: Vec<PathBuf>, // Overwritten paths kept aside until the transaction commits
+    rolled_back: bool,
+    exit_code: i32,
+}
+
+impl<'a, O: TransferOptions> TransferBatch<'a, O> {
+    pub fn new(
+        options: &'a O,
+        io: &'a Io,
+        mode: TransferMode,
+        progress: Option<Progress>,
+    ) -> io::Result<Self> {
+        let journal = match options.journal() {
+            Some(path) => Some(create_journal(path, options)?),
+            None => None,
+        };
+
+        Ok(Self {
+            options,
+            io,
+            mode,
+            log: TransferLog::new(io.stdout()),
+            journal,
+            overwrite_policy: options.overwrite_policy(),
+            prompt: Prompt::default(),
+            progress,
+            report: if options.json_lines() {
+                Some(TransferReport::new(io.stdout(), mode))
+            } else {
+                None
+            },
+            completed_entries: Vec::new(),
+            temp_paths: Vec::new(),
+            rolled_back: false,
+            exit_code: EXIT_CODE_OK,
+        })
+    }
+
+    pub fn end(mut self) -> io::Result<i32> {
+        if !self.rolled_back {
+            for temp_path in &self.temp_paths {
+                if let Err(error) = remove_path(temp_path) {
+                    write_error(&mut self.io.stderr(), &error)?;
+                    self.exit_code = EXIT_CODE_IO_ERROR;
+                }
+            }
+        }
+        if let Some(progress) = &mut self.progress {
+            let line = progress
+                .poll(true)
+                .expect("Forced progress should be rendered");
+            if progress.is_terminal() {
+                self.log.end_progress(&line)?;
+            } else {
+                self.log.print_progress(&line)?;
+            }
+        }
+        if let Some(report) = &mut self.report {
+            report.write_summary(self.rolled_back)?;
+        }
+        Ok(self.exit_code)
+    }
+
+    pub fn prepare(&mut self, src_path: &Path, dst_path: &Path) -> io::Result<Preparation> {
+        if self.options.symlink_mode() == SymlinkMode::Skip
+            && FileType::from(src_path) == FileType::Symlink
+        {
+            return Ok(Preparation::Skipped);
+        }
+
+        // Transfer to the same path does nothing and must not be undone
+        let missing_dirs = if (self.journal.is_some() || self.options.transaction())
+            && !is_same_file(src_path, dst_path).unwrap_or(false)
+        {
+            Some(find_missing_dirs(dst_path))
+        } else {
+            None
+        };
+
+        let io = self.io;
+        let prompt = &mut self.prompt;
+        let log = &mut self.log;
+
+        match check_overwrite(src_path, dst_path, &self.overwrite_policy, |src, dst| {
+            log.clear_progress()?;
+            prompt.confirm_overwrite(&mut io.stderr(), src, dst)
+        }) {
+            Ok(Overwrite::Allowed) => {
+                let dst_type = FileType::from(dst_path);
+                if dst_type != FileType::Unknown
+                    && !is_same_file(src_path, dst_path).unwrap_or(false)
+                {
+                    // Undo of a copy must not remove a path which replaced some other one
+                    if let Some(journal) = &mut self.journal {
+                        journal.record_overwrite(dst_path)?;
+                    }
+                    // Rollback has to restore the overwritten path (directories are merged, not replaced)
+                    if self.options.transaction() && dst_type != FileType::Dir {
+                        match self.move_aside(src_path, dst_path, dst_type) {
+                            Ok(temp_path) => {
+                                self.completed_entries.push(JournalEntry::Backup(
+                                    dst_path.to_path_buf(),
+                                    temp_path.clone(),
+                                ));
+                                self.temp_paths.push(temp_path);
+                            }
+                            Err(error) => return Ok(Preparation::Failed(missing_dirs, error)),
+                        }
+                    }
+                }
+            }
+            Ok(Overwrite::Skipped) => return Ok(Preparation::Skipped),
+            Ok(Overwrite::Backup(dst_backup_path)) => {
+                if let Err(error) = rename_path(dst_path, &dst_backup_path) {
+                    return Ok(Preparation::Failed(missing_dirs, error));
+                }
+                if let Some(journal) = &mut self.journal {
+                    journal.record_backup(dst_path, &dst_backup_path)?;
+                }
+                if self.options.transaction() {
+                    self.completed_entries.push(JournalEntry::Backup(
+                        dst_path.to_path_buf(),
+                        dst_backup_path,
+                    ));
+                }
+            }
+            Err(error) => return Ok(Preparation::Failed(missing_dirs, error)),
+        }
+
+        Ok(Preparation::Ready(missing_dirs))
+    }
+
+    pub fn begin(&mut self, src_path: &Path, dst_path: &Path) -> io::Result<()> {
+        // Progress output would break the log line, so it is written whole when the transfer ends
+        if self.options.verbose() && self.progress.is_none() {
+            self.log.begin_transfer(self.mode, src_path, dst_path)?;
+        }
+        Ok(())
+    }
+
+    pub fn skip(&mut self, index: usize, src_path: &Path, dst_path: &Path) -> io::Result<()> {
+        self.finish_progress(index, 0)?;
+        if self.options.verbose() {
+            self.log.begin_transfer(self.mode, src_path, dst_path)?;
+            self.log.end_with_skip()?;
+        }
+        if let Some(report) = &mut self.report {
+            let stats = TransferStats::default();
+            report.write_transfer(src_path, dst_path, TransferStatus::Skipped, None, stats)?;
+        }
+        self.render_progress(true)
+    }
+
+    // Reports progress of a file being copied in the calling thread
+    pub fn update_progress(&mut self, copy_progress: &CopyProgress) -> io::Result<()> {
+        if let Some(progress) = &mut self.progress {
+            progress.update_file(copy_progress);
+        }
+        self.render_progress(false)
+    }
+
+    // Reports bytes copied by transfers running in other threads
+    pub fn add_progress(&mut self, new_bytes: u64) -> io::Result<()> {
+        if let Some(progress) = &mut self.progress {
+            progress.add_bytes(new_bytes);
+        }
+        self.render_progress(false)
+    }
+
+    // Returns whether the batch should continue
+    pub fn finish(
+        &mut self,
+        index: usize,
+        src_path: PathBuf,
+        dst_path: PathBuf,
+        missing_dirs: Option<Vec<PathBuf>>,
+        stats: TransferStats,
+        result: Result<()>,
+    ) -> io::Result<bool> {
+        self.finish_progress(index, stats.copied_bytes)?;
+        if self.options.verbose() && self.progress.is_some() {
+            self.log.begin_transfer(self.mode, &src_path, &dst_path)?;
+        }
+        let proceed = self.finish_transfer(src_path, dst_path, missing_dirs, stats, result)?;
+        self.render_progress(true)?;
+        Ok(proceed)
+    }
+
+    fn finish_transfer(
+        &mut self,
+        src_path: PathBuf,
+        dst_path: PathBuf,
+        missing_dirs: Option<Vec<PathBuf>>,
+        stats: TransferStats,
+        result: Result<()>,
+    ) -> io::Result<bool> {
+        match result {
+            Ok(()) => {
+                if let Some(report) = &mut self.report {
+                    let status = TransferStatus::Ok;
+                    report.write_transfer(&src_path, &dst_path, status, None, stats)?;
+                }
+                if let (Some(journal), Some(missing_dirs)) = (&mut self.journal, &missing_dirs) {
+                    journal.record_transfer(missing_dirs, &src_path, &dst_path)?;
+                }
+                if self.options.verbose() {
+                    self.log.end_with_success()?;
+                }
+                if let (true, Some(missing_dirs)) = (self.options.transaction(), missing_dirs) {
+                    self.completed_entries
+                        .extend(missing_dirs.into_iter().map(JournalEntry::Dir));
+                    self.completed_entries
+                        .push(JournalEntry::Transfer(src_path, dst_path));
+                }
+                Ok(true)
+            }
+            Err(error) => {
+                if self.options.verbose() {
+                    self.log.end_with_failure()?;
+                }
+                if let Some(report) = &mut self.report {
+                    let message = error.to_string();
+                    let status = TransferStatus::Failed;
+                    report.write_transfer(&src_path, &dst_path, status, Some(&message), stats)?;
+                }
+
+                write_error(&mut self.io.stderr(), &error)?;
+                self.exit_code = EXIT_CODE_IO_ERROR;
+
+                if self.options.transaction() {
+                    if let Some(missing_dirs) = missing_dirs {
+                        // Failed transfer could have created some of its parent directories
+                        self.completed_entries.extend(
+                            missing_dirs
+                                .into_iter()
+                                .filter(|dir_path| dir_path.is_dir())
+                                .map(JournalEntry::Dir),
+                        );
+                    }
+                    // Roll back as much as possible, regardless of errors
+                    let undo = Undo::new(self.io, &mut self.log, self.mode, self.options, true);
+                    undo.run(&self.completed_entries, self.journal.as_mut())?;
+                    self.rolled_back = true;
+                    Ok(false)
+                } else {
+                    Ok(self.options.fail_at_end())
+                }
+            }
+        }
+    }
+
+    fn move_aside(&self, src_path: &Path, dst_path: &Path, dst_type: FileType) -> Result<PathBuf> {
+        // The transfer would fail anyway, but only after the overwritten path was moved
+        let src_type = FileType::of_source(src_path, self.options.symlink_mode());
+        check_types(src_path, src_type, dst_path, dst_type)?;
+
+        let temp_path = make_temp_path(dst_path)?;
+        rename_path(dst_path, &temp_path)?;
+        Ok(temp_path)
+    }
+
+    fn finish_progress(&mut self, index: usize, copied_bytes: u64) -> io::Result<()> {
+        if let Some(progress) = &mut self.progress {
+            progress.finish_pair(index, copied_bytes);
+            self.log.clear_progress()?; // Log lines and errors are written over the progress bar
+        }
+        Ok(())
+    }
+
+    fn render_progress(&mut self, force: bool) -> io::Result<()> {
+        if let Some(progress) = &mut self.progress {
+            if let Some(line) = progress.poll(force && progress.is_terminal()) {
+                if progress.is_terminal() {
+                    self.log.draw_progress(&line)?;
+                } else {
+                    self.log.print_progress(&line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn create_journal<O: TransferOptions>(
+    path: &Path,
+    options: &O,
+) -> io::Result<Journal<BufWriter<File>>> {
+    // Input paths are always valid UTF-8, so the working directory is the only thing to check
+    let working_dir = env::current_dir()?;
+    check_journal_path(&working_dir)?;
+
+    let terminator = if options.read_nul() { 0 } else { b'\n' };
+    Ok(Journal::new(
+        BufWriter::new(File::create(path)?),
+        terminator,
+        working_dir,
+    ))
+}
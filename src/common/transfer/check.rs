@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use fs_extra::error::{Error, ErrorKind};
+
+use crate::transfer::fs::{check_types, normalize_path, FileType, SymlinkMode, TransferMode};
+
+pub fn check_transfers(
+    pairs: &[(PathBuf, PathBuf)],
+    mode: TransferMode,
+    symlink_mode: SymlinkMode,
+    working_dir: &Path,
+) -> Vec<Error> {
+    let keys = pairs
+        .iter()
+        .map(|(src_path, dst_path)| {
+            (
+                normalize_path(src_path, working_dir),
+                normalize_path(dst_path, working_dir),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut src_indices = HashMap::<&Path, Vec<usize>>::new();
+    for (index, (src_key, dst_key)) in keys.iter().enumerate() {
+        if src_key != dst_key {
+            src_indices.entry(src_key).or_default().push(index);
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut file_types = HashMap::<&Path, FileType>::new();
+    let mut dst_sources = HashMap::<&Path, &Path>::new();
+
+    for (index, ((src_path, dst_path), (src_key, dst_key))) in pairs.iter().zip(&keys).enumerate() {
+        if src_key == dst_key {
+            continue; // Transfer to the same path does nothing
+        }
+
+        // Types of already transferred paths are derived from previous transfers, not read from FS
+        let src_type = match (get_file_type(&file_types, src_key), symlink_mode) {
+            (FileType::Symlink, SymlinkMode::Skip) => continue,
+            (FileType::Symlink, SymlinkMode::Follow) => FileType::of_target(src_key),
+            (src_type, _) => src_type,
+        };
+        let dst_type = get_file_type(&file_types, dst_key);
+
+        if let Err(error) = check_types(src_path, src_type, dst_path, dst_type) {
+            errors.push(error);
+        } else {
+            if let TransferMode::Move = mode {
+                file_types.insert(src_key, FileType::Unknown);
+            }
+            file_types.insert(dst_key, src_type);
+        }
+
+        if let Some(prev_src_path) = dst_sources.insert(dst_key, src_path) {
+            errors.push(Error::new(
+                ErrorKind::Other,
+                &format!(
+                    "Path '{}' is a destination of both '{}' and '{}'",
+                    dst_path.to_string_lossy(),
+                    prev_src_path.to_string_lossy(),
+                    src_path.to_string_lossy()
+                ),
+            ));
+        }
+
+        // Only existing destination is overwritten, otherwise it is just an intermediate path
+        let next_index = match dst_type {
+            FileType::Unknown => None,
+            _ => find_next_index(&src_indices, dst_key, index),
+        };
+
+        if let Some(next_index) = next_index {
+            errors.push(Error::new(
+                ErrorKind::Other,
+                &format!(
+                    "Path '{}' would be overwritten by '{}' before being transferred to '{}'",
+                    dst_path.to_string_lossy(),
+                    src_path.to_string_lossy(),
+                    pairs[next_index].1.to_string_lossy()
+                ),
+            ));
+        }
+    }
+
+    errors
+}
+
+fn get_file_type(file_types: &HashMap<&Path, FileType>, key: &Path) -> FileType {
+    match file_types.get(key) {
+        Some(file_type) => *file_type,
+        None => FileType::from(key),
+    }
+}
+
+fn find_next_index(
+    src_indices: &HashMap<&Path, Vec<usize>>,
+    key: &Path,
+    index: usize,
+) -> Option<usize> {
+    let indices = src_indices.get(key)?;
+    let position = indices.partition_point(|&other_index| other_index <= index);
+    indices.get(position).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test]
+    fn no_errors() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child("b").create_dir_all().unwrap();
+        dir.child("c").touch().unwrap();
+
+        assert_eq!(
+            check(
+                &dir,
+                TransferMode::Move,
+                &[("a", "d"), ("b", "e"), ("c", "./c")]
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test_case(TransferMode::Move, &["Path 'a' not found or user lacks permission"] ; "move mode")]
+    #[test_case(TransferMode::Copy, &[]                                              ; "copy mode")]
+    fn source_transferred_twice(mode: TransferMode, errors: &[&str]) {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+
+        assert_eq!(check(&dir, mode, &[("a", "b"), ("a", "c")]), errors);
+    }
+
+    #[test]
+    fn source_not_found() {
+        let dir = TempDir::new().unwrap();
+
+        assert_eq!(
+            check(&dir, TransferMode::Move, &[("a", "b")]),
+            &["Path 'a' not found or user lacks permission"]
+        );
+    }
+
+    #[test]
+    fn types_mismatch() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child("b").create_dir_all().unwrap();
+        dir.child("c").create_dir_all().unwrap();
+        dir.child("d").touch().unwrap();
+
+        assert_eq!(
+            check(&dir, TransferMode::Copy, &[("a", "b"), ("c", "d")]),
+            &[
+                "Cannot to overwrite directory 'b' with file 'a'",
+                "Cannot to overwrite file 'd' with directory 'c'",
+            ]
+        );
+    }
+
+    #[test]
+    fn types_mismatch_after_transfer() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child("b").create_dir_all().unwrap();
+
+        assert_eq!(
+            check(&dir, TransferMode::Move, &[("a", "c"), ("b", "d/../c")]),
+            &[
+                "Cannot to overwrite file 'd/../c' with directory 'b'",
+                "Path 'd/../c' is a destination of both 'a' and 'b'",
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_destination() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child("b").touch().unwrap();
+        dir.child("c").touch().unwrap();
+
+        assert_eq!(
+            check(
+                &dir,
+                TransferMode::Move,
+                &[("a", "d"), ("b", "./d"), ("c", "e/../d")]
+            ),
+            &[
+                "Path './d' is a destination of both 'a' and 'b'",
+                "Path 'e/../d' is a destination of both 'b' and 'c'",
+            ]
+        );
+    }
+
+    #[test]
+    fn destination_is_later_source() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child("b").touch().unwrap();
+
+        assert_eq!(
+            check(&dir, TransferMode::Move, &[("a", "b"), ("b", "c")]),
+            &["Path 'b' would be overwritten by 'a' before being transferred to 'c'"]
+        );
+    }
+
+    #[test]
+    fn destination_is_later_source_but_missing() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+
+        assert_eq!(
+            check(&dir, TransferMode::Move, &[("a", "b"), ("b", "c")]),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn destination_is_earlier_source() {
+        let dir = TempDir::new().unwrap();
+        dir.child("b").touch().unwrap();
+
+        assert_eq!(
+            check(&dir, TransferMode::Move, &[("b", "c"), ("a", "b")]),
+            &["Path 'a' not found or user lacks permission"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test_case(SymlinkMode::Keep,   &[("l", "c"), ("d", "e")], &[]                                                     ; "keep")]
+    #[test_case(SymlinkMode::Keep,   &[("l", "b")],             &["Cannot to overwrite directory 'b' with symlink 'l'"] ; "keep to dir")]
+    #[test_case(SymlinkMode::Follow, &[("l", "c"), ("d", "e")], &["Path 'd' not found or user lacks permission"]        ; "follow")]
+    #[test_case(SymlinkMode::Follow, &[("l", "a")],             &["Cannot to overwrite file 'a' with directory 'l'"]    ; "follow to file")]
+    #[test_case(SymlinkMode::Skip,   &[("l", "b"), ("d", "a")], &[]                                                     ; "skip")]
+    fn symlinks(symlink_mode: SymlinkMode, pairs: &[(&str, &str)], errors: &[&str]) {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child("b").create_dir_all().unwrap();
+        dir.child("l")
+            .symlink_to_dir(dir.child("b").path())
+            .unwrap();
+        dir.child("d")
+            .symlink_to_file(dir.child("x").path())
+            .unwrap();
+
+        assert_eq!(
+            check_with_symlinks(&dir, TransferMode::Copy, symlink_mode, pairs),
+            errors
+        );
+    }
+
+    fn check(dir: &TempDir, mode: TransferMode, pairs: &[(&str, &str)]) -> Vec<String> {
+        check_with_symlinks(dir, mode, SymlinkMode::Keep, pairs)
+    }
+
+    fn check_with_symlinks(
+        dir: &TempDir,
+        mode: TransferMode,
+        symlink_mode: SymlinkMode,
+        pairs: &[(&str, &str)],
+    ) -> Vec<String> {
+        let pairs = pairs
+            .iter()
+            .map(|(src_path, dst_path)| (PathBuf::from(src_path), PathBuf::from(dst_path)))
+            .collect::<Vec<_>>();
+
+        check_transfers(&pairs, mode, symlink_mode, dir.path())
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+}
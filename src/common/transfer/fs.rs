@@ -1,23 +1,41 @@
-use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+use std::{env, fs, io};
 
 use fs_extra::error::{Error, ErrorKind, Result};
 use fs_extra::{dir, file};
 use lazy_static::lazy_static;
-use same_file::is_same_file;
+use pathdiff::diff_paths;
+use same_file::{is_same_file, Handle};
 
-#[derive(PartialEq, Debug)]
+use crate::transfer::metadata::copy_metadata;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum FileType {
     File,
     Dir,
+    Symlink,
     Unknown,
 }
 
-impl From<&Path> for FileType {
-    fn from(path: &Path) -> Self {
-        match path.metadata() {
+impl FileType {
+    // Unlike `FileType::from`, this follows symlinks
+    pub fn of_target(path: &Path) -> Self {
+        Self::from_metadata(path.metadata())
+    }
+
+    pub fn of_source(path: &Path, symlink_mode: SymlinkMode) -> Self {
+        match (Self::from(path), symlink_mode) {
+            (FileType::Symlink, SymlinkMode::Follow) => Self::of_target(path),
+            (file_type, _) => file_type,
+        }
+    }
+
+    fn from_metadata(metadata: io::Result<fs::Metadata>) -> Self {
+        match metadata {
             Ok(metadata) => {
-                if metadata.is_dir() {
+                if metadata.file_type().is_symlink() {
+                    FileType::Symlink
+                } else if metadata.is_dir() {
                     FileType::Dir
                 } else {
                     FileType::File
@@ -28,14 +46,309 @@ impl From<&Path> for FileType {
     }
 }
 
+impl From<&Path> for FileType {
+    fn from(path: &Path) -> Self {
+        Self::from_metadata(path.symlink_metadata())
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum TransferMode {
     Move,
     Copy,
 }
 
-pub fn transfer_path(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Result<()> {
-    match (FileType::from(src_path), FileType::from(dst_path)) {
+pub const OVERWRITE_POLICIES: &[&str] = &[ALWAYS, SKIP, FAIL, NEWER, BACKUP, PROMPT];
+pub const SYMLINK_MODES: &[&str] = &[KEEP, RETARGET, FOLLOW, SKIP];
+
+const ALWAYS: &str = "always";
+const SKIP: &str = "skip";
+const FAIL: &str = "fail";
+const NEWER: &str = "newer";
+const BACKUP: &str = "backup";
+const PROMPT: &str = "prompt";
+const KEEP: &str = "keep";
+const RETARGET: &str = "retarget";
+const FOLLOW: &str = "follow";
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum OverwritePolicy {
+    Always,
+    Skip,
+    Fail,
+    Newer,
+    Backup(BackupMode),
+    Prompt,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum BackupMode {
+    Numbered,
+    Suffix(String),
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SymlinkMode {
+    Keep,
+    Retarget,
+    Follow,
+    Skip,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Overwrite {
+    Allowed,
+    Skipped,
+    Backup(PathBuf),
+}
+
+pub fn parse_overwrite_policy(string: &str) -> std::result::Result<OverwritePolicy, &'static str> {
+    match string {
+        ALWAYS => Ok(OverwritePolicy::Always),
+        SKIP => Ok(OverwritePolicy::Skip),
+        FAIL => Ok(OverwritePolicy::Fail),
+        NEWER => Ok(OverwritePolicy::Newer),
+        BACKUP => Ok(OverwritePolicy::Backup(BackupMode::Numbered)),
+        PROMPT => Ok(OverwritePolicy::Prompt),
+        _ => Err("invalid value"),
+    }
+}
+
+pub fn parse_symlink_mode(string: &str) -> std::result::Result<SymlinkMode, &'static str> {
+    match string {
+        KEEP => Ok(SymlinkMode::Keep),
+        RETARGET => Ok(SymlinkMode::Retarget),
+        FOLLOW => Ok(SymlinkMode::Follow),
+        SKIP => Ok(SymlinkMode::Skip),
+        _ => Err("invalid value"),
+    }
+}
+
+pub fn check_overwrite<C>(
+    src_path: &Path,
+    dst_path: &Path,
+    policy: &OverwritePolicy,
+    confirm: C,
+) -> Result<Overwrite>
+where
+    C: FnOnce(&Path, &Path) -> std::io::Result<bool>,
+{
+    if FileType::from(dst_path) == FileType::Unknown
+        || is_same_file(src_path, dst_path).unwrap_or(false)
+    {
+        return Ok(Overwrite::Allowed);
+    }
+
+    match policy {
+        OverwritePolicy::Always => Ok(Overwrite::Allowed),
+        OverwritePolicy::Skip => Ok(Overwrite::Skipped),
+        OverwritePolicy::Fail => Err(Error::new(
+            ErrorKind::AlreadyExists,
+            &format!("Path '{}' already exists", dst_path.to_string_lossy()),
+        )),
+        OverwritePolicy::Newer => {
+            if src_path.metadata()?.modified()? > dst_path.metadata()?.modified()? {
+                Ok(Overwrite::Allowed)
+            } else {
+                Ok(Overwrite::Skipped)
+            }
+        }
+        OverwritePolicy::Backup(mode) => Ok(Overwrite::Backup(make_backup_path(dst_path, mode)?)),
+        OverwritePolicy::Prompt => {
+            if confirm(src_path, dst_path)? {
+                Ok(Overwrite::Allowed)
+            } else {
+                Ok(Overwrite::Skipped)
+            }
+        }
+    }
+}
+
+fn make_backup_path(path: &Path, mode: &BackupMode) -> Result<PathBuf> {
+    let file_name = match path.file_name() {
+        Some(file_name) => file_name.to_string_lossy(),
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidFileName,
+                &format!("Cannot make backup of '{}'", path.to_string_lossy()),
+            ))
+        }
+    };
+
+    match mode {
+        BackupMode::Suffix(suffix) => Ok(path.with_file_name(format!("{}{}", file_name, suffix))),
+        BackupMode::Numbered => {
+            let prefix = format!("{}.~", file_name);
+            let parent_path = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+
+            let mut last_number = 0;
+
+            for entry in parent_path.read_dir()? {
+                let entry_name = entry?.file_name();
+                let entry_name = entry_name.to_string_lossy();
+
+                if let Some(number) = entry_name
+                    .strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix('~'))
+                    .and_then(|number| number.parse::<u32>().ok())
+                {
+                    last_number = last_number.max(number);
+                }
+            }
+
+            Ok(path.with_file_name(format!("{}{}~", prefix, last_number + 1)))
+        }
+    }
+}
+
+// Unused sibling path where an existing path can be temporarily moved aside
+pub fn make_temp_path(path: &Path) -> Result<PathBuf> {
+    let file_name = match path.file_name() {
+        Some(file_name) => file_name.to_string_lossy(),
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidFileName,
+                &format!("Cannot move aside '{}'", path.to_string_lossy()),
+            ))
+        }
+    };
+
+    for number in 1.. {
+        let temp_path = path.with_file_name(format!("{}.~tmp{}~", file_name, number));
+        if FileType::from(temp_path.as_path()) == FileType::Unknown {
+            return Ok(temp_path);
+        }
+    }
+
+    unreachable!("Some temporary path should be unused")
+}
+
+pub fn rename_path(path: &Path, new_path: &Path) -> Result<()> {
+    fs::rename(path, new_path)?;
+    Ok(())
+}
+
+// Progress of a file being copied, reported by `transfer_path_with_progress`
+pub struct CopyProgress<'a> {
+    pub path: &'a Path,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub new_bytes: u64, // Bytes copied since the previous report
+}
+
+pub fn transfer_path(
+    src_path: &Path,
+    dst_path: &Path,
+    mode: TransferMode,
+    symlink_mode: SymlinkMode,
+    preserve: bool,
+) -> Result<()> {
+    transfer_path_impl(src_path, dst_path, mode, symlink_mode, preserve, None)
+}
+
+pub fn transfer_path_with_progress<F: FnMut(&CopyProgress)>(
+    src_path: &Path,
+    dst_path: &Path,
+    mode: TransferMode,
+    symlink_mode: SymlinkMode,
+    preserve: bool,
+    mut progress_handler: F,
+) -> Result<()> {
+    transfer_path_impl(
+        src_path,
+        dst_path,
+        mode,
+        symlink_mode,
+        preserve,
+        Some(&mut progress_handler),
+    )
+}
+
+fn transfer_path_impl(
+    src_path: &Path,
+    dst_path: &Path,
+    mode: TransferMode,
+    symlink_mode: SymlinkMode,
+    preserve: bool,
+    mut progress_handler: Option<&mut dyn FnMut(&CopyProgress)>,
+) -> Result<()> {
+    let src_type = FileType::of_source(src_path, symlink_mode);
+    let dst_type = FileType::from(dst_path);
+
+    check_types(src_path, src_type, dst_path, dst_type)?;
+
+    if src_type == FileType::Dir {
+        check_not_nested(src_path, dst_path)?;
+        dir::create_all(dst_path, false)?;
+    } else if let Some(dst_parent) = dst_path.parent() {
+        dir::create_all(dst_parent, false)?;
+    }
+
+    match mode {
+        TransferMode::Move => {
+            if src_type == FileType::Symlink {
+                let target = fs::read_link(src_path)?;
+                let new_target = match symlink_mode {
+                    SymlinkMode::Retarget => {
+                        retarget_symlink(&target, src_path, dst_path, src_path)?
+                    }
+                    _ => target.clone(),
+                };
+                if new_target != target || fs::rename(src_path, dst_path).is_err() {
+                    replace_with_symlink(&new_target, dst_path, dst_type)?;
+                    remove_symlink(src_path)?;
+                }
+            } else if FileType::from(src_path) == FileType::Symlink {
+                // Followed symlink is replaced by a copy of its target
+                PathCopy::new(
+                    src_path,
+                    symlink_mode,
+                    preserve,
+                    progress_handler.as_deref_mut(),
+                )
+                .copy_whole(src_path, dst_path, dst_type)?;
+                remove_symlink(src_path)?;
+            } else if fs::rename(src_path, dst_path).is_ok() {
+                if src_type == FileType::Dir && symlink_mode == SymlinkMode::Retarget {
+                    retarget_symlinks(src_path, dst_path, src_path)?;
+                }
+            } else {
+                // Moved directory contents must stay complete, so their symlinks are never followed nor skipped
+                let nested_mode = match symlink_mode {
+                    SymlinkMode::Retarget => SymlinkMode::Retarget,
+                    _ => SymlinkMode::Keep,
+                };
+                PathCopy::new(
+                    src_path,
+                    nested_mode,
+                    preserve,
+                    progress_handler.as_deref_mut(),
+                )
+                .copy_whole(src_path, dst_path, dst_type)?;
+                remove_path(src_path)?;
+            }
+        }
+        TransferMode::Copy => {
+            if dst_type == FileType::Unknown || !is_same_file(src_path, dst_path).unwrap_or(false) {
+                PathCopy::new(src_path, symlink_mode, preserve, progress_handler)
+                    .copy(src_path, dst_path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn check_types(
+    src_path: &Path,
+    src_type: FileType,
+    dst_path: &Path,
+    dst_type: FileType,
+) -> Result<()> {
+    match (src_type, dst_type) {
         (FileType::Unknown, _) => Err(Error::new(
             ErrorKind::NotFound,
             &format!(
@@ -53,6 +366,15 @@ pub fn transfer_path(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Re
             ),
         )),
 
+        (FileType::Symlink, FileType::Dir) => Err(Error::new(
+            ErrorKind::Other,
+            &format!(
+                "Cannot to overwrite directory '{}' with symlink '{}'",
+                dst_path.to_string_lossy(),
+                src_path.to_string_lossy()
+            ),
+        )),
+
         (FileType::Dir, FileType::File) => Err(Error::new(
             ErrorKind::Other,
             &format!(
@@ -62,47 +384,307 @@ pub fn transfer_path(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Re
             ),
         )),
 
-        (FileType::File, dst_type) => {
-            if let Some(dst_parent) = dst_path.parent() {
-                dir::create_all(dst_parent, false)?;
-            }
-            match mode {
-                TransferMode::Move => {
-                    if fs::rename(src_path, dst_path).is_err() {
-                        file::move_file(src_path, dst_path, &FILE_COPY_OPTIONS)?;
-                    }
+        (FileType::Dir, FileType::Symlink) => Err(Error::new(
+            ErrorKind::Other,
+            &format!(
+                "Cannot to overwrite symlink '{}' with directory '{}'",
+                dst_path.to_string_lossy(),
+                src_path.to_string_lossy()
+            ),
+        )),
+
+        _ => Ok(()),
+    }
+}
+
+fn check_not_nested(src_path: &Path, dst_path: &Path) -> Result<()> {
+    let working_dir = env::current_dir()?;
+    let src_key = normalize_path(src_path, &working_dir);
+    let dst_key = normalize_path(dst_path, &working_dir);
+
+    if dst_key != src_key && dst_key.starts_with(&src_key) {
+        Err(Error::new(
+            ErrorKind::Other,
+            &format!(
+                "Cannot to transfer directory '{}' into its subdirectory '{}'",
+                src_path.to_string_lossy(),
+                dst_path.to_string_lossy()
+            ),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+// Recursive copy which handles symlinks (including the copied path itself) according to the mode
+struct PathCopy<'a, 'b> {
+    src_root: &'a Path,
+    symlink_mode: SymlinkMode,
+    preserve: bool,
+    progress_handler: Option<&'a mut (dyn FnMut(&CopyProgress) + 'b)>,
+    dir_handles: Vec<Handle>, // Directories being copied, to detect loops of followed symlinks
+}
+
+impl<'a, 'b> PathCopy<'a, 'b> {
+    fn new(
+        src_root: &'a Path,
+        symlink_mode: SymlinkMode,
+        preserve: bool,
+        progress_handler: Option<&'a mut (dyn FnMut(&CopyProgress) + 'b)>,
+    ) -> Self {
+        Self {
+            src_root,
+            symlink_mode,
+            preserve,
+            progress_handler,
+            dir_handles: Vec::new(),
+        }
+    }
+
+    // Move must not leave a partial copy behind, when the destination did not exist before
+    fn copy_whole(&mut self, src_path: &Path, dst_path: &Path, dst_type: FileType) -> Result<()> {
+        let result = self.copy(src_path, dst_path);
+        if result.is_err() && dst_type == FileType::Unknown && FileType::from(dst_path) != dst_type
+        {
+            remove_path(dst_path).ok(); // The original error is more important
+        }
+        result
+    }
+
+    fn copy(&mut self, src_path: &Path, dst_path: &Path) -> Result<()> {
+        let src_type = FileType::of_source(src_path, self.symlink_mode);
+        let dst_type = FileType::from(dst_path);
+
+        check_types(src_path, src_type, dst_path, dst_type)?;
+
+        match src_type {
+            FileType::File => {
+                if dst_type == FileType::Symlink {
+                    remove_symlink(dst_path)?; // Otherwise, the symlink target would be overwritten
                 }
-                TransferMode::Copy => {
-                    if dst_type == FileType::Unknown || !is_same_file(src_path, dst_path)? {
-                        file::copy(src_path, dst_path, &FILE_COPY_OPTIONS)?;
-                    }
+                self.copy_file(src_path, dst_path)?;
+                if self.preserve {
+                    copy_metadata(src_path, dst_path)?;
                 }
             }
-            Ok(())
+            FileType::Dir => self.copy_dir(src_path, dst_path)?,
+            FileType::Symlink => {
+                let mut target = fs::read_link(src_path)?;
+                if self.symlink_mode == SymlinkMode::Retarget {
+                    target = retarget_symlink(&target, src_path, dst_path, self.src_root)?;
+                }
+                replace_with_symlink(&target, dst_path, dst_type)?;
+            }
+            FileType::Unknown => unreachable!("Source type should have been checked"),
         }
 
-        (FileType::Dir, dst_type) => {
-            dir::create_all(dst_path, false)?;
-            match mode {
-                TransferMode::Move => {
-                    if fs::rename(src_path, dst_path).is_err() {
-                        dir::move_dir(src_path, dst_path, &DIR_COPY_OPTIONS)?;
-                    }
-                }
-                TransferMode::Copy => {
-                    if dst_type == FileType::Unknown || !is_same_file(src_path, dst_path)? {
-                        dir::copy(src_path, dst_path, &DIR_COPY_OPTIONS)?;
-                    }
+        Ok(())
+    }
+
+    fn copy_file(&mut self, src_path: &Path, dst_path: &Path) -> Result<()> {
+        let handler = match &mut self.progress_handler {
+            Some(handler) => handler,
+            None => return file::copy(src_path, dst_path, &FILE_COPY_OPTIONS).map(|_| ()),
+        };
+
+        let mut reported_bytes = 0;
+
+        file::copy_with_progress(src_path, dst_path, &FILE_COPY_OPTIONS, |process| {
+            handler(&CopyProgress {
+                path: src_path,
+                copied_bytes: process.copied_bytes,
+                total_bytes: process.total_bytes,
+                new_bytes: process.copied_bytes - reported_bytes,
+            });
+            reported_bytes = process.copied_bytes;
+        })?;
+
+        // Unlike `file::copy`, this does not copy permissions
+        fs::set_permissions(dst_path, src_path.metadata()?.permissions())?;
+        Ok(())
+    }
+
+    fn copy_dir(&mut self, src_path: &Path, dst_path: &Path) -> Result<()> {
+        let handle = Handle::from_path(src_path)?;
+
+        if self.dir_handles.contains(&handle) {
+            return Err(Error::new(
+                ErrorKind::Other,
+                &format!(
+                    "Directory '{}' contains itself through a symlink",
+                    src_path.to_string_lossy()
+                ),
+            ));
+        }
+
+        dir::create_all(dst_path, false)?;
+        self.dir_handles.push(handle);
+
+        for entry in src_path.read_dir()? {
+            let entry = entry?;
+            let entry_path = entry.path();
+
+            if self.symlink_mode == SymlinkMode::Skip
+                && FileType::from(entry_path.as_path()) == FileType::Symlink
+            {
+                continue;
+            }
+
+            self.copy(&entry_path, &dst_path.join(entry.file_name()))?;
+        }
+
+        self.dir_handles.pop();
+
+        // Directory is processed after its contents, so its timestamps are not changed afterwards
+        if self.preserve {
+            copy_metadata(src_path, dst_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Relative target is changed to point to the same path from the new symlink location.
+// The only exception is a target inside the transferred directory, which is transferred too.
+fn retarget_symlink(
+    target: &Path,
+    src_path: &Path,
+    dst_path: &Path,
+    src_root: &Path,
+) -> io::Result<PathBuf> {
+    if target.is_absolute() {
+        return Ok(target.to_path_buf());
+    }
+
+    let working_dir = env::current_dir()?;
+    let src_path = normalize_path(src_path, &working_dir);
+    let dst_path = normalize_path(dst_path, &working_dir);
+    let src_root = normalize_path(src_root, &working_dir);
+    let target_path = normalize_path(target, src_path.parent().unwrap_or(&src_path));
+
+    if src_path != src_root && target_path.starts_with(&src_root) {
+        Ok(target.to_path_buf())
+    } else {
+        match diff_paths(&target_path, dst_path.parent().unwrap_or(&dst_path)) {
+            Some(path) if path.as_os_str().is_empty() => Ok(PathBuf::from(".")),
+            Some(path) => Ok(path),
+            None => Ok(target_path),
+        }
+    }
+}
+
+// Fixes symlinks inside an already renamed directory
+fn retarget_symlinks(src_dir: &Path, dst_dir: &Path, src_root: &Path) -> Result<()> {
+    for entry in dst_dir.read_dir()? {
+        let entry = entry?;
+        let src_path = src_dir.join(entry.file_name());
+        let dst_path = entry.path();
+
+        match FileType::from(dst_path.as_path()) {
+            FileType::Dir => retarget_symlinks(&src_path, &dst_path, src_root)?,
+            FileType::Symlink => {
+                let target = fs::read_link(&dst_path)?;
+                let new_target = retarget_symlink(&target, &src_path, &dst_path, src_root)?;
+                if new_target != target {
+                    replace_with_symlink(&new_target, &dst_path, FileType::Symlink)?;
                 }
             }
-            Ok(())
+            FileType::File | FileType::Unknown => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn replace_with_symlink(target: &Path, path: &Path, path_type: FileType) -> Result<()> {
+    if path_type != FileType::Unknown {
+        remove_path(path)?;
+    }
+    create_symlink(target, path)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, path: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, path: &Path) -> io::Result<()> {
+    use std::os::windows::fs::{symlink_dir, symlink_file};
+
+    // Windows distinguishes between file and directory symlinks
+    if path.parent().unwrap_or(Path::new("")).join(target).is_dir() {
+        symlink_dir(target, path)
+    } else {
+        symlink_file(target, path)
+    }
+}
+
+fn remove_symlink(path: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        if path.is_dir() {
+            return fs::remove_dir(path); // Directory symlink
         }
     }
+    fs::remove_file(path)
+}
+
+pub fn find_missing_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut missing_dirs = path
+        .ancestors()
+        .skip(1)
+        .take_while(|dir_path| !dir_path.as_os_str().is_empty() && !dir_path.exists())
+        .map(Path::to_path_buf)
+        .collect::<Vec<_>>();
+
+    missing_dirs.reverse();
+    missing_dirs
+}
+
+pub fn remove_path(path: &Path) -> Result<()> {
+    match FileType::from(path) {
+        FileType::File => file::remove(path),
+        FileType::Dir => dir::remove(path),
+        FileType::Symlink => Ok(remove_symlink(path)?),
+        FileType::Unknown => Err(Error::new(
+            ErrorKind::NotFound,
+            &format!(
+                "Path '{}' not found or user lacks permission",
+                path.to_string_lossy()
+            ),
+        )),
+    }
+}
+
+pub fn remove_empty_dir(path: &Path) -> Result<bool> {
+    if FileType::from(path) != FileType::Dir || path.read_dir()?.next().is_some() {
+        Ok(false)
+    } else {
+        fs::remove_dir(path)?;
+        Ok(true)
+    }
+}
+
+pub fn normalize_path(path: &Path, working_dir: &Path) -> PathBuf {
+    let mut normalized_path = PathBuf::new();
+
+    for component in working_dir.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized_path.pop();
+            }
+            component => normalized_path.push(component),
+        }
+    }
+
+    normalized_path
 }
 
 lazy_static! {
     pub static ref FILE_COPY_OPTIONS: file::CopyOptions = get_file_copy_options();
-    pub static ref DIR_COPY_OPTIONS: dir::CopyOptions = get_dir_copy_options();
 }
 
 fn get_file_copy_options() -> file::CopyOptions {
@@ -112,15 +694,6 @@ fn get_file_copy_options() -> file::CopyOptions {
     options
 }
 
-fn get_dir_copy_options() -> dir::CopyOptions {
-    let mut options = dir::CopyOptions::new();
-    options.overwrite = true;
-    options.skip_exist = false;
-    options.copy_inside = true;
-    options.content_only = true;
-    options
-}
-
 #[cfg(test)]
 mod tests {
     use assert_fs::prelude::*;
@@ -139,7 +712,49 @@ mod tests {
         assert_eq!(FileType::from(path), file_type);
     }
 
+    #[cfg(unix)]
+    #[test_case("a", SymlinkMode::Keep,   FileType::Symlink, FileType::File    ; "keep")]
+    #[test_case("a", SymlinkMode::Follow, FileType::Symlink, FileType::File    ; "follow")]
+    #[test_case("x", SymlinkMode::Keep,   FileType::Symlink, FileType::Unknown ; "keep dangling")]
+    #[test_case("x", SymlinkMode::Follow, FileType::Symlink, FileType::Unknown ; "follow dangling")]
+    fn file_type_symlink(
+        target: &str,
+        symlink_mode: SymlinkMode,
+        file_type: FileType,
+        target_type: FileType,
+    ) {
+        let dir = temp_dir();
+        touch(dir.child("a"));
+        let link_path = symlink(dir.child("l"), target);
+
+        assert_eq!(FileType::from(link_path.as_path()), file_type);
+        assert_eq!(FileType::of_target(&link_path), target_type);
+        assert_eq!(
+            FileType::of_source(&link_path, symlink_mode),
+            match symlink_mode {
+                SymlinkMode::Follow => target_type,
+                _ => file_type,
+            }
+        );
+    }
+
+    #[test_case("",         "/a/b"     ; "empty")]
+    #[test_case(".",        "/a/b"     ; "current dir")]
+    #[test_case("c",        "/a/b/c"   ; "relative")]
+    #[test_case("./c/./d",  "/a/b/c/d" ; "current dir components")]
+    #[test_case("../c",     "/a/c"     ; "parent dir component")]
+    #[test_case("../../..", "/"        ; "parent dir beyond root")]
+    #[test_case("/c",       "/c"       ; "absolute")]
+    fn normalize_path(path: &str, result: &str) {
+        assert_eq!(
+            super::normalize_path(Path::new(path), Path::new("/a/b")),
+            Path::new(result)
+        );
+    }
+
     mod transfer_path {
+        use filetime::FileTime;
+
         use super::*;
 
         #[test]
@@ -147,8 +762,14 @@ mod tests {
             let src_file = temp_file("a");
 
             assert_eq!(
-                transfer_path(src_file.path(), &Path::new("b"), TransferMode::Move) // Mode is irrelevant
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    Path::new("b"),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                ) // Mode is irrelevant
+                .map_err(unpack_fse_error),
                 Err((
                     debug_fse_error_kind(ErrorKind::NotFound),
                     format!(
@@ -167,8 +788,14 @@ mod tests {
             let dst_dir = temp_dir();
 
             assert_eq!(
-                transfer_path(src_file.path(), dst_dir.path(), TransferMode::Move) // Mode is irrelevant
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    dst_dir.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                ) // Mode is irrelevant
+                .map_err(unpack_fse_error),
                 Err((
                     debug_fse_error_kind(ErrorKind::Other),
                     format!(
@@ -189,8 +816,14 @@ mod tests {
             let dst_file = touch(temp_file("a"));
 
             assert_eq!(
-                transfer_path(src_dir.path(), dst_file.path(), TransferMode::Move) // Mode is irrelevant
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    dst_file.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                ) // Mode is irrelevant
+                .map_err(unpack_fse_error),
                 Err((
                     debug_fse_error_kind(ErrorKind::Other),
                     format!(
@@ -211,8 +844,14 @@ mod tests {
             let dst_file = temp_file("b");
 
             assert_eq!(
-                transfer_path(src_file.path(), dst_file.path(), TransferMode::Move)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -225,8 +864,14 @@ mod tests {
             let src_file = write(temp_file("a"), "1");
 
             assert_eq!(
-                transfer_path(src_file.path(), src_file.path(), TransferMode::Move)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    src_file.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -239,8 +884,14 @@ mod tests {
             let dst_file = write(temp_file("b"), "2");
 
             assert_eq!(
-                transfer_path(src_file.path(), dst_file.path(), TransferMode::Move)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -254,13 +905,42 @@ mod tests {
             let dst_file = temp_file("b");
 
             assert_eq!(
-                transfer_path(src_file.path(), dst_file.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_file.assert("1");
+            dst_file.assert("1");
+        }
+
+        #[test]
+        fn copy_file_preserve() {
+            let src_file = write(temp_file("a"), "1");
+            let dst_file = temp_file("b");
+            let mtime = set_mtime(src_file.path());
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    true
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
             src_file.assert("1");
             dst_file.assert("1");
+            assert_eq!(get_mtime(dst_file.path()), mtime);
         }
 
         #[test]
@@ -269,8 +949,14 @@ mod tests {
             let src_file = write(temp_file("a"), "1");
 
             assert_eq!(
-                transfer_path(src_file.path(), src_file.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    src_file.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -283,8 +969,14 @@ mod tests {
             let dst_file = write(temp_file("b"), "2");
 
             assert_eq!(
-                transfer_path(src_file.path(), dst_file.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -303,8 +995,14 @@ mod tests {
             let dst_file = dst_dir.child("c");
 
             assert_eq!(
-                transfer_path(src_dir.path(), dst_dir.path(), TransferMode::Move)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -321,8 +1019,14 @@ mod tests {
             let src_file = write(src_dir.child("a"), "1");
 
             assert_eq!(
-                transfer_path(src_dir.path(), src_dir.path(), TransferMode::Move)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    src_dir.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -341,8 +1045,14 @@ mod tests {
             let dst_file = write(dst_dir.child("c"), "2");
 
             assert_eq!(
-                transfer_path(src_dir.path(), dst_dir.path(), TransferMode::Move)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -364,8 +1074,14 @@ mod tests {
             let dst_file = dst_dir.child("c");
 
             assert_eq!(
-                transfer_path(src_dir.path(), dst_dir.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -376,6 +1092,35 @@ mod tests {
             dst_file.assert("1");
         }
 
+        #[test]
+        fn copy_dir_preserve() {
+            let root_dir = temp_dir();
+
+            let src_dir = mkdir(root_dir.child("a"));
+            let src_file = write(src_dir.child("c"), "1");
+            let file_mtime = set_mtime(src_file.path());
+            let dir_mtime = set_mtime(src_dir.path());
+
+            let dst_dir = root_dir.child("b");
+            let dst_file = dst_dir.child("c");
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    true
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            dst_file.assert("1");
+            assert_eq!(get_mtime(dst_dir.path()), dir_mtime);
+            assert_eq!(get_mtime(dst_file.path()), file_mtime);
+        }
+
         #[test]
         #[timeout(5000)] // fs_extra::dir::copy freezes for same src/dst path
         fn copy_dir_to_itself() {
@@ -383,8 +1128,14 @@ mod tests {
             let src_file = write(src_dir.child("a"), "1");
 
             assert_eq!(
-                transfer_path(src_dir.path(), src_dir.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    src_dir.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -403,8 +1154,14 @@ mod tests {
             let dst_file = write(dst_dir.child("c"), "2");
 
             assert_eq!(
-                transfer_path(src_dir.path(), dst_dir.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -414,13 +1171,571 @@ mod tests {
             dst_dir.assert(predicates::path::is_dir());
             dst_file.assert("1");
         }
+
+        fn set_mtime(path: &Path) -> FileTime {
+            let mtime = FileTime::from_unix_time(1_000_000_000, 0);
+            filetime::set_file_mtime(path, mtime).unwrap();
+            mtime
+        }
+
+        fn get_mtime(path: &Path) -> FileTime {
+            FileTime::from_last_modification_time(&path.metadata().unwrap())
+        }
+    }
+
+    #[cfg(unix)]
+    mod transfer_symlink {
+        use std::fs::read_link;
+
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case(TransferMode::Copy, SymlinkMode::Keep,     "a"    ; "copy keep")]
+        #[test_case(TransferMode::Copy, SymlinkMode::Retarget, "../a" ; "copy retarget")]
+        #[test_case(TransferMode::Move, SymlinkMode::Keep,     "a"    ; "move keep")]
+        #[test_case(TransferMode::Move, SymlinkMode::Retarget, "../a" ; "move retarget")]
+        fn link(mode: TransferMode, symlink_mode: SymlinkMode, target: &str) {
+            let root_dir = temp_dir();
+            let src_path = symlink(root_dir.child("l"), "a");
+            let dst_path = root_dir.path().join("d/l");
+
+            assert_eq!(
+                transfer_path(&src_path, &dst_path, mode, symlink_mode, false)
+                    .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            assert_eq!(read_link(&dst_path).unwrap(), Path::new(target));
+            assert_eq!(
+                FileType::from(src_path.as_path()),
+                match mode {
+                    TransferMode::Copy => FileType::Symlink,
+                    TransferMode::Move => FileType::Unknown,
+                }
+            );
+        }
+
+        #[test_case(TransferMode::Copy, FileType::Symlink ; "copy mode")]
+        #[test_case(TransferMode::Move, FileType::Unknown ; "move mode")]
+        fn follow(mode: TransferMode, src_type: FileType) {
+            let root_dir = temp_dir();
+            let target_file = write(root_dir.child("a"), "1");
+            let src_path = symlink(root_dir.child("l"), "a");
+            let dst_file = root_dir.child("b");
+
+            assert_eq!(
+                transfer_path(&src_path, dst_file.path(), mode, SymlinkMode::Follow, false)
+                    .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            assert_eq!(FileType::from(src_path.as_path()), src_type);
+            assert_eq!(FileType::from(dst_file.path()), FileType::File);
+            target_file.assert("1");
+            dst_file.assert("1");
+        }
+
+        #[test]
+        fn file_to_symlink() {
+            let root_dir = temp_dir();
+            let src_file = write(root_dir.child("a"), "1");
+            let target_file = write(root_dir.child("b"), "2");
+            let dst_path = symlink(root_dir.child("l"), "b");
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    &dst_path,
+                    TransferMode::Copy,
+                    SymlinkMode::Keep,
+                    false
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            assert_eq!(FileType::from(dst_path.as_path()), FileType::File);
+            target_file.assert("2");
+        }
+
+        #[test_case(SymlinkMode::Keep,     Some("f"), Some("../a")    ; "keep")]
+        #[test_case(SymlinkMode::Retarget, Some("f"), Some("../../a") ; "retarget")]
+        #[test_case(SymlinkMode::Follow,   None,      None            ; "follow")]
+        #[test_case(SymlinkMode::Skip,     None,      None            ; "skip")]
+        fn copy_dir(
+            symlink_mode: SymlinkMode,
+            inner_target: Option<&str>,
+            outer_target: Option<&str>,
+        ) {
+            let root_dir = temp_dir();
+            write(root_dir.child("a"), "1");
+            let src_dir = mkdir(root_dir.child("s"));
+            write(src_dir.child("f"), "2");
+            symlink(src_dir.child("i"), "f");
+            symlink(src_dir.child("o"), "../a");
+            let dst_dir = root_dir.child("d/s");
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy,
+                    symlink_mode,
+                    false
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            dst_dir.child("f").assert("2");
+
+            for (name, target, content) in [("i", inner_target, "2"), ("o", outer_target, "1")] {
+                let dst_child = dst_dir.child(name);
+                match (symlink_mode, target) {
+                    (_, Some(target)) => {
+                        assert_eq!(read_link(dst_child.path()).unwrap(), Path::new(target));
+                    }
+                    (SymlinkMode::Follow, None) => {
+                        dst_child.assert(content);
+                    }
+                    (_, None) => {
+                        dst_child.assert(predicates::path::missing());
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn move_dir_retarget() {
+            let root_dir = temp_dir();
+            let src_dir = mkdir(root_dir.child("s"));
+            symlink(src_dir.child("i"), "f");
+            symlink(src_dir.child("o"), "../a");
+            let dst_dir = root_dir.child("d/s");
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Retarget,
+                    false
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_dir.assert(predicates::path::missing());
+            assert_eq!(
+                read_link(dst_dir.child("i").path()).unwrap(),
+                Path::new("f")
+            );
+            assert_eq!(
+                read_link(dst_dir.child("o").path()).unwrap(),
+                Path::new("../../a")
+            );
+        }
+
+        #[test]
+        fn follow_loop() {
+            let src_dir = temp_dir();
+            symlink(src_dir.child("l"), ".");
+            let dst_dir = temp_dir();
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy,
+                    SymlinkMode::Follow,
+                    false
+                )
+                .map_err(unpack_fse_error),
+                Err((
+                    debug_fse_error_kind(ErrorKind::Other),
+                    format!(
+                        "Directory '{}' contains itself through a symlink",
+                        src_dir.child("l").path().to_string_lossy()
+                    )
+                ))
+            );
+        }
+
+        #[test]
+        fn move_follow_failure_removes_partial_copy() {
+            let root_dir = temp_dir();
+            let src_dir = mkdir(root_dir.child("s"));
+            write(src_dir.child("f"), "1");
+            symlink(src_dir.child("l"), ".");
+            let src_path = symlink(root_dir.child("t"), "s");
+            let dst_dir = root_dir.child("d/t");
+
+            assert_eq!(
+                transfer_path(
+                    &src_path,
+                    dst_dir.path(),
+                    TransferMode::Move,
+                    SymlinkMode::Follow,
+                    false
+                )
+                .map_err(unpack_fse_error),
+                Err((
+                    debug_fse_error_kind(ErrorKind::Other),
+                    format!(
+                        "Directory '{}' contains itself through a symlink",
+                        src_path.join("l").to_string_lossy()
+                    )
+                ))
+            );
+
+            assert_eq!(FileType::from(src_path.as_path()), FileType::Symlink);
+            dst_dir.assert(predicates::path::missing());
+        }
     }
 
     #[test]
-    fn same_dir_and_file_copy_options() {
-        assert_eq!(DIR_COPY_OPTIONS.overwrite, FILE_COPY_OPTIONS.overwrite);
-        assert_eq!(DIR_COPY_OPTIONS.skip_exist, FILE_COPY_OPTIONS.skip_exist);
-        assert_eq!(DIR_COPY_OPTIONS.buffer_size, FILE_COPY_OPTIONS.buffer_size);
+    fn dir_to_subdir() {
+        let src_dir = temp_dir();
+        let dst_dir = src_dir.child("a");
+
+        assert_eq!(
+            transfer_path(
+                src_dir.path(),
+                dst_dir.path(),
+                TransferMode::Copy,
+                SymlinkMode::Keep,
+                false
+            )
+            .map_err(unpack_fse_error),
+            Err((
+                debug_fse_error_kind(ErrorKind::Other),
+                format!(
+                    "Cannot to transfer directory '{}' into its subdirectory '{}'",
+                    src_dir.path().to_string_lossy(),
+                    dst_dir.path().to_string_lossy()
+                )
+            ))
+        );
+    }
+
+    #[test_case("",     Err("invalid value")                              ; "empty")]
+    #[test_case("x",    Err("invalid value")                              ; "invalid")]
+    #[test_case(ALWAYS, Ok(OverwritePolicy::Always)                       ; "always")]
+    #[test_case(SKIP,   Ok(OverwritePolicy::Skip)                         ; "skip")]
+    #[test_case(FAIL,   Ok(OverwritePolicy::Fail)                         ; "fail")]
+    #[test_case(NEWER,  Ok(OverwritePolicy::Newer)                        ; "newer")]
+    #[test_case(BACKUP, Ok(OverwritePolicy::Backup(BackupMode::Numbered)) ; "backup")]
+    #[test_case(PROMPT, Ok(OverwritePolicy::Prompt)                       ; "prompt")]
+    fn parse_overwrite_policy(
+        input: &str,
+        result: std::result::Result<OverwritePolicy, &'static str>,
+    ) {
+        assert_eq!(super::parse_overwrite_policy(input), result);
+    }
+
+    #[test_case("",       Err("invalid value")      ; "empty")]
+    #[test_case("x",      Err("invalid value")      ; "invalid")]
+    #[test_case(KEEP,     Ok(SymlinkMode::Keep)     ; "keep")]
+    #[test_case(RETARGET, Ok(SymlinkMode::Retarget) ; "retarget")]
+    #[test_case(FOLLOW,   Ok(SymlinkMode::Follow)   ; "follow")]
+    #[test_case(SKIP,     Ok(SymlinkMode::Skip)     ; "skip")]
+    fn parse_symlink_mode(input: &str, result: std::result::Result<SymlinkMode, &'static str>) {
+        assert_eq!(super::parse_symlink_mode(input), result);
+    }
+
+    mod check_overwrite {
+        use std::fs::File;
+        use std::time::{Duration, SystemTime};
+
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case(OverwritePolicy::Always ; "always")]
+        #[test_case(OverwritePolicy::Skip   ; "skip")]
+        #[test_case(OverwritePolicy::Fail   ; "fail")]
+        #[test_case(OverwritePolicy::Newer  ; "newer")]
+        #[test_case(OverwritePolicy::Prompt ; "prompt")]
+        fn missing_destination(policy: OverwritePolicy) {
+            let root_dir = temp_dir();
+            let src_file = touch(root_dir.child("a"));
+
+            assert_eq!(
+                check_overwrite(
+                    src_file.path(),
+                    &root_dir.path().join("b"),
+                    &policy,
+                    no_confirm
+                )
+                .map_err(unpack_fse_error),
+                Ok(Overwrite::Allowed)
+            );
+        }
+
+        #[test]
+        fn same_destination() {
+            let src_file = touch(temp_file("a"));
+
+            assert_eq!(
+                check_overwrite(
+                    src_file.path(),
+                    src_file.path(),
+                    &OverwritePolicy::Fail,
+                    no_confirm
+                )
+                .map_err(unpack_fse_error),
+                Ok(Overwrite::Allowed)
+            );
+        }
+
+        #[test_case(OverwritePolicy::Always, Ok(Overwrite::Allowed)                              ; "always")]
+        #[test_case(OverwritePolicy::Skip,   Ok(Overwrite::Skipped)                              ; "skip")]
+        #[test_case(OverwritePolicy::Fail,   Err(debug_fse_error_kind(ErrorKind::AlreadyExists)) ; "fail")]
+        fn existing_destination(
+            policy: OverwritePolicy,
+            result: std::result::Result<Overwrite, String>,
+        ) {
+            let root_dir = temp_dir();
+            let src_file = touch(root_dir.child("a"));
+            let dst_file = touch(root_dir.child("b"));
+
+            assert_eq!(
+                check_overwrite(src_file.path(), dst_file.path(), &policy, no_confirm)
+                    .map_err(|error| debug_fse_error_kind(error.kind)),
+                result
+            );
+        }
+
+        #[test_case(-10, Overwrite::Skipped ; "older")]
+        #[test_case(0,   Overwrite::Skipped ; "same")]
+        #[test_case(10,  Overwrite::Allowed ; "newer")]
+        fn newer_source(src_offset: i64, result: Overwrite) {
+            let root_dir = temp_dir();
+            let src_file = touch(root_dir.child("a"));
+            let dst_file = touch(root_dir.child("b"));
+
+            let time = SystemTime::now() - Duration::from_secs(100);
+            let src_time = if src_offset < 0 {
+                time - Duration::from_secs(src_offset.unsigned_abs())
+            } else {
+                time + Duration::from_secs(src_offset.unsigned_abs())
+            };
+
+            set_modified(src_file.path(), src_time);
+            set_modified(dst_file.path(), time);
+
+            assert_eq!(
+                check_overwrite(
+                    src_file.path(),
+                    dst_file.path(),
+                    &OverwritePolicy::Newer,
+                    no_confirm
+                )
+                .map_err(unpack_fse_error),
+                Ok(result)
+            );
+        }
+
+        #[test_case(false, Overwrite::Skipped ; "rejected")]
+        #[test_case(true,  Overwrite::Allowed ; "confirmed")]
+        fn prompt(answer: bool, result: Overwrite) {
+            let root_dir = temp_dir();
+            let src_file = touch(root_dir.child("a"));
+            let dst_file = touch(root_dir.child("b"));
+
+            assert_eq!(
+                check_overwrite(
+                    src_file.path(),
+                    dst_file.path(),
+                    &OverwritePolicy::Prompt,
+                    |src_path, dst_path| {
+                        assert_eq!(src_path, src_file.path());
+                        assert_eq!(dst_path, dst_file.path());
+                        Ok(answer)
+                    }
+                )
+                .map_err(unpack_fse_error),
+                Ok(result)
+            );
+        }
+
+        #[test]
+        fn backup_numbered() {
+            let root_dir = temp_dir();
+            let src_file = touch(root_dir.child("a"));
+            let dst_file = touch(root_dir.child("b"));
+            touch(root_dir.child("b.~1~"));
+            touch(root_dir.child("b.~3~"));
+            touch(root_dir.child("b.~x~"));
+            touch(root_dir.child("c.~5~"));
+
+            assert_eq!(
+                check_overwrite(
+                    src_file.path(),
+                    dst_file.path(),
+                    &OverwritePolicy::Backup(BackupMode::Numbered),
+                    no_confirm
+                )
+                .map_err(unpack_fse_error),
+                Ok(Overwrite::Backup(root_dir.path().join("b.~4~")))
+            );
+        }
+
+        #[test]
+        fn backup_suffix() {
+            let root_dir = temp_dir();
+            let src_file = touch(root_dir.child("a"));
+            let dst_file = touch(root_dir.child("b"));
+
+            assert_eq!(
+                check_overwrite(
+                    src_file.path(),
+                    dst_file.path(),
+                    &OverwritePolicy::Backup(BackupMode::Suffix(".bak".into())),
+                    no_confirm
+                )
+                .map_err(unpack_fse_error),
+                Ok(Overwrite::Backup(root_dir.path().join("b.bak")))
+            );
+        }
+
+        fn no_confirm(_: &Path, _: &Path) -> std::io::Result<bool> {
+            panic!("Confirmation should not be requested")
+        }
+
+        fn set_modified(path: &Path, time: SystemTime) {
+            File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn make_temp_path() {
+        let root_dir = temp_dir();
+        touch(root_dir.child("a.~tmp1~"));
+        touch(root_dir.child("a.~tmp2~"));
+
+        assert_eq!(
+            super::make_temp_path(&root_dir.path().join("a")).map_err(unpack_fse_error),
+            Ok(root_dir.path().join("a.~tmp3~"))
+        );
+    }
+
+    #[test]
+    fn rename_path() {
+        let root_dir = temp_dir();
+        let src_file = write(root_dir.child("a"), "1");
+        let dst_file = root_dir.child("b");
+
+        assert_eq!(
+            super::rename_path(src_file.path(), dst_file.path()).map_err(unpack_fse_error),
+            Ok(())
+        );
+
+        src_file.assert(predicates::path::missing());
+        dst_file.assert("1");
+    }
+
+    #[test]
+    fn find_missing_absolute_dirs() {
+        let root_dir = temp_dir();
+        mkdir(root_dir.child("a"));
+
+        assert_eq!(
+            find_missing_dirs(&root_dir.path().join("a/b/c/d")),
+            vec![root_dir.path().join("a/b"), root_dir.path().join("a/b/c")]
+        );
+    }
+
+    #[test_case("a",     &[]         ; "relative file")]
+    #[test_case("a/b/c", &["a", "a/b"] ; "relative dirs")]
+    fn find_missing_relative_dirs(path: &str, result: &[&str]) {
+        assert_eq!(
+            find_missing_dirs(Path::new(path)),
+            result.iter().map(PathBuf::from).collect::<Vec<_>>()
+        );
+    }
+
+    mod remove_path {
+        use super::*;
+
+        #[test]
+        fn path_not_found() {
+            let file = temp_file("a");
+
+            assert_eq!(
+                remove_path(file.path()).map_err(unpack_fse_error),
+                Err((
+                    debug_fse_error_kind(ErrorKind::NotFound),
+                    format!(
+                        "Path '{}' not found or user lacks permission",
+                        file.path().to_string_lossy()
+                    )
+                ))
+            );
+        }
+
+        #[test]
+        fn file() {
+            let file = touch(temp_file("a"));
+
+            assert_eq!(remove_path(file.path()).map_err(unpack_fse_error), Ok(()));
+            file.assert(predicates::path::missing());
+        }
+
+        #[test]
+        fn dir() {
+            let root_dir = temp_dir();
+            let dir = mkdir(root_dir.child("a"));
+            touch(dir.child("b"));
+
+            assert_eq!(remove_path(dir.path()).map_err(unpack_fse_error), Ok(()));
+            dir.assert(predicates::path::missing());
+        }
+    }
+
+    mod remove_empty_dir {
+        use super::*;
+
+        #[test]
+        fn empty() {
+            let root_dir = temp_dir();
+            let dir = mkdir(root_dir.child("a"));
+
+            assert_eq!(
+                remove_empty_dir(dir.path()).map_err(unpack_fse_error),
+                Ok(true)
+            );
+            dir.assert(predicates::path::missing());
+        }
+
+        #[test]
+        fn nonempty() {
+            let root_dir = temp_dir();
+            let dir = mkdir(root_dir.child("a"));
+            touch(dir.child("b"));
+
+            assert_eq!(
+                remove_empty_dir(dir.path()).map_err(unpack_fse_error),
+                Ok(false)
+            );
+            dir.assert(predicates::path::is_dir());
+        }
+
+        #[test]
+        fn missing() {
+            let dir = temp_file("a");
+
+            assert_eq!(
+                remove_empty_dir(dir.path()).map_err(unpack_fse_error),
+                Ok(false)
+            );
+        }
     }
 
     fn temp_dir() -> TempDir {
@@ -445,4 +1760,10 @@ mod tests {
         file.write_str(data).unwrap();
         file
     }
+
+    #[cfg(unix)]
+    fn symlink<P: PathChild + AsRef<Path>>(path: P, target: &str) -> PathBuf {
+        std::os::unix::fs::symlink(target, path.as_ref()).unwrap();
+        path.as_ref().to_path_buf()
+    }
 }
@@ -5,7 +5,7 @@ use std::path::PathBuf;
 use crate::input::{Splitter, Terminator};
 use crate::symbols::{DIFF_IN, DIFF_OUT};
 
-struct Position {
+pub struct Position {
     item: usize,
     offset: usize,
 }
@@ -57,7 +57,15 @@ impl<I: BufRead> PathDiff<I> {
     }
 }
 
-fn extract_path(value: &str, position: &Position, prefix: char) -> Result<PathBuf> {
+impl<I: BufRead> Iterator for PathDiff<I> {
+    type Item = Result<(PathBuf, PathBuf)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read().transpose()
+    }
+}
+
+pub fn extract_path(value: &str, position: &Position, prefix: char) -> Result<PathBuf> {
     if let Some(first_char) = value.chars().next() {
         if first_char == prefix {
             let path = &value[prefix.len_utf8()..];
@@ -83,7 +91,7 @@ fn extract_path(value: &str, position: &Position, prefix: char) -> Result<PathBu
     }
 }
 
-fn make_unexpected_eof_error(position: &Position, prefix: char) -> Error {
+pub fn make_unexpected_eof_error(position: &Position, prefix: char) -> Error {
     Error::new(
         ErrorKind::UnexpectedEof,
         format!("Expected '{}' ({})", prefix, position),
@@ -144,5 +152,25 @@ mod tests {
                 Err((kind, message.into()))
             )
         }
+
+        #[test]
+        fn iter() {
+            assert_eq!(
+                PathDiff::new(
+                    "<abc\n>def\n< g \n> h \nx".as_bytes(),
+                    Terminator::Newline { required: false }
+                )
+                .map(|result| result.map_err(unpack_io_error))
+                .collect::<Vec<_>>(),
+                vec![
+                    Ok(("abc".into(), "def".into())),
+                    Ok((" g ".into(), " h ".into())),
+                    Err((
+                        ErrorKind::InvalidData,
+                        "Expected '<' but got 'x' (item #5 at offset 20)".into()
+                    )),
+                ]
+            );
+        }
     }
 }
@@ -0,0 +1,284 @@
+use std::io::{BufRead, Error, ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+
+use crate::input::{Splitter, Terminator};
+use crate::symbols::{DIFF_IN, DIFF_OUT, JOURNAL_BACKUP, JOURNAL_DIR, JOURNAL_OVERWRITE};
+use crate::transfer::input::{extract_path, make_unexpected_eof_error, Position};
+
+#[derive(PartialEq, Debug)]
+pub enum JournalEntry {
+    Dir(PathBuf),
+    Backup(PathBuf, PathBuf),
+    Overwrite(PathBuf), // Destination path existed before the transfer
+    Transfer(PathBuf, PathBuf),
+}
+
+pub struct Journal<O: Write> {
+    output: O,
+    terminator: u8,
+    working_dir: PathBuf, // Relative paths are recorded as absolute, so undo works from any directory
+}
+
+impl<O: Write> Journal<O> {
+    pub fn new(output: O, terminator: u8, working_dir: PathBuf) -> Self {
+        Self {
+            output,
+            terminator,
+            working_dir,
+        }
+    }
+
+    pub fn record_transfer(
+        &mut self,
+        created_dirs: &[PathBuf],
+        src_path: &Path,
+        dst_path: &Path,
+    ) -> Result<()> {
+        for dir_path in created_dirs {
+            self.write_item(JOURNAL_DIR, dir_path)?;
+        }
+        self.write_item(DIFF_IN, src_path)?;
+        self.write_item(DIFF_OUT, dst_path)?;
+        self.output.flush() // So the journal is usable even when the process gets killed
+    }
+
+    pub fn record_backup(&mut self, path: &Path, backup_path: &Path) -> Result<()> {
+        self.write_item(JOURNAL_BACKUP, path)?;
+        self.write_item(DIFF_OUT, backup_path)?;
+        self.output.flush()
+    }
+
+    pub fn record_overwrite(&mut self, path: &Path) -> Result<()> {
+        self.write_item(JOURNAL_OVERWRITE, path)?;
+        self.output.flush()
+    }
+
+    // Journal is read the same way as input of mvb/cpb, which must be valid UTF-8
+    fn write_item(&mut self, prefix: char, path: &Path) -> Result<()> {
+        let path = self.working_dir.join(path);
+
+        if let Some(path) = path.to_str() {
+            write!(self.output, "{}{}", prefix, path)?;
+            self.output.write_all(&[self.terminator])
+        } else {
+            Err(make_non_utf8_error(&path))
+        }
+    }
+}
+
+pub fn check_journal_path(path: &Path) -> Result<()> {
+    if path.to_str().is_some() {
+        Ok(())
+    } else {
+        Err(make_non_utf8_error(path))
+    }
+}
+
+fn make_non_utf8_error(path: &Path) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Path '{}' is not valid UTF-8 and cannot be recorded in journal",
+            path.to_string_lossy()
+        ),
+    )
+}
+
+pub fn read_journal<I: BufRead>(input: I, terminator: Terminator) -> Result<Vec<JournalEntry>> {
+    let mut splitter = Splitter::new(input, terminator);
+    let mut position = Position::new();
+    let mut entries = Vec::new();
+
+    while let Some((value, size)) = splitter.read()? {
+        if value.starts_with(JOURNAL_DIR) {
+            let dir_path = extract_path(value, &position, JOURNAL_DIR)?;
+            position.increment(size);
+            entries.push(JournalEntry::Dir(dir_path));
+            continue;
+        }
+
+        if value.starts_with(JOURNAL_OVERWRITE) {
+            let path = extract_path(value, &position, JOURNAL_OVERWRITE)?;
+            position.increment(size);
+            entries.push(JournalEntry::Overwrite(path));
+            continue;
+        }
+
+        let prefix = if value.starts_with(JOURNAL_BACKUP) {
+            JOURNAL_BACKUP
+        } else {
+            DIFF_IN
+        };
+
+        let src_path = extract_path(value, &position, prefix)?;
+        position.increment(size);
+
+        let dst_path = match splitter.read()? {
+            Some((value, size)) => {
+                let dst_path = extract_path(value, &position, DIFF_OUT)?;
+                position.increment(size);
+                dst_path
+            }
+            None => return Err(make_unexpected_eof_error(&position, DIFF_OUT)),
+        };
+
+        if prefix == JOURNAL_BACKUP {
+            entries.push(JournalEntry::Backup(src_path, dst_path));
+        } else {
+            entries.push(JournalEntry::Transfer(src_path, dst_path));
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod journal {
+        use std::env;
+
+        use super::*;
+
+        #[test]
+        fn record_transfer() {
+            let mut output = Vec::new();
+            let mut journal = Journal::new(&mut output, b'\n', PathBuf::new());
+
+            journal
+                .record_transfer(&[], Path::new("a"), Path::new("b"))
+                .unwrap();
+            journal
+                .record_transfer(
+                    &["c".into(), "c/d".into()],
+                    Path::new("e"),
+                    Path::new("c/d/f"),
+                )
+                .unwrap();
+
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                "<a\n>b\n+c\n+c/d\n<e\n>c/d/f\n"
+            );
+        }
+
+        #[test]
+        fn record_backup() {
+            let mut output = Vec::new();
+
+            Journal::new(&mut output, b'\n', PathBuf::new())
+                .record_backup(Path::new("a"), Path::new("a.~1~"))
+                .unwrap();
+
+            assert_eq!(String::from_utf8(output).unwrap(), "~a\n>a.~1~\n");
+        }
+
+        #[test]
+        fn record_overwrite() {
+            let mut output = Vec::new();
+
+            Journal::new(&mut output, b'\n', PathBuf::new())
+                .record_overwrite(Path::new("a"))
+                .unwrap();
+
+            assert_eq!(String::from_utf8(output).unwrap(), "!a\n");
+        }
+
+        #[test]
+        fn record_relative_to_working_dir() {
+            let mut output = Vec::new();
+            let working_dir = env::current_dir().unwrap();
+
+            Journal::new(&mut output, b'\n', working_dir.clone())
+                .record_transfer(&["b".into()], Path::new("a"), &working_dir.join("b/c"))
+                .unwrap();
+
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                format!(
+                    "+{}\n<{}\n>{}\n",
+                    working_dir.join("b").to_string_lossy(),
+                    working_dir.join("a").to_string_lossy(),
+                    working_dir.join("b/c").to_string_lossy(),
+                )
+            );
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn record_non_utf8() {
+            use std::ffi::OsStr;
+            use std::os::unix::ffi::OsStrExt;
+
+            let mut output = Vec::new();
+            let working_dir = PathBuf::from(OsStr::from_bytes(b"/a\xff"));
+
+            assert_eq!(
+                Journal::new(&mut output, b'\n', working_dir)
+                    .record_overwrite(Path::new("b"))
+                    .map_err(|error| error.to_string()),
+                Err(String::from(
+                    "Path '/a\u{fffd}/b' is not valid UTF-8 and cannot be recorded in journal"
+                ))
+            );
+            assert!(output.is_empty());
+        }
+
+        #[test]
+        fn record_transfer_nul() {
+            let mut output = Vec::new();
+
+            Journal::new(&mut output, 0, PathBuf::new())
+                .record_transfer(&["c".into()], Path::new("a"), Path::new("c/b"))
+                .unwrap();
+
+            assert_eq!(String::from_utf8(output).unwrap(), "+c\0<a\0>c/b\0");
+        }
+    }
+
+    mod read_journal {
+        use std::io::ErrorKind;
+
+        use test_case::test_case;
+
+        use super::*;
+        use crate::testing::unpack_io_error;
+
+        #[test]
+        fn ok() {
+            assert_eq!(
+                read_journal(
+                    "<a\n>b\n+c\n+c/d\n~c/d/f\n>c/d/f~\n!g\n<e\n>c/d/f".as_bytes(),
+                    Terminator::Newline { required: false }
+                )
+                .map_err(unpack_io_error),
+                Ok(vec![
+                    JournalEntry::Transfer("a".into(), "b".into()),
+                    JournalEntry::Dir("c".into()),
+                    JournalEntry::Dir("c/d".into()),
+                    JournalEntry::Backup("c/d/f".into(), "c/d/f~".into()),
+                    JournalEntry::Overwrite("g".into()),
+                    JournalEntry::Transfer("e".into(), "c/d/f".into()),
+                ])
+            );
+        }
+
+        type E = ErrorKind;
+
+        #[test_case("a",       E::InvalidData,   "Expected '<' but got 'a' (item #1 at offset 0)"  ; "in prefix invalid")]
+        #[test_case("+",       E::UnexpectedEof, "Expected a path after '+' (item #1 at offset 0)" ; "dir path missing")]
+        #[test_case("+a\n<b",  E::UnexpectedEof, "Expected '>' (item #3 at offset 5)"              ; "out missing")]
+        #[test_case("<a\n+b",  E::InvalidData,   "Expected '>' but got '+' (item #2 at offset 3)"  ; "out prefix invalid")]
+        #[test_case("~",       E::UnexpectedEof, "Expected a path after '~' (item #1 at offset 0)" ; "backup path missing")]
+        #[test_case("~a",      E::UnexpectedEof, "Expected '>' (item #2 at offset 2)"              ; "backup out missing")]
+        #[test_case("!",       E::UnexpectedEof, "Expected a path after '!' (item #1 at offset 0)" ; "overwrite path missing")]
+        fn err(input: &str, kind: ErrorKind, message: &str) {
+            assert_eq!(
+                read_journal(input.as_bytes(), Terminator::Newline { required: false })
+                    .map_err(unpack_io_error),
+                Err((kind, message.into()))
+            );
+        }
+    }
+}
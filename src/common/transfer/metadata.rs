@@ -0,0 +1,152 @@
+use std::fs;
+use std::io::{ErrorKind, Result};
+use std::path::Path;
+
+use filetime::FileTime;
+
+// Symlinks are not supported as destination, source symlink is followed
+pub fn copy_metadata(src_path: &Path, dst_path: &Path) -> Result<()> {
+    let metadata = src_path.metadata()?;
+
+    #[cfg(unix)]
+    copy_ownership(&metadata, dst_path)?;
+    #[cfg(target_os = "linux")]
+    copy_xattrs(src_path, dst_path)?;
+
+    fs::set_permissions(dst_path, metadata.permissions())?;
+
+    filetime::set_file_times(
+        dst_path,
+        FileTime::from_last_access_time(&metadata),
+        FileTime::from_last_modification_time(&metadata),
+    )
+}
+
+#[cfg(unix)]
+fn copy_ownership(metadata: &fs::Metadata, dst_path: &Path) -> Result<()> {
+    use std::os::unix::fs::{chown, MetadataExt};
+
+    // Only privileged user can change owner, but anyone can change group to one of their groups
+    match chown(dst_path, Some(metadata.uid()), Some(metadata.gid())) {
+        Err(error) if error.kind() == ErrorKind::PermissionDenied => {
+            ignore_permission_error(chown(dst_path, None, Some(metadata.gid())))
+        }
+        result => result,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn copy_xattrs(src_path: &Path, dst_path: &Path) -> Result<()> {
+    let names = match xattr::list_deref(src_path) {
+        Ok(names) => names,
+        Err(error) if error.kind() == ErrorKind::Unsupported => return Ok(()),
+        Err(error) => return Err(error),
+    };
+
+    for name in names {
+        if let Some(value) = xattr::get_deref(src_path, &name)? {
+            // Some namespaces (e.g. "trusted") require privileges
+            ignore_permission_error(xattr::set_deref(dst_path, &name, &value))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn ignore_permission_error(result: Result<()>) -> Result<()> {
+    match result {
+        Err(error) if error.kind() == ErrorKind::PermissionDenied => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn copy_metadata() {
+        let dir = TempDir::new().unwrap();
+        let src_file = dir.child("a");
+        let dst_file = dir.child("b");
+
+        src_file.touch().unwrap();
+        dst_file.touch().unwrap();
+        set_readonly(src_file.path());
+        set_times(src_file.path(), 1000);
+
+        super::copy_metadata(src_file.path(), dst_file.path()).unwrap();
+        assert_same_metadata(src_file.path(), dst_file.path());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_metadata_from_symlink() {
+        let dir = TempDir::new().unwrap();
+        let src_file = dir.child("a");
+        let src_link = dir.child("b");
+        let dst_file = dir.child("c");
+
+        src_file.touch().unwrap();
+        dst_file.touch().unwrap();
+        set_times(src_file.path(), 1000);
+        src_link.symlink_to_file(src_file.path()).unwrap();
+
+        super::copy_metadata(src_link.path(), dst_file.path()).unwrap();
+        assert_same_metadata(src_file.path(), dst_file.path());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn copy_xattrs() {
+        let dir = TempDir::new().unwrap();
+        let src_file = dir.child("a");
+        let dst_file = dir.child("b");
+
+        src_file.touch().unwrap();
+        dst_file.touch().unwrap();
+
+        if xattr::set(src_file.path(), "user.test", b"value").is_err() {
+            return; // FS without user xattrs support (e.g. tmpfs on older kernels)
+        }
+
+        super::copy_metadata(src_file.path(), dst_file.path()).unwrap();
+
+        assert_eq!(
+            xattr::get(dst_file.path(), "user.test").unwrap(),
+            Some(b"value".to_vec())
+        );
+    }
+
+    fn set_readonly(path: &Path) {
+        let mut permissions = path.metadata().unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(path, permissions).unwrap();
+    }
+
+    fn set_times(path: &Path, secs_ago: u64) {
+        let time = FileTime::from_system_time(SystemTime::now() - Duration::from_secs(secs_ago));
+        filetime::set_file_times(path, time, time).unwrap();
+    }
+
+    fn assert_same_metadata(src_path: &Path, dst_path: &Path) {
+        let src_metadata = src_path.metadata().unwrap();
+        let dst_metadata = dst_path.metadata().unwrap();
+
+        assert_eq!(src_metadata.permissions(), dst_metadata.permissions());
+        assert_eq!(
+            FileTime::from_last_modification_time(&src_metadata),
+            FileTime::from_last_modification_time(&dst_metadata)
+        );
+        assert_eq!(
+            FileTime::from_last_access_time(&src_metadata),
+            FileTime::from_last_access_time(&dst_metadata)
+        );
+    }
+}
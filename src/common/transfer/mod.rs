@@ -1,9 +1,23 @@
-pub use fs::TransferMode;
-pub use run::{run_transfer, TransferOptions};
+pub use fs::{
+    parse_overwrite_policy, parse_symlink_mode, BackupMode, OverwritePolicy, SymlinkMode,
+    TransferMode, OVERWRITE_POLICIES, SYMLINK_MODES,
+};
+pub use parallel::parse_jobs;
+pub use run::{run_transfer, TransferOptions, EXIT_CODE_CHECK_ERROR};
 
+mod batch;
+mod check;
 mod fs;
 mod input;
+mod journal;
+mod metadata;
+mod order;
 mod output;
+mod parallel;
+mod progress;
+mod prompt;
+mod report;
 mod run;
 #[cfg(test)]
 mod testing;
+mod undo;
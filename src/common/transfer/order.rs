@@ -0,0 +1,186 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::transfer::fs::normalize_path;
+
+const TEMP_SUFFIX: &str = ".mvb-tmp";
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Pending,
+    Visiting,
+    Done,
+}
+
+// Reorders moves so that no destination is overwritten before its own move.
+// Chains (a->b, b->c) are reversed and cycles (a->b, b->a) are broken using a temporary path.
+pub fn order_moves(pairs: Vec<(PathBuf, PathBuf)>, working_dir: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let keys = pairs
+        .iter()
+        .map(|(src_path, dst_path)| {
+            (
+                normalize_path(src_path, working_dir),
+                normalize_path(dst_path, working_dir),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut src_indices = HashMap::<&Path, usize>::new();
+    for (index, (src_key, dst_key)) in keys.iter().enumerate() {
+        if src_key != dst_key {
+            src_indices.entry(src_key).or_insert(index);
+        }
+    }
+
+    // Each move depends on (at most one) other move which has to vacate its destination first
+    let dependencies = keys
+        .iter()
+        .map(|(src_key, dst_key)| {
+            if src_key == dst_key {
+                None
+            } else {
+                src_indices.get(dst_key.as_path()).copied()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut used_keys = keys
+        .iter()
+        .flat_map(|(src_key, dst_key)| [src_key.clone(), dst_key.clone()])
+        .collect::<HashSet<_>>();
+
+    let mut pairs = pairs.into_iter().map(Some).collect::<Vec<_>>();
+    let mut states = vec![State::Pending; pairs.len()];
+    let mut ordered_pairs = Vec::with_capacity(pairs.len());
+
+    for start_index in 0..pairs.len() {
+        let mut chain = Vec::new();
+        let mut cycle_index = None;
+        let mut next_index = Some(start_index);
+
+        while let Some(index) = next_index {
+            match states[index] {
+                State::Done => break,
+                State::Visiting => {
+                    cycle_index = Some(index);
+                    break;
+                }
+                State::Pending => {
+                    states[index] = State::Visiting;
+                    chain.push(index);
+                    next_index = dependencies[index];
+                }
+            }
+        }
+
+        let temp_path = cycle_index.map(|index| {
+            let src_path = pairs[index].as_ref().map(|(src_path, _)| src_path.clone());
+            let src_path = src_path.expect("Pair in cycle should not be ordered yet");
+            let temp_path = make_temp_path(&src_path, working_dir, &mut used_keys);
+            ordered_pairs.push((src_path, temp_path.clone()));
+            temp_path
+        });
+
+        for index in chain.into_iter().rev() {
+            let (src_path, dst_path) = pairs[index].take().expect("Pair should be ordered once");
+
+            match &temp_path {
+                Some(temp_path) if cycle_index == Some(index) => {
+                    ordered_pairs.push((temp_path.clone(), dst_path))
+                }
+                _ => ordered_pairs.push((src_path, dst_path)),
+            }
+
+            states[index] = State::Done;
+        }
+    }
+
+    ordered_pairs
+}
+
+fn make_temp_path(
+    src_path: &Path,
+    working_dir: &Path,
+    used_keys: &mut HashSet<PathBuf>,
+) -> PathBuf {
+    let src_path = if src_path.file_name().is_some() {
+        src_path.to_path_buf()
+    } else {
+        normalize_path(src_path, working_dir)
+    };
+
+    let mut base_name = src_path.file_name().unwrap_or_default().to_owned();
+    base_name.push(TEMP_SUFFIX);
+
+    for number in 0.. {
+        let mut name = OsString::from(&base_name);
+        if number > 0 {
+            name.push(number.to_string());
+        }
+
+        let temp_path = src_path.with_file_name(name);
+        let temp_key = normalize_path(&temp_path, working_dir);
+
+        if fs::symlink_metadata(&temp_key).is_err() && used_keys.insert(temp_key) {
+            return temp_path;
+        }
+    }
+
+    unreachable!("Temporary path should have been found")
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(&[],                                     &[]                                                               ; "empty")]
+    #[test_case(&[("a", "b")],                           &[("a", "b")]                                                     ; "single")]
+    #[test_case(&[("a", "b"), ("c", "d")],               &[("a", "b"), ("c", "d")]                                         ; "independent")]
+    #[test_case(&[("a", "a"), ("b", "a")],               &[("a", "a"), ("b", "a")]                                         ; "same path")]
+    #[test_case(&[("a", "b"), ("b", "c")],               &[("b", "c"), ("a", "b")]                                         ; "chain")]
+    #[test_case(&[("a", "b"), ("b", "c"), ("c", "d")],   &[("c", "d"), ("b", "c"), ("a", "b")]                             ; "long chain")]
+    #[test_case(&[("b", "c"), ("x", "y"), ("a", "./b")], &[("b", "c"), ("x", "y"), ("a", "./b")]                           ; "ordered chain")]
+    #[test_case(&[("a", "b"), ("b", "a")],               &[("a", "a.mvb-tmp"), ("b", "a"), ("a.mvb-tmp", "b")]             ; "cycle")]
+    #[test_case(&[("a", "b"), ("b", "c"), ("c", "a")],   &[("a", "a.mvb-tmp"), ("c", "a"), ("b", "c"), ("a.mvb-tmp", "b")] ; "long cycle")]
+    #[test_case(&[("x", "a"), ("a", "b"), ("b", "a")],   &[("a", "a.mvb-tmp"), ("b", "a"), ("a.mvb-tmp", "b"), ("x", "a")] ; "cycle with tail")]
+    #[test_case(&[("d/a", "d/b"), ("d/b", "d/a")],       &[("d/a", "d/a.mvb-tmp"), ("d/b", "d/a"), ("d/a.mvb-tmp", "d/b")] ; "cycle in dir")]
+    fn order(pairs: &[(&str, &str)], result: &[(&str, &str)]) {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(
+            order_moves(make_pairs(pairs), dir.path()),
+            make_pairs(result)
+        );
+    }
+
+    #[test]
+    fn temp_path_collision() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a.mvb-tmp").touch().unwrap();
+
+        assert_eq!(
+            order_moves(
+                make_pairs(&[("a", "b"), ("b", "a"), ("c", "a.mvb-tmp1")]),
+                dir.path()
+            ),
+            make_pairs(&[
+                ("a", "a.mvb-tmp2"),
+                ("b", "a"),
+                ("a.mvb-tmp2", "b"),
+                ("c", "a.mvb-tmp1")
+            ])
+        );
+    }
+
+    fn make_pairs(pairs: &[(&str, &str)]) -> Vec<(PathBuf, PathBuf)> {
+        pairs
+            .iter()
+            .map(|(src_path, dst_path)| (PathBuf::from(src_path), PathBuf::from(dst_path)))
+            .collect()
+    }
+}
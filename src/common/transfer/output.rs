@@ -8,11 +8,15 @@ use crate::transfer::fs::TransferMode;
 
 pub struct TransferLog<O: Write + WriteColor> {
     output: O,
+    progress_width: usize, // Width of the progress line currently drawn in place
 }
 
 impl<O: Write + WriteColor> TransferLog<O> {
     pub fn new(output: O) -> Self {
-        Self { output }
+        Self {
+            output,
+            progress_width: 0,
+        }
     }
 
     pub fn begin_transfer(
@@ -37,6 +41,15 @@ impl<O: Write + WriteColor> TransferLog<O> {
         self.output.flush()
     }
 
+    pub fn begin_removal(&mut self, path: &Path) -> Result<()> {
+        write!(self.output, "Removing '")?;
+        self.output.set_color(&spec_color(Color::Blue))?;
+        write!(self.output, "{}", path.to_string_lossy())?;
+        self.output.reset()?;
+        write!(self.output, "' ... ")?;
+        self.output.flush()
+    }
+
     pub fn end_with_success(&mut self) -> Result<()> {
         self.end_transfer(Color::Green, "OK")
     }
@@ -45,12 +58,44 @@ impl<O: Write + WriteColor> TransferLog<O> {
         self.end_transfer(Color::Red, "FAILED")
     }
 
+    pub fn end_with_skip(&mut self) -> Result<()> {
+        self.end_transfer(Color::Yellow, "SKIPPED")
+    }
+
     pub fn end_transfer(&mut self, color: Color, result: &str) -> Result<()> {
         self.output.set_color(&spec_color(color))?;
         write!(self.output, "{}", result)?;
         self.output.reset()?;
         writeln!(self.output)
     }
+
+    pub fn draw_progress(&mut self, line: &str) -> Result<()> {
+        let width = line.chars().count();
+        let padding = self.progress_width.saturating_sub(width); // Overwrites rest of the previous line
+        write!(self.output, "\r{}{}", line, " ".repeat(padding))?;
+        self.progress_width = width;
+        self.output.flush()
+    }
+
+    pub fn clear_progress(&mut self) -> Result<()> {
+        if self.progress_width > 0 {
+            write!(self.output, "\r{}\r", " ".repeat(self.progress_width))?;
+            self.progress_width = 0;
+            self.output.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn end_progress(&mut self, line: &str) -> Result<()> {
+        self.draw_progress(line)?;
+        self.progress_width = 0;
+        writeln!(self.output)
+    }
+
+    pub fn print_progress(&mut self, line: &str) -> Result<()> {
+        writeln!(self.output, "{}", line)
+    }
 }
 
 #[cfg(test)]
@@ -66,7 +111,7 @@ pub mod tests {
         let mut output = ColoredOuput::new();
 
         TransferLog::new(&mut output)
-            .begin_transfer(mode, &Path::new("a/b.c"), &Path::new("d/e.f"))
+            .begin_transfer(mode, Path::new("a/b.c"), Path::new("d/e.f"))
             .unwrap();
 
         assert_eq!(
@@ -81,6 +126,24 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn begin_removal() {
+        let mut output = ColoredOuput::new();
+
+        TransferLog::new(&mut output)
+            .begin_removal(Path::new("a/b.c"))
+            .unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[
+                OutputChunk::plain("Removing '"),
+                OutputChunk::color(Color::Blue, "a/b.c"),
+                OutputChunk::plain("' ... ")
+            ]
+        );
+    }
+
     #[test]
     fn end_with_success() {
         let mut output = ColoredOuput::new();
@@ -108,4 +171,38 @@ pub mod tests {
             ]
         );
     }
+
+    #[test]
+    fn end_with_skip() {
+        let mut output = ColoredOuput::new();
+        TransferLog::new(&mut output).end_with_skip().unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[
+                OutputChunk::color(Color::Yellow, "SKIPPED"),
+                OutputChunk::plain("\n")
+            ]
+        );
+    }
+
+    #[test]
+    fn progress() {
+        let mut output = ColoredOuput::new();
+        let mut log = TransferLog::new(&mut output);
+
+        log.clear_progress().unwrap();
+        log.draw_progress("abcd").unwrap();
+        log.draw_progress("ab").unwrap();
+        log.clear_progress().unwrap();
+        log.draw_progress("abc").unwrap();
+        log.end_progress("a").unwrap();
+        log.clear_progress().unwrap();
+        log.print_progress("ab").unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[OutputChunk::plain("\rabcd\rab  \r  \r\rabc\ra  \nab\n")]
+        );
+    }
 }
@@ -0,0 +1,260 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Instant;
+use std::{io, mem, thread};
+
+use fs_extra::error::Result as FsResult;
+
+use crate::run::{Io, Result};
+use crate::transfer::batch::{Preparation, TransferBatch};
+use crate::transfer::fs::{normalize_path, transfer_path_with_progress, TransferMode};
+use crate::transfer::progress::{Progress, TERMINAL_INTERVAL};
+use crate::transfer::report::TransferStats;
+use crate::transfer::run::TransferOptions;
+
+pub fn parse_jobs(string: &str) -> std::result::Result<usize, &'static str> {
+    match string.parse() {
+        Ok(0) | Err(_) => Err("value must be a positive integer"),
+        Ok(jobs) => Ok(jobs),
+    }
+}
+
+enum State {
+    Skipped,
+    Running(Option<Vec<PathBuf>>),
+    Finished(Option<Vec<PathBuf>>, TransferStats, FsResult<()>),
+}
+
+type TransferResult = (usize, TransferStats, FsResult<()>);
+
+// Transfers run in worker threads, everything else (overwrite checks, logging, report, journal) in the calling thread.
+// A transfer is started only after all previous transfers it depends on are finished.
+// Results are reported in the input order, as if transfers were run sequentially.
+pub fn transfer_paths_parallel<O: TransferOptions>(
+    options: &O,
+    io: &Io,
+    mode: TransferMode,
+    pairs: &[(PathBuf, PathBuf)],
+    working_dir: &Path,
+    progress: Option<Progress>,
+) -> Result {
+    let keys = pairs
+        .iter()
+        .map(|(src_path, dst_path)| {
+            (
+                normalize_path(src_path, working_dir),
+                normalize_path(dst_path, working_dir),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let symlink_mode = options.symlink_mode();
+    let preserve = options.preserve();
+
+    let (job_sender, job_receiver) = mpsc::channel::<usize>();
+    let (result_sender, result_receiver) = mpsc::channel::<TransferResult>();
+    let job_receiver = Mutex::new(job_receiver);
+    let new_bytes = AtomicU64::new(0); // Copied by workers, but not yet reported
+
+    thread::scope(|scope| {
+        for _ in 0..options.jobs() {
+            let job_receiver = &job_receiver;
+            let result_sender = result_sender.clone();
+            let new_bytes = &new_bytes;
+
+            scope.spawn(move || loop {
+                // Lock is held only while waiting for a job, not during the transfer
+                let index = match job_receiver.lock().expect("Job lock poisoned").recv() {
+                    Ok(index) => index,
+                    Err(_) => break, // No more jobs
+                };
+
+                let (src_path, dst_path) = &pairs[index];
+                let mut stats = TransferStats::default();
+                let started = Instant::now();
+                let result = transfer_path_with_progress(
+                    src_path,
+                    dst_path,
+                    mode,
+                    symlink_mode,
+                    preserve,
+                    |progress| {
+                        stats.copied_bytes += progress.new_bytes;
+                        new_bytes.fetch_add(progress.new_bytes, Ordering::Relaxed);
+                    },
+                );
+
+                stats.duration = started.elapsed();
+
+                if result_sender.send((index, stats, result)).is_err() {
+                    break;
+                }
+            });
+        }
+
+        let mut batch = TransferBatch::new(options, io, mode, progress)?;
+        let mut states = Vec::<State>::with_capacity(pairs.len());
+        let mut running = Vec::<usize>::new();
+        let mut reported = 0;
+        let mut stopped = false;
+
+        for (index, (src_path, dst_path)) in pairs.iter().enumerate() {
+            while running
+                .iter()
+                .any(|&other_index| are_dependent(&keys[other_index], &keys[index]))
+            {
+                receive_result(
+                    &result_receiver,
+                    &new_bytes,
+                    &mut batch,
+                    &mut states,
+                    &mut running,
+                )?;
+                stopped |= !report_results(&mut batch, pairs, &mut states, &mut reported)?;
+            }
+
+            if stopped {
+                break;
+            }
+
+            match batch.prepare(src_path, dst_path)? {
+                Preparation::Skipped => states.push(State::Skipped),
+                Preparation::Failed(missing_dirs, error) => {
+                    let stats = TransferStats::default();
+                    states.push(State::Finished(missing_dirs, stats, Err(error)))
+                }
+                Preparation::Ready(missing_dirs) => {
+                    job_sender
+                        .send(index)
+                        .expect("Workers should be running while there are jobs");
+                    states.push(State::Running(missing_dirs));
+                    running.push(index);
+                }
+            }
+
+            stopped |= !report_results(&mut batch, pairs, &mut states, &mut reported)?;
+        }
+
+        drop(job_sender);
+
+        // Already started transfers are always finished and reported
+        while !running.is_empty() {
+            receive_result(
+                &result_receiver,
+                &new_bytes,
+                &mut batch,
+                &mut states,
+                &mut running,
+            )?;
+            report_results(&mut batch, pairs, &mut states, &mut reported)?;
+        }
+
+        batch.end()
+    })
+}
+
+fn receive_result<O: TransferOptions>(
+    receiver: &mpsc::Receiver<TransferResult>,
+    new_bytes: &AtomicU64,
+    batch: &mut TransferBatch<O>,
+    states: &mut [State],
+    running: &mut Vec<usize>,
+) -> io::Result<()> {
+    let (index, stats, result) = loop {
+        // Progress is refreshed while waiting for transfers
+        let received = receiver.recv_timeout(TERMINAL_INTERVAL);
+        batch.add_progress(new_bytes.swap(0, Ordering::Relaxed))?;
+
+        match received {
+            Ok(result) => break result,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => {
+                panic!("Workers should be running while there are running transfers")
+            }
+        }
+    };
+
+    running.retain(|&other_index| other_index != index);
+
+    if let State::Running(missing_dirs) = mem::replace(&mut states[index], State::Skipped) {
+        states[index] = State::Finished(missing_dirs, stats, result);
+    }
+
+    Ok(())
+}
+
+// Returns whether the batch should continue
+fn report_results<O: TransferOptions>(
+    batch: &mut TransferBatch<O>,
+    pairs: &[(PathBuf, PathBuf)],
+    states: &mut [State],
+    reported: &mut usize,
+) -> io::Result<bool> {
+    let mut proceed = true;
+
+    while let Some(state) = states.get_mut(*reported) {
+        if let State::Running(_) = state {
+            break;
+        }
+
+        let (src_path, dst_path) = pairs[*reported].clone();
+
+        match mem::replace(state, State::Skipped) {
+            State::Skipped => batch.skip(*reported, &src_path, &dst_path)?,
+            State::Finished(missing_dirs, stats, result) => {
+                batch.begin(&src_path, &dst_path)?;
+                proceed &=
+                    batch.finish(*reported, src_path, dst_path, missing_dirs, stats, result)?;
+            }
+            State::Running(_) => unreachable!("Running transfer should not be reported"),
+        }
+
+        *reported += 1;
+    }
+
+    Ok(proceed)
+}
+
+// Transfers are dependent when one of them writes to a path used by the other one (or to its parent/child path)
+fn are_dependent((src_a, dst_a): &(PathBuf, PathBuf), (src_b, dst_b): &(PathBuf, PathBuf)) -> bool {
+    are_nested(dst_a, dst_b) || are_nested(dst_a, src_b) || are_nested(src_a, dst_b)
+}
+
+fn are_nested(path_a: &Path, path_b: &Path) -> bool {
+    path_a.starts_with(path_b) || path_b.starts_with(path_a)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("",   Err("value must be a positive integer") ; "empty")]
+    #[test_case("x",  Err("value must be a positive integer") ; "invalid")]
+    #[test_case("-1", Err("value must be a positive integer") ; "negative")]
+    #[test_case("0",  Err("value must be a positive integer") ; "zero")]
+    #[test_case("1",  Ok(1)                                   ; "one")]
+    #[test_case("8",  Ok(8)                                   ; "more")]
+    fn parse_jobs(input: &str, result: std::result::Result<usize, &'static str>) {
+        assert_eq!(super::parse_jobs(input), result);
+    }
+
+    #[test_case(("/a", "/b"),   ("/c", "/d"),   false ; "independent")]
+    #[test_case(("/a", "/b"),   ("/a", "/c"),   false ; "same source")]
+    #[test_case(("/a", "/b/c"), ("/d", "/b/e"), false ; "same parent")]
+    #[test_case(("/a", "/b"),   ("/c", "/b"),   true  ; "same destination")]
+    #[test_case(("/a", "/b"),   ("/c", "/b/d"), true  ; "nested destination")]
+    #[test_case(("/a", "/b"),   ("/b", "/c"),   true  ; "destination is source")]
+    #[test_case(("/a", "/b"),   ("/b/d", "/c"), true  ; "destination is parent of source")]
+    #[test_case(("/a/d", "/b"), ("/c", "/a"),   true  ; "source is nested in destination")]
+    fn are_dependent(pair_a: (&str, &str), pair_b: (&str, &str), result: bool) {
+        let pair_a = (PathBuf::from(pair_a.0), PathBuf::from(pair_a.1));
+        let pair_b = (PathBuf::from(pair_b.0), PathBuf::from(pair_b.1));
+
+        assert_eq!(super::are_dependent(&pair_a, &pair_b), result);
+        assert_eq!(super::are_dependent(&pair_b, &pair_a), result);
+    }
+}
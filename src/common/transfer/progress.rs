@@ -0,0 +1,295 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use same_file::Handle;
+
+use crate::transfer::fs::{CopyProgress, FileType, SymlinkMode};
+use crate::utils::format_bytes;
+
+const BAR_WIDTH: usize = 20;
+
+pub const TERMINAL_INTERVAL: Duration = Duration::from_millis(100);
+pub const PLAIN_INTERVAL: Duration = Duration::from_secs(5);
+
+// Size of everything a transfer of the path copies (symlinks themselves have zero size)
+pub fn measure_size(path: &Path, symlink_mode: SymlinkMode) -> u64 {
+    measure_size_impl(path, symlink_mode, &mut Vec::new())
+}
+
+fn measure_size_impl(path: &Path, symlink_mode: SymlinkMode, dir_handles: &mut Vec<Handle>) -> u64 {
+    match FileType::of_source(path, symlink_mode) {
+        FileType::File => path.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+        FileType::Dir => {
+            let handle = match Handle::from_path(path) {
+                Ok(handle) => handle,
+                Err(_) => return 0,
+            };
+            if dir_handles.contains(&handle) {
+                return 0; // Loop of followed symlinks, the transfer is going to fail anyway
+            }
+
+            dir_handles.push(handle);
+            let size = match path.read_dir() {
+                Ok(entries) => entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| measure_size_impl(&entry.path(), symlink_mode, dir_handles))
+                    .sum(),
+                Err(_) => 0,
+            };
+            dir_handles.pop();
+            size
+        }
+        FileType::Symlink | FileType::Unknown => 0,
+    }
+}
+
+struct FileState {
+    name: String,
+    copied_bytes: u64,
+    total_bytes: u64,
+}
+
+// Progress is rendered as a bar redrawn in place on terminal, or as periodically printed lines otherwise.
+pub struct Progress {
+    sizes: Vec<u64>,
+    total_bytes: u64,
+    done_pairs: usize,
+    done_bytes: u64,
+    active_bytes: u64, // Bytes copied by unfinished transfers
+    file: Option<FileState>,
+    terminal: bool,
+    started: Instant,
+    rendered: Option<Instant>,
+}
+
+impl Progress {
+    pub fn new(sizes: Vec<u64>, terminal: bool) -> Self {
+        let started = Instant::now();
+
+        Self {
+            total_bytes: sizes.iter().sum(),
+            sizes,
+            done_pairs: 0,
+            done_bytes: 0,
+            active_bytes: 0,
+            file: None,
+            terminal,
+            started,
+            // Plain output does not need to start with an empty progress
+            rendered: if terminal { None } else { Some(started) },
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    pub fn add_bytes(&mut self, new_bytes: u64) {
+        self.active_bytes += new_bytes;
+    }
+
+    pub fn update_file(&mut self, progress: &CopyProgress) {
+        self.add_bytes(progress.new_bytes);
+        self.file = Some(FileState {
+            name: progress
+                .path
+                .file_name()
+                .unwrap_or(progress.path.as_os_str())
+                .to_string_lossy()
+                .into_owned(),
+            copied_bytes: progress.copied_bytes,
+            total_bytes: progress.total_bytes,
+        });
+    }
+
+    pub fn finish_pair(&mut self, index: usize, copied_bytes: u64) {
+        self.active_bytes = self.active_bytes.saturating_sub(copied_bytes);
+        self.done_bytes += self.sizes[index];
+        self.done_pairs += 1;
+        self.file = None;
+    }
+
+    // Returns progress line when it is time to render it again
+    pub fn poll(&mut self, force: bool) -> Option<String> {
+        let now = Instant::now();
+        let interval = if self.terminal {
+            TERMINAL_INTERVAL
+        } else {
+            PLAIN_INTERVAL
+        };
+
+        match self.rendered {
+            Some(rendered) if !force && now.duration_since(rendered) < interval => None,
+            _ => {
+                self.rendered = Some(now);
+                Some(self.format(now.duration_since(self.started)))
+            }
+        }
+    }
+
+    pub fn format(&self, elapsed: Duration) -> String {
+        let total_pairs = self.sizes.len();
+        let copied_bytes = (self.done_bytes + self.active_bytes).min(self.total_bytes);
+
+        let ratio = if self.total_bytes > 0 {
+            copied_bytes as f64 / self.total_bytes as f64
+        } else if total_pairs > 0 {
+            self.done_pairs as f64 / total_pairs as f64
+        } else {
+            1.0
+        };
+
+        let eta = if ratio > 0.0 {
+            format_duration(elapsed.mul_f64((1.0 - ratio) / ratio))
+        } else {
+            String::from("--:--")
+        };
+
+        let status = format!(
+            "{}/{} paths, {} / {} ({}%), ETA {}",
+            self.done_pairs,
+            total_pairs,
+            format_bytes(copied_bytes),
+            format_bytes(self.total_bytes),
+            (ratio * 100.0) as u32,
+            eta
+        );
+
+        if !self.terminal {
+            return format!("Progress: {}", status);
+        }
+
+        let filled = (ratio * BAR_WIDTH as f64) as usize;
+        let bar = format!("{}{}", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled));
+
+        match &self.file {
+            Some(file) => format!(
+                "[{}] {}, '{}' {}%",
+                bar,
+                status,
+                file.name,
+                file_percent(file.copied_bytes, file.total_bytes)
+            ),
+            None => format!("[{}] {}", bar, status),
+        }
+    }
+}
+
+fn file_percent(copied_bytes: u64, total_bytes: u64) -> u64 {
+    (copied_bytes * 100).checked_div(total_bytes).unwrap_or(100)
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, mins, secs)
+    } else {
+        format!("{:02}:{:02}", mins, secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test]
+    fn measure_size_files() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").write_str("abc").unwrap();
+        dir.child("b/c").write_str("de").unwrap();
+        dir.child("b/d/e").write_str("f").unwrap();
+        dir.child("b/f").create_dir_all().unwrap();
+
+        assert_eq!(super::measure_size(dir.path(), SymlinkMode::Keep), 6);
+        assert_eq!(super::measure_size(&dir.join("a"), SymlinkMode::Keep), 3);
+        assert_eq!(super::measure_size(&dir.join("b"), SymlinkMode::Keep), 3);
+        assert_eq!(super::measure_size(&dir.join("b/f"), SymlinkMode::Keep), 0);
+        assert_eq!(super::measure_size(&dir.join("x"), SymlinkMode::Keep), 0);
+    }
+
+    #[cfg(unix)]
+    #[test_case(SymlinkMode::Keep,     0 ; "keep")]
+    #[test_case(SymlinkMode::Retarget, 0 ; "retarget")]
+    #[test_case(SymlinkMode::Follow,   6 ; "follow")]
+    #[test_case(SymlinkMode::Skip,     0 ; "skip")]
+    fn measure_size_symlinks(symlink_mode: SymlinkMode, size: u64) {
+        let dir = TempDir::new().unwrap();
+        dir.child("a/b").write_str("abc").unwrap();
+        dir.child("c").create_dir_all().unwrap();
+        dir.child("c/d").symlink_to_file(dir.join("a/b")).unwrap();
+        dir.child("c/e").symlink_to_dir(dir.join("a")).unwrap();
+        dir.child("c/f").symlink_to_dir(dir.join("c")).unwrap(); // Loop
+
+        assert_eq!(super::measure_size(&dir.join("c"), symlink_mode), size);
+    }
+
+    #[test_case(0,     "00:00"    ; "zero")]
+    #[test_case(59,    "00:59"    ; "seconds")]
+    #[test_case(61,    "01:01"    ; "minutes")]
+    #[test_case(3600,  "1:00:00"  ; "hours")]
+    #[test_case(90061, "25:01:01" ; "days")]
+    fn format_duration(secs: u64, result: &str) {
+        assert_eq!(super::format_duration(Duration::from_secs(secs)), result);
+    }
+
+    #[test_case(false, "Progress: 0/2 paths, 0 B / 4.0 KiB (0%), ETA --:--"              ; "plain")]
+    #[test_case(true,  "[--------------------] 0/2 paths, 0 B / 4.0 KiB (0%), ETA --:--" ; "terminal")]
+    fn format_start(terminal: bool, result: &str) {
+        let progress = Progress::new(vec![1024, 3072], terminal);
+        assert_eq!(progress.format(Duration::from_secs(1)), result);
+    }
+
+    #[test_case(false, "Progress: 1/2 paths, 2.0 KiB / 4.0 KiB (50%), ETA 00:10"                       ; "plain")]
+    #[test_case(true,  "[##########----------] 1/2 paths, 2.0 KiB / 4.0 KiB (50%), ETA 00:10, 'c' 33%" ; "terminal")]
+    fn format_running(terminal: bool, result: &str) {
+        let mut progress = Progress::new(vec![1024, 3072], terminal);
+        progress.finish_pair(0, 0);
+        progress.update_file(&CopyProgress {
+            path: Path::new("a/b/c"),
+            copied_bytes: 1024,
+            total_bytes: 3072,
+            new_bytes: 1024,
+        });
+        assert_eq!(progress.format(Duration::from_secs(10)), result);
+    }
+
+    #[test_case(false, "Progress: 2/2 paths, 4.0 KiB / 4.0 KiB (100%), ETA 00:00"              ; "plain")]
+    #[test_case(true,  "[####################] 2/2 paths, 4.0 KiB / 4.0 KiB (100%), ETA 00:00" ; "terminal")]
+    fn format_end(terminal: bool, result: &str) {
+        let mut progress = Progress::new(vec![1024, 3072], terminal);
+        progress.add_bytes(3072);
+        progress.finish_pair(1, 3072);
+        progress.finish_pair(0, 0);
+        assert_eq!(progress.format(Duration::from_secs(10)), result);
+    }
+
+    #[test_case(0, "Progress: 0/2 paths, 0 B / 0 B (0%), ETA --:--"   ; "start")]
+    #[test_case(1, "Progress: 1/2 paths, 0 B / 0 B (50%), ETA 00:10"  ; "running")]
+    #[test_case(2, "Progress: 2/2 paths, 0 B / 0 B (100%), ETA 00:00" ; "end")]
+    fn format_empty(done_pairs: usize, result: &str) {
+        let mut progress = Progress::new(vec![0, 0], false);
+        for index in 0..done_pairs {
+            progress.finish_pair(index, 0);
+        }
+        assert_eq!(progress.format(Duration::from_secs(10)), result);
+    }
+
+    #[test]
+    fn poll() {
+        let mut progress = Progress::new(vec![0], false);
+        assert_eq!(progress.poll(false), None);
+        assert!(progress.poll(true).is_some());
+        assert_eq!(progress.poll(false), None);
+
+        let mut progress = Progress::new(vec![0], true);
+        assert!(progress.poll(false).is_some());
+        assert_eq!(progress.poll(false), None);
+        assert!(progress.poll(true).is_some());
+    }
+}
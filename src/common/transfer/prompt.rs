@@ -0,0 +1,107 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error, Result, Write};
+use std::path::Path;
+
+use termcolor::{Color, WriteColor};
+
+use crate::color::spec_color;
+
+#[cfg(unix)]
+const TERMINAL_PATH: &str = "/dev/tty";
+#[cfg(windows)]
+const TERMINAL_PATH: &str = "CONIN$";
+
+// Standard input is occupied by instructions, so answers are read directly from terminal.
+#[derive(Default)]
+pub struct Prompt {
+    terminal: Option<BufReader<File>>,
+}
+
+impl Prompt {
+    pub fn confirm_overwrite<O: Write + WriteColor>(
+        &mut self,
+        output: &mut O,
+        src_path: &Path,
+        dst_path: &Path,
+    ) -> Result<bool> {
+        let terminal = match &mut self.terminal {
+            Some(terminal) => terminal,
+            None => self.terminal.insert(open_terminal()?),
+        };
+        ask_overwrite(output, terminal, src_path, dst_path)
+    }
+}
+
+pub fn ask_overwrite<O: Write + WriteColor, I: BufRead>(
+    output: &mut O,
+    input: &mut I,
+    src_path: &Path,
+    dst_path: &Path,
+) -> Result<bool> {
+    write!(output, "Overwrite '")?;
+    output.set_color(&spec_color(Color::Blue))?;
+    write!(output, "{}", dst_path.to_string_lossy())?;
+    output.reset()?;
+    write!(output, "' with '")?;
+    output.set_color(&spec_color(Color::Blue))?;
+    write!(output, "{}", src_path.to_string_lossy())?;
+    output.reset()?;
+    write!(output, "'? [y/N] ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn open_terminal() -> Result<BufReader<File>> {
+    match File::open(TERMINAL_PATH) {
+        Ok(file) => Ok(BufReader::new(file)),
+        Err(error) => Err(Error::new(
+            error.kind(),
+            format!("Cannot open terminal to prompt user: {}", error),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::testing::{ColoredOuput, OutputChunk};
+
+    #[test_case("",      false ; "eof")]
+    #[test_case("\n",    false ; "empty")]
+    #[test_case("n\n",   false ; "no")]
+    #[test_case("x\n",   false ; "other")]
+    #[test_case("y\n",   true  ; "y")]
+    #[test_case("Y\n",   true  ; "y uppercase")]
+    #[test_case(" yes ", true  ; "yes")]
+    fn ask_overwrite(input: &str, result: bool) {
+        let mut output = ColoredOuput::new();
+
+        assert_eq!(
+            super::ask_overwrite(
+                &mut output,
+                &mut input.as_bytes(),
+                Path::new("a"),
+                Path::new("b")
+            )
+            .unwrap(),
+            result
+        );
+
+        assert_eq!(
+            output.chunks(),
+            &[
+                OutputChunk::plain("Overwrite '"),
+                OutputChunk::color(Color::Blue, "b"),
+                OutputChunk::plain("' with '"),
+                OutputChunk::color(Color::Blue, "a"),
+                OutputChunk::plain("'? [y/N] "),
+            ]
+        );
+    }
+}
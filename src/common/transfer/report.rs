@@ -0,0 +1,174 @@
+use std::io::{Result, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::transfer::fs::TransferMode;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum TransferStatus {
+    Ok,
+    Failed,
+    Skipped,
+}
+
+// Bytes copied by a transfer (zero when paths were only renamed) and its duration
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+pub struct TransferStats {
+    pub copied_bytes: u64,
+    pub duration: Duration,
+}
+
+// Machine-readable alternative to `TransferLog`, one JSON object per line.
+pub struct TransferReport<O: Write> {
+    output: O,
+    mode: TransferMode,
+    started: Instant,
+    ok_count: usize,
+    failed_count: usize,
+    skipped_count: usize,
+    copied_bytes: u64,
+}
+
+impl<O: Write> TransferReport<O> {
+    pub fn new(output: O, mode: TransferMode) -> Self {
+        Self {
+            output,
+            mode,
+            started: Instant::now(),
+            ok_count: 0,
+            failed_count: 0,
+            skipped_count: 0,
+            copied_bytes: 0,
+        }
+    }
+
+    pub fn write_transfer(
+        &mut self,
+        src_path: &Path,
+        dst_path: &Path,
+        status: TransferStatus,
+        error: Option<&str>,
+        stats: TransferStats,
+    ) -> Result<()> {
+        match status {
+            TransferStatus::Ok => self.ok_count += 1,
+            TransferStatus::Failed => self.failed_count += 1,
+            TransferStatus::Skipped => self.skipped_count += 1,
+        }
+        self.copied_bytes += stats.copied_bytes;
+
+        let action = match self.mode {
+            TransferMode::Move => "move",
+            TransferMode::Copy => "copy",
+        };
+        let status = match status {
+            TransferStatus::Ok => "ok",
+            TransferStatus::Failed => "failed",
+            TransferStatus::Skipped => "skipped",
+        };
+
+        self.write_record(json!({
+            "type": "transfer",
+            "src": src_path.to_string_lossy(),
+            "dst": dst_path.to_string_lossy(),
+            "action": action,
+            "status": status,
+            "error": error,
+            "bytes": stats.copied_bytes,
+            "duration_ms": stats.duration.as_millis() as u64,
+        }))
+    }
+
+    pub fn write_summary(&mut self, rolled_back: bool) -> Result<()> {
+        self.write_summary_with_duration(self.started.elapsed(), rolled_back)
+    }
+
+    fn write_summary_with_duration(&mut self, duration: Duration, rolled_back: bool) -> Result<()> {
+        self.write_record(json!({
+            "type": "summary",
+            "total": self.ok_count + self.failed_count + self.skipped_count,
+            "ok": self.ok_count,
+            "failed": self.failed_count,
+            "skipped": self.skipped_count,
+            "bytes": self.copied_bytes,
+            "duration_ms": duration.as_millis() as u64,
+            "rolled_back": rolled_back,
+        }))
+    }
+
+    fn write_record(&mut self, record: Value) -> Result<()> {
+        serde_json::to_writer(&mut self.output, &record)?;
+        writeln!(self.output)?;
+        self.output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(TransferMode::Move, TransferStatus::Ok,      None,                 r#""action":"move","status":"ok","error":null"#               ; "move ok")]
+    #[test_case(TransferMode::Copy, TransferStatus::Ok,      None,                 r#""action":"copy","status":"ok","error":null"#               ; "copy ok")]
+    #[test_case(TransferMode::Copy, TransferStatus::Failed,  Some("Bad \"path\""), r#""action":"copy","status":"failed","error":"Bad \"path\"""# ; "failed")]
+    #[test_case(TransferMode::Copy, TransferStatus::Failed,  Some("a\nb\0"),       r#""action":"copy","status":"failed","error":"a\nb\u0000""#   ; "failed control chars")]
+    #[test_case(TransferMode::Copy, TransferStatus::Skipped, None,                 r#""action":"copy","status":"skipped","error":null"#          ; "skipped")]
+    fn write_transfer(
+        mode: TransferMode,
+        status: TransferStatus,
+        error: Option<&str>,
+        result: &str,
+    ) {
+        let mut output = Vec::new();
+        let stats = TransferStats {
+            copied_bytes: 1024,
+            duration: Duration::from_millis(15),
+        };
+
+        TransferReport::new(&mut output, mode)
+            .write_transfer(Path::new("a/b"), Path::new("c\\d"), status, error, stats)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "{{\"type\":\"transfer\",\"src\":\"a/b\",\"dst\":\"c\\\\d\",{},\"bytes\":1024,\"duration_ms\":15}}\n",
+                result
+            )
+        );
+    }
+
+    #[test]
+    fn write_summary() {
+        let mut output = Vec::new();
+        let mut report = TransferReport::new(&mut output, TransferMode::Copy);
+        let path = Path::new("a");
+
+        for (status, copied_bytes) in [
+            (TransferStatus::Ok, 10),
+            (TransferStatus::Ok, 20),
+            (TransferStatus::Failed, 5),
+            (TransferStatus::Skipped, 0),
+        ] {
+            let stats = TransferStats {
+                copied_bytes,
+                duration: Duration::default(),
+            };
+            report
+                .write_transfer(path, path, status, None, stats)
+                .unwrap();
+        }
+
+        report
+            .write_summary_with_duration(Duration::from_millis(1500), true)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap().lines().last().unwrap(),
+            r#"{"type":"summary","total":4,"ok":2,"failed":1,"skipped":1,"bytes":35,"duration_ms":1500,"rolled_back":true}"#
+        );
+    }
+}
@@ -1,59 +1,194 @@
+use std::env;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
 use crate::input::Terminator;
 use crate::output::write_error;
-use crate::run::{Io, Options, Result, EXIT_CODE_IO_ERROR, EXIT_CODE_OK};
-use crate::transfer::fs::{transfer_path, TransferMode};
+use crate::run::{Io, Options, Result, EXIT_CODE_OK};
+use crate::transfer::batch::{Preparation, TransferBatch};
+use crate::transfer::check::check_transfers;
+use crate::transfer::fs::{
+    transfer_path_with_progress, OverwritePolicy, SymlinkMode, TransferMode,
+};
 use crate::transfer::input::PathDiff;
+use crate::transfer::order::order_moves;
 use crate::transfer::output::TransferLog;
+use crate::transfer::parallel::transfer_paths_parallel;
+use crate::transfer::progress::{measure_size, Progress};
+use crate::transfer::report::{TransferReport, TransferStats, TransferStatus};
+use crate::transfer::undo::run_undo;
+
+pub const EXIT_CODE_CHECK_ERROR: i32 = 3;
 
 pub trait TransferOptions {
     fn read_nul(&self) -> bool;
     fn verbose(&self) -> bool;
     fn fail_at_end(&self) -> bool;
+    fn check(&self) -> bool;
+    fn dry_run(&self) -> bool;
+    fn journal(&self) -> Option<&Path>;
+    fn undo(&self) -> Option<&Path>;
+    fn overwrite_policy(&self) -> OverwritePolicy;
+    fn preserve(&self) -> bool;
+    fn symlink_mode(&self) -> SymlinkMode;
+    fn progress(&self) -> bool;
+    fn json_lines(&self) -> bool;
+
+    fn transaction(&self) -> bool {
+        false
+    }
+
+    fn jobs(&self) -> usize {
+        1
+    }
 }
 
 pub fn run_transfer<O>(options: &O, io: &Io, mode: TransferMode) -> Result
 where
     O: Options + TransferOptions,
 {
-    let terminator = if options.read_nul() {
+    if let Some(journal_path) = options.undo() {
+        let journal = io::BufReader::new(File::open(journal_path)?);
+        return run_undo(options, io, mode, journal, make_terminator(options));
+    }
+
+    let path_diff = PathDiff::new(io.stdin(), make_terminator(options));
+    let checked = options.check() || options.dry_run();
+    let parallel = options.jobs() > 1;
+
+    // Progress needs to know all paths in advance
+    if let (TransferMode::Copy, false, false, false) = (mode, checked, parallel, options.progress())
+    {
+        return transfer_paths(options, io, mode, path_diff, None);
+    }
+
+    let mut pairs = path_diff.collect::<io::Result<Vec<_>>>()?;
+    let working_dir = env::current_dir()?;
+
+    if let TransferMode::Move = mode {
+        pairs = order_moves(pairs, &working_dir);
+    }
+
+    if checked {
+        let errors = check_transfers(&pairs, mode, options.symlink_mode(), &working_dir);
+
+        if !errors.is_empty() {
+            for error in &errors {
+                write_error(&mut io.stderr(), error)?;
+            }
+            return Ok(EXIT_CODE_CHECK_ERROR);
+        }
+
+        if options.dry_run() {
+            if options.verbose() {
+                let mut log = TransferLog::new(io.stdout());
+
+                for (src_path, dst_path) in &pairs {
+                    log.begin_transfer(mode, src_path, dst_path)?;
+                    log.end_with_skip()?;
+                }
+            } else if options.json_lines() {
+                let mut report = TransferReport::new(io.stdout(), mode);
+                let stats = TransferStats::default();
+
+                for (src_path, dst_path) in &pairs {
+                    report.write_transfer(
+                        src_path,
+                        dst_path,
+                        TransferStatus::Skipped,
+                        None,
+                        stats,
+                    )?;
+                }
+                report.write_summary(false)?;
+            }
+            return Ok(EXIT_CODE_OK);
+        }
+    }
+
+    let progress = if options.progress() {
+        let sizes = pairs
+            .iter()
+            .map(|(src_path, _)| measure_size(src_path, options.symlink_mode()))
+            .collect();
+        Some(Progress::new(sizes, atty::is(atty::Stream::Stdout)))
+    } else {
+        None
+    };
+
+    if parallel {
+        transfer_paths_parallel(options, io, mode, &pairs, &working_dir, progress)
+    } else {
+        transfer_paths(options, io, mode, pairs.into_iter().map(Ok), progress)
+    }
+}
+
+pub fn make_terminator<O: TransferOptions>(options: &O) -> Terminator {
+    if options.read_nul() {
         Terminator::Byte {
             value: 0,
             required: false,
         }
     } else {
         Terminator::Newline { required: false }
-    };
+    }
+}
 
-    let mut path_diff = PathDiff::new(io.stdin(), terminator);
-    let mut log = TransferLog::new(io.stdout());
-    let mut exit_code = EXIT_CODE_OK;
+fn transfer_paths<O, P>(
+    options: &O,
+    io: &Io,
+    mode: TransferMode,
+    pairs: P,
+    progress: Option<Progress>,
+) -> Result
+where
+    O: TransferOptions,
+    P: IntoIterator<Item = io::Result<(PathBuf, PathBuf)>>,
+{
+    let mut batch = TransferBatch::new(options, io, mode, progress)?;
 
-    while let Some((src_path, dst_path)) = path_diff.read()? {
-        if options.verbose() {
-            log.begin_transfer(mode, &src_path, &dst_path)?;
-        }
+    for (index, pair) in pairs.into_iter().enumerate() {
+        let (src_path, dst_path) = pair?;
+        let mut stats = TransferStats::default();
 
-        match transfer_path(&src_path, &dst_path, mode) {
-            Ok(()) => {
-                if options.verbose() {
-                    log.end_with_success()?;
-                }
+        let (missing_dirs, result) = match batch.prepare(&src_path, &dst_path)? {
+            Preparation::Skipped => {
+                batch.skip(index, &src_path, &dst_path)?;
+                continue;
             }
-            Err(error) => {
-                if options.verbose() {
-                    log.end_with_failure()?;
-                }
-
-                write_error(&mut io.stderr(), &error)?;
-
-                if options.fail_at_end() {
-                    exit_code = EXIT_CODE_IO_ERROR;
-                } else {
-                    return Ok(EXIT_CODE_IO_ERROR);
-                }
+            Preparation::Failed(missing_dirs, error) => {
+                batch.begin(&src_path, &dst_path)?;
+                (missing_dirs, Err(error))
             }
+            Preparation::Ready(missing_dirs) => {
+                batch.begin(&src_path, &dst_path)?;
+                let mut progress_result = Ok(());
+                let started = Instant::now();
+                let result = transfer_path_with_progress(
+                    &src_path,
+                    &dst_path,
+                    mode,
+                    options.symlink_mode(),
+                    options.preserve(),
+                    |copy_progress| {
+                        stats.copied_bytes += copy_progress.new_bytes;
+                        if progress_result.is_ok() {
+                            progress_result = batch.update_progress(copy_progress);
+                        }
+                    },
+                );
+                stats.duration = started.elapsed();
+                progress_result?;
+                (missing_dirs, result)
+            }
+        };
+
+        if !batch.finish(index, src_path, dst_path, missing_dirs, stats, result)? {
+            break;
         }
     }
 
-    Ok(exit_code)
+    batch.end()
 }
@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use fs_extra::error::{Error, ErrorKind};
+
+use termcolor::WriteColor;
+
+use crate::input::Terminator;
+use crate::output::write_error;
+use crate::run::{Io, Result, EXIT_CODE_IO_ERROR, EXIT_CODE_OK};
+use crate::transfer::fs::{
+    remove_empty_dir, remove_path, rename_path, transfer_path, SymlinkMode, TransferMode,
+};
+use crate::transfer::journal::{read_journal, Journal, JournalEntry};
+use crate::transfer::output::TransferLog;
+use crate::transfer::run::TransferOptions;
+
+pub fn run_undo<O, I>(
+    options: &O,
+    io: &Io,
+    mode: TransferMode,
+    journal: I,
+    terminator: Terminator,
+) -> Result
+where
+    O: TransferOptions,
+    I: BufRead,
+{
+    let entries = read_journal(journal, terminator)?;
+    let mut log = TransferLog::new(io.stdout());
+    let undo = Undo::new(io, &mut log, mode, options, options.fail_at_end());
+    undo.run(&entries, None::<&mut Journal<io::Sink>>)
+}
+
+pub struct Undo<'a, L: Write + WriteColor> {
+    io: &'a Io,
+    log: &'a mut TransferLog<L>,
+    mode: TransferMode,
+    verbose: bool,
+    preserve: bool,
+    fail_at_end: bool,
+}
+
+impl<'a, L: Write + WriteColor> Undo<'a, L> {
+    pub fn new<O: TransferOptions>(
+        io: &'a Io,
+        log: &'a mut TransferLog<L>,
+        mode: TransferMode,
+        options: &O,
+        fail_at_end: bool,
+    ) -> Self {
+        Self {
+            io,
+            log,
+            mode,
+            verbose: options.verbose(),
+            preserve: options.preserve(),
+            fail_at_end,
+        }
+    }
+
+    // Reverted moves are recorded in the journal (if any), so it stays consistent with FS.
+    pub fn run<W: Write>(
+        self,
+        entries: &[JournalEntry],
+        mut journal: Option<&mut Journal<W>>,
+    ) -> Result {
+        let mut exit_code = EXIT_CODE_OK;
+
+        let overwritten_paths = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                JournalEntry::Overwrite(path) => Some(path.as_path()),
+                _ => None,
+            })
+            .collect::<HashSet<&Path>>();
+
+        for entry in entries.iter().rev() {
+            let result = match entry {
+                JournalEntry::Transfer(src_path, dst_path) => match self.mode {
+                    TransferMode::Move => {
+                        if self.verbose {
+                            self.log.begin_transfer(self.mode, dst_path, src_path)?;
+                        }
+                        let result = transfer_path(
+                            dst_path,
+                            src_path,
+                            self.mode,
+                            SymlinkMode::Keep, // Whatever is at the destination is moved back as it is
+                            self.preserve,
+                        );
+                        if let (Ok(()), Some(journal)) = (&result, &mut journal) {
+                            journal.record_transfer(&[], dst_path, src_path)?;
+                        }
+                        result.map(|_| true)
+                    }
+                    TransferMode::Copy => {
+                        if self.verbose {
+                            self.log.begin_removal(dst_path)?;
+                        }
+                        if overwritten_paths.contains(dst_path.as_path()) {
+                            // Original content is lost, removal would not restore anything
+                            Err(Error::new(
+                                ErrorKind::Other,
+                                &format!(
+                                    "Path '{}' replaced an existing path and cannot be removed",
+                                    dst_path.to_string_lossy()
+                                ),
+                            ))
+                        } else {
+                            remove_path(dst_path).map(|_| true)
+                        }
+                    }
+                },
+                JournalEntry::Overwrite(_) => continue, // Only affects undo of the transfer
+                JournalEntry::Backup(path, backup_path) => {
+                    if self.verbose {
+                        self.log
+                            .begin_transfer(TransferMode::Move, backup_path, path)?;
+                    }
+                    rename_path(backup_path, path).map(|_| true)
+                }
+                JournalEntry::Dir(dir_path) => {
+                    if self.verbose {
+                        self.log.begin_removal(dir_path)?;
+                    }
+                    remove_empty_dir(dir_path) // Directory is kept when something else was put there
+                }
+            };
+
+            match result {
+                Ok(true) => {
+                    if self.verbose {
+                        self.log.end_with_success()?;
+                    }
+                }
+                Ok(false) => {
+                    if self.verbose {
+                        self.log.end_with_skip()?;
+                    }
+                }
+                Err(error) => {
+                    if self.verbose {
+                        self.log.end_with_failure()?;
+                    }
+
+                    write_error(&mut self.io.stderr(), &error)?;
+
+                    if self.fail_at_end {
+                        exit_code = EXIT_CODE_IO_ERROR;
+                    } else {
+                        return Ok(EXIT_CODE_IO_ERROR);
+                    }
+                }
+            }
+        }
+
+        Ok(exit_code)
+    }
+}
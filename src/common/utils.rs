@@ -1,19 +1,28 @@
 use std::io::{Error, ErrorKind, Result};
 
-static mut STATIC_STRINGS: Vec<String> = Vec::new();
-
 pub fn into_static_str(value: String) -> &'static str {
-    unsafe {
-        // Well, this is ugly but the current usage should be actually safe:
-        // 1) It's used only by cli.rs to generate static strings for clap attributes.
-        // 2) Values are never modified after being pushed to vector.
-        // 3) Vectors is only modified / accessed by a single thread.
-        STATIC_STRINGS.push(value);
-        STATIC_STRINGS
-            .last()
-            .expect("Expected at least one static string result")
-            .as_str()
+    // Only used by cli.rs to generate static strings for clap attributes,
+    // so leaking a few strings for the lifetime of the process is fine.
+    Box::leak(value.into_boxed_str())
+}
+
+// Human-readable size with binary units
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
     }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
 }
 
 pub fn str_from_utf8(data: &[u8]) -> Result<&str> {
@@ -31,6 +40,8 @@ pub fn str_from_utf8(data: &[u8]) -> Result<&str> {
 
 #[cfg(test)]
 mod tests {
+    use test_case::test_case;
+
     use super::*;
 
     #[test] // Do not use test_case, this is expected to work only for a single thread.
@@ -44,6 +55,18 @@ mod tests {
         assert_eq!(str_3, "abc");
     }
 
+    #[test_case(0,               "0 B"        ; "zero")]
+    #[test_case(1023,            "1023 B"     ; "bytes")]
+    #[test_case(1024,            "1.0 KiB"    ; "kibibytes")]
+    #[test_case(1536,            "1.5 KiB"    ; "kibibytes fraction")]
+    #[test_case(5 * 1024 * 1024, "5.0 MiB"    ; "mebibytes")]
+    #[test_case(3 << 30,         "3.0 GiB"    ; "gibibytes")]
+    #[test_case(2 << 40,         "2.0 TiB"    ; "tebibytes")]
+    #[test_case(2048 << 50,      "2048.0 PiB" ; "max unit")]
+    fn format_bytes(bytes: u64, result: &str) {
+        assert_eq!(super::format_bytes(bytes), result);
+    }
+
     mod str_from_utf8 {
         use super::*;
         use crate::testing::unpack_io_error;
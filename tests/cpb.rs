@@ -3,7 +3,7 @@ mod utils;
 
 use assert_fs::prelude::*;
 use predicates::prelude::*;
-use utils::{cpb, temp_dir, write};
+use utils::{absolute_path, cpb, temp_dir, write};
 
 #[test]
 fn no_input() {
@@ -196,6 +196,632 @@ mod verbose {
     }
 }
 
+mod overwrite {
+    use super::*;
+
+    #[test]
+    fn skip() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--overwrite=skip")
+            .arg("--verbose")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("Copying 'a' to 'b' ... SKIPPED\n")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+    }
+
+    #[test]
+    fn fail() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--overwrite=fail")
+            .write_stdin("<a\n>b")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'b' already exists\n");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+    }
+
+    #[test]
+    fn backup_numbered() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+        let backup_file_1 = write(dir.child("b.~1~"), "3");
+        let backup_file_2 = dir.child("b.~2~");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--overwrite=backup")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("1");
+        backup_file_1.assert("3");
+        backup_file_2.assert("2");
+    }
+
+    #[test]
+    fn backup_suffix() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+        let backup_file = dir.child("b.bak");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--overwrite=backup")
+            .arg("--backup-suffix=.bak")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("1");
+        backup_file.assert("2");
+    }
+
+    #[test]
+    fn backup_undo() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+        let backup_file = dir.child("b.~1~");
+        let journal_file = dir.child("journal");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--overwrite=backup")
+            .arg("--journal=journal")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success();
+
+        journal_file.assert(format!(
+            "~{}\n>{}\n<{}\n>{}\n",
+            absolute_path(&dir, "b"),
+            absolute_path(&dir, "b.~1~"),
+            absolute_path(&dir, "a"),
+            absolute_path(&dir, "b")
+        ));
+
+        cpb()
+            .arg("--undo")
+            .arg(journal_file.path())
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+        backup_file.assert(predicates::path::missing());
+    }
+}
+
+mod preserve {
+    use filetime::FileTime;
+
+    use super::*;
+
+    #[test]
+    fn timestamps() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b");
+        let mtime = FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(src_file.path(), mtime).unwrap();
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--preserve")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dst_file.assert("1");
+        assert_eq!(
+            FileTime::from_last_modification_time(&dst_file.path().metadata().unwrap()),
+            mtime
+        );
+    }
+}
+
+#[cfg(unix)]
+mod symlinks {
+    use std::fs::read_link;
+    use std::os::unix::fs::symlink;
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn keep() {
+        let dir = temp_dir();
+        symlink("x", dir.child("a")).unwrap(); // Dangling
+
+        cpb()
+            .current_dir(dir.path())
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        assert_eq!(read_link(dir.child("b")).unwrap(), Path::new("x"));
+    }
+
+    #[test]
+    fn follow() {
+        let dir = temp_dir();
+        write(dir.child("a"), "1");
+        symlink("a", dir.child("b")).unwrap();
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--symlinks=follow")
+            .write_stdin("<b\n>c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dir.child("c").assert("1");
+        dir.child("c").assert(predicate::path::is_symlink().not());
+    }
+
+    #[test]
+    fn skip() {
+        let dir = temp_dir();
+        write(dir.child("a"), "1");
+        symlink("a", dir.child("b")).unwrap();
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--symlinks=skip")
+            .arg("--verbose")
+            .write_stdin("<a\n>c\n<b\n>d")
+            .assert()
+            .success()
+            .stdout("Copying 'a' to 'c' ... OK\nCopying 'b' to 'd' ... SKIPPED\n")
+            .stderr("");
+
+        dir.child("c").assert("1");
+        dir.child("d").assert(predicate::path::missing());
+    }
+}
+
+mod jobs {
+    use super::*;
+
+    #[test]
+    fn ordered_output() {
+        let dir = temp_dir();
+        let mut input = String::new();
+        let mut output = String::new();
+
+        for index in 0..20 {
+            write(dir.child(format!("a{}", index)), &index.to_string());
+            input.push_str(&format!("<a{}\n>b{}\n", index, index));
+            output.push_str(&format!("Copying 'a{}' to 'b{}' ... OK\n", index, index));
+        }
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--jobs=4")
+            .arg("--verbose")
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout(output)
+            .stderr("");
+
+        for index in 0..20 {
+            dir.child(format!("b{}", index)).assert(index.to_string());
+        }
+    }
+
+    #[test]
+    fn dependent() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+        write(dir.child("b"), "2");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--jobs=2")
+            .write_stdin("<a\n>b\n<b\n>c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dir.child("b").assert("1");
+        dir.child("c").assert("1");
+    }
+
+    #[test]
+    fn failure_at_end() {
+        let dir = temp_dir();
+
+        write(dir.child("a2"), "2");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--jobs=2")
+            .arg("--fail-at-end")
+            .arg("--verbose")
+            .write_stdin("<a1\n>b1\n<a2\n>b2")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("Copying 'a1' to 'b1' ... FAILED\nCopying 'a2' to 'b2' ... OK\n")
+            .stderr("error: Path 'a1' not found or user lacks permission\n");
+
+        dir.child("b1").assert(predicates::path::missing());
+        dir.child("b2").assert("2");
+    }
+}
+
+mod check {
+    use super::*;
+
+    #[test]
+    fn success() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("1");
+    }
+
+    #[test]
+    fn failure() {
+        let dir = temp_dir();
+
+        let src_file_1 = dir.child("a1");
+        let src_file_2 = write(dir.child("a2"), "2");
+        let src_file_3 = write(dir.child("a3"), "3");
+
+        let dst_file_2 = dir.child("b2");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a1\n>b1\n<a2\n>b2\n<a3\n>b2")
+            .assert()
+            .failure()
+            .code(3)
+            .stdout("")
+            .stderr(concat!(
+                "error: Path 'a1' not found or user lacks permission\n",
+                "error: Path 'b2' is a destination of both 'a2' and 'a3'\n"
+            ));
+
+        src_file_1.assert(predicates::path::missing());
+        src_file_2.assert("2");
+        src_file_3.assert("3");
+
+        dst_file_2.assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn dry_run() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--dry-run")
+            .arg("--verbose")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("Copying 'a' to 'b' ... SKIPPED\n")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert(predicates::path::missing());
+    }
+}
+
+mod journal {
+    use super::*;
+
+    #[test]
+    fn undo() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b/c/d");
+        let journal_file = dir.child("journal");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--journal=journal")
+            .write_stdin("<a\n>b/c/d")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("1");
+        journal_file.assert(format!(
+            "+{}\n+{}\n<{}\n>{}\n",
+            absolute_path(&dir, "b"),
+            absolute_path(&dir, "b/c"),
+            absolute_path(&dir, "a"),
+            absolute_path(&dir, "b/c/d")
+        ));
+
+        cpb()
+            .arg("--undo")
+            .arg(journal_file.path())
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stdout(format!(
+                "Removing '{}' ... OK\nRemoving '{}' ... OK\nRemoving '{}' ... OK\n",
+                absolute_path(&dir, "b/c/d"),
+                absolute_path(&dir, "b/c"),
+                absolute_path(&dir, "b")
+            ))
+            .stderr("");
+
+        src_file.assert("1");
+        dir.child("b").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn undo_keeps_nonempty_dir() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let journal_file = write(dir.child("journal"), "+b\n<a\n>b/c\n");
+        let other_file = write(dir.child("b/d"), "2");
+        write(dir.child("b/c"), "1");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--undo")
+            .arg(journal_file.path())
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        other_file.assert("2");
+        dir.child("b/c").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn undo_keeps_overwritten() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+        let journal_file = dir.child("journal");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--journal=journal")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success();
+
+        journal_file.assert(format!(
+            "!{}\n<{}\n>{}\n",
+            absolute_path(&dir, "b"),
+            absolute_path(&dir, "a"),
+            absolute_path(&dir, "b")
+        ));
+
+        cpb()
+            .arg("--undo")
+            .arg(journal_file.path())
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr(format!(
+                "error: Path '{}' replaced an existing path and cannot be removed\n",
+                absolute_path(&dir, "b")
+            ));
+
+        src_file.assert("1");
+        dst_file.assert("1");
+    }
+
+    #[test]
+    fn undo_failure() {
+        let dir = temp_dir();
+
+        write(dir.child("journal"), "<a\n>b\n");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--undo=journal")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'b' not found or user lacks permission\n");
+    }
+}
+
+mod progress {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+        write(dir.child("b/c"), "23");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--progress")
+            .write_stdin("<a\n>d\n<b\n>e")
+            .assert()
+            .success()
+            .stdout("Progress: 2/2 paths, 3 B / 3 B (100%), ETA 00:00\n")
+            .stderr("");
+
+        dir.child("d").assert("1");
+        dir.child("e/c").assert("23");
+    }
+
+    #[test]
+    fn verbose() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--progress")
+            .arg("--verbose")
+            .arg("--fail-at-end")
+            .write_stdin("<a\n>b\n<c\n>d")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout(
+                "Copying 'a' to 'b' ... OK\n\
+                 Copying 'c' to 'd' ... FAILED\n\
+                 Progress: 2/2 paths, 1 B / 1 B (100%), ETA 00:00\n",
+            )
+            .stderr("error: Path 'c' not found or user lacks permission\n");
+    }
+
+    #[test]
+    fn jobs() {
+        let dir = temp_dir();
+        let mut input = String::new();
+
+        for index in 0..10 {
+            write(dir.child(format!("a{}", index)), "12");
+            input.push_str(&format!("<a{}\n>b{}\n", index, index));
+        }
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--progress")
+            .arg("--jobs=4")
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout("Progress: 10/10 paths, 20 B / 20 B (100%), ETA 00:00\n")
+            .stderr("");
+    }
+}
+
+mod json_lines {
+    use super::*;
+
+    #[test]
+    fn transfers() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "12");
+        write(dir.child("b"), "3");
+        write(dir.child("c"), "4");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--json-lines")
+            .arg("--overwrite=skip")
+            .arg("--fail-at-end")
+            .write_stdin("<a\n>d\n<b\n>c\n<e\n>f")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout(
+                predicate::str::is_match(concat!(
+                    r#"^\{"type":"transfer","src":"a","dst":"d","action":"copy","status":"ok","error":null,"bytes":2,"duration_ms":\d+\}\n"#,
+                    r#"\{"type":"transfer","src":"b","dst":"c","action":"copy","status":"skipped","error":null,"bytes":0,"duration_ms":0\}\n"#,
+                    r#"\{"type":"transfer","src":"e","dst":"f","action":"copy","status":"failed","error":"Path 'e' not found or user lacks permission","bytes":0,"duration_ms":\d+\}\n"#,
+                    r#"\{"type":"summary","total":3,"ok":1,"failed":1,"skipped":1,"bytes":2,"duration_ms":\d+,"rolled_back":false\}\n$"#,
+                ))
+                .unwrap(),
+            )
+            .stderr("error: Path 'e' not found or user lacks permission\n");
+    }
+
+    #[test]
+    fn dry_run() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--json-lines")
+            .arg("--dry-run")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout(
+                predicate::str::is_match(concat!(
+                    r#"^\{"type":"transfer","src":"a","dst":"b","action":"copy","status":"skipped","error":null,"bytes":0,"duration_ms":0\}\n"#,
+                    r#"\{"type":"summary","total":1,"ok":0,"failed":0,"skipped":1,"bytes":0,"duration_ms":\d+,"rolled_back":false\}\n$"#,
+                ))
+                .unwrap(),
+            )
+            .stderr("");
+
+        dir.child("b").assert(predicates::path::missing());
+    }
+}
+
 #[test]
 fn help() {
     cpb()
@@ -3,7 +3,7 @@ mod utils;
 
 use assert_fs::prelude::*;
 use predicates::prelude::*;
-use utils::{mvb, temp_dir, write};
+use utils::{absolute_path, mvb, temp_dir, write};
 
 #[test]
 fn no_input() {
@@ -193,6 +193,682 @@ mod verbose {
     }
 }
 
+mod order {
+    use super::*;
+
+    #[test]
+    fn chain() {
+        let dir = temp_dir();
+
+        let file_1 = write(dir.child("1"), "1");
+        let file_2 = write(dir.child("2"), "2");
+        let file_3 = dir.child("3");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--verbose")
+            .write_stdin("<1\n>2\n<2\n>3")
+            .assert()
+            .success()
+            .stdout("Moving '2' to '3' ... OK\nMoving '1' to '2' ... OK\n")
+            .stderr("");
+
+        file_1.assert(predicates::path::missing());
+        file_2.assert("1");
+        file_3.assert("2");
+    }
+
+    #[test]
+    fn cycle() {
+        let dir = temp_dir();
+
+        let file_a = write(dir.child("a"), "1");
+        let file_b = write(dir.child("b"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--verbose")
+            .write_stdin("<a\n>b\n<b\n>a")
+            .assert()
+            .success()
+            .stdout(concat!(
+                "Moving 'a' to 'a.mvb-tmp' ... OK\n",
+                "Moving 'b' to 'a' ... OK\n",
+                "Moving 'a.mvb-tmp' to 'b' ... OK\n"
+            ))
+            .stderr("");
+
+        file_a.assert("2");
+        file_b.assert("1");
+        dir.child("a.mvb-tmp").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn check() {
+        let dir = temp_dir();
+
+        let file_a = write(dir.child("a"), "1");
+        let file_b = write(dir.child("b"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a\n>b\n<b\n>a")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        file_a.assert("2");
+        file_b.assert("1");
+    }
+}
+
+mod overwrite {
+    use super::*;
+
+    #[test]
+    fn skip() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--overwrite=skip")
+            .arg("--verbose")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("Moving 'a' to 'b' ... SKIPPED\n")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+    }
+
+    #[test]
+    fn fail() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--overwrite=fail")
+            .write_stdin("<a\n>b")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'b' already exists\n");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+    }
+
+    #[test]
+    fn backup_numbered() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+        let backup_file_1 = write(dir.child("b.~1~"), "3");
+        let backup_file_2 = dir.child("b.~2~");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--overwrite=backup")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert(predicates::path::missing());
+        dst_file.assert("1");
+        backup_file_1.assert("3");
+        backup_file_2.assert("2");
+    }
+
+    #[test]
+    fn backup_suffix() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+        let backup_file = dir.child("b.bak");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--overwrite=backup")
+            .arg("--backup-suffix=.bak")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert(predicates::path::missing());
+        dst_file.assert("1");
+        backup_file.assert("2");
+    }
+
+    #[test]
+    fn backup_undo() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+        let backup_file = dir.child("b.~1~");
+        let journal_file = dir.child("journal");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--overwrite=backup")
+            .arg("--journal=journal")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success();
+
+        journal_file.assert(format!(
+            "~{}\n>{}\n<{}\n>{}\n",
+            absolute_path(&dir, "b"),
+            absolute_path(&dir, "b.~1~"),
+            absolute_path(&dir, "a"),
+            absolute_path(&dir, "b")
+        ));
+
+        mvb()
+            .arg("--undo")
+            .arg(journal_file.path())
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+        backup_file.assert(predicates::path::missing());
+    }
+}
+
+#[cfg(unix)]
+mod symlinks {
+    use std::fs::read_link;
+    use std::os::unix::fs::symlink;
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn keep() {
+        let dir = temp_dir();
+        symlink("x", dir.child("a")).unwrap(); // Dangling
+
+        mvb()
+            .current_dir(dir.path())
+            .write_stdin("<a\n>b/c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dir.child("a").assert(predicate::path::missing());
+        assert_eq!(read_link(dir.child("b/c")).unwrap(), Path::new("x"));
+    }
+
+    #[test]
+    fn retarget() {
+        let dir = temp_dir();
+        symlink("x", dir.child("a")).unwrap();
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--symlinks=retarget")
+            .write_stdin("<a\n>b/c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dir.child("a").assert(predicate::path::missing());
+        assert_eq!(read_link(dir.child("b/c")).unwrap(), Path::new("../x"));
+    }
+}
+
+#[cfg(unix)]
+mod preserve {
+    use std::fs;
+    use std::os::unix::fs::{symlink, MetadataExt};
+    use std::path::Path;
+
+    use filetime::FileTime;
+
+    use super::*;
+
+    #[test]
+    fn follow_symlink() {
+        let dir = temp_dir();
+
+        let target_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("c");
+        let mtime = FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(target_file.path(), mtime).unwrap();
+        symlink("a", dir.child("b")).unwrap();
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--symlinks=follow")
+            .arg("--preserve")
+            .write_stdin("<b\n>c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dir.child("b").assert(predicate::path::missing());
+        dst_file.assert("1");
+        assert_eq!(get_mtime(dst_file.path()), mtime);
+    }
+
+    #[test]
+    fn other_file_system() {
+        let dir = temp_dir();
+        let src_root = Path::new("/dev/shm");
+
+        if !src_root.is_dir()
+            || src_root.metadata().unwrap().dev() == dir.path().metadata().unwrap().dev()
+        {
+            return; // No other file system is available
+        }
+
+        // Source is moved away, so there is nothing to clean up
+        let src_path = src_root.join(format!("mvb-preserve-{}", std::process::id()));
+        let dst_file = dir.child("b/c");
+        fs::write(&src_path, "1").unwrap();
+        let mtime = FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(&src_path, mtime).unwrap();
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--preserve")
+            .write_stdin(format!("<{}\n>b/c", src_path.to_string_lossy()))
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        assert!(!src_path.exists());
+        dst_file.assert("1");
+        assert_eq!(get_mtime(dst_file.path()), mtime);
+    }
+
+    fn get_mtime(path: &Path) -> FileTime {
+        FileTime::from_last_modification_time(&path.metadata().unwrap())
+    }
+}
+
+mod check {
+    use super::*;
+
+    #[test]
+    fn success() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert(predicates::path::missing());
+        dst_file.assert("1");
+    }
+
+    #[test]
+    fn failure() {
+        let dir = temp_dir();
+
+        let src_file_1 = dir.child("a1");
+        let src_file_2 = write(dir.child("a2"), "2");
+        let src_file_3 = write(dir.child("a3"), "3");
+
+        let dst_file_2 = dir.child("b2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a1\n>b1\n<a2\n>b2\n<a3\n>b2")
+            .assert()
+            .failure()
+            .code(3)
+            .stdout("")
+            .stderr(concat!(
+                "error: Path 'a1' not found or user lacks permission\n",
+                "error: Path 'b2' is a destination of both 'a2' and 'a3'\n"
+            ));
+
+        src_file_1.assert(predicates::path::missing());
+        src_file_2.assert("2");
+        src_file_3.assert("3");
+
+        dst_file_2.assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn dry_run() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--dry-run")
+            .arg("--verbose")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("Moving 'a' to 'b' ... SKIPPED\n")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert(predicates::path::missing());
+    }
+}
+
+mod transaction {
+    use super::*;
+
+    #[test]
+    fn rollback() {
+        let dir = temp_dir();
+
+        let src_file_1 = write(dir.child("a1"), "1");
+        let src_file_2 = write(dir.child("a2"), "2");
+        let dst_file_1 = dir.child("b/c1");
+        let dst_file_2 = dir.child("b/c2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--transaction")
+            .arg("--verbose")
+            .write_stdin("<a1\n>b/c1\n<a2\n>b/c2\n<a3\n>b/c3")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout(concat!(
+                "Moving 'a1' to 'b/c1' ... OK\n",
+                "Moving 'a2' to 'b/c2' ... OK\n",
+                "Moving 'a3' to 'b/c3' ... FAILED\n",
+                "Moving 'b/c2' to 'a2' ... OK\n",
+                "Moving 'b/c1' to 'a1' ... OK\n",
+                "Removing 'b' ... OK\n",
+            ))
+            .stderr("error: Path 'a3' not found or user lacks permission\n");
+
+        src_file_1.assert("1");
+        src_file_2.assert("2");
+        dst_file_1.assert(predicates::path::missing());
+        dst_file_2.assert(predicates::path::missing());
+        dir.child("b").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn rollback_created_dirs_of_failed() {
+        let dir = temp_dir();
+
+        let src_file_1 = write(dir.child("a1"), "1");
+        let src_file_2 = write(dir.child("a2"), "2");
+        let dst_path_2 = format!("c/d/{}", "x".repeat(300)); // Fails after parent directories are created
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--transaction")
+            .write_stdin(format!("<a1\n>b1\n<a2\n>{}", dst_path_2))
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("");
+
+        src_file_1.assert("1");
+        src_file_2.assert("2");
+        dir.child("b1").assert(predicates::path::missing());
+        dir.child("c").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn rollback_overwritten() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--transaction")
+            .arg("--verbose")
+            .write_stdin("<a\n>b\n<c\n>d")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout(concat!(
+                "Moving 'a' to 'b' ... OK\n",
+                "Moving 'c' to 'd' ... FAILED\n",
+                "Moving 'b' to 'a' ... OK\n",
+                "Moving 'b.~tmp1~' to 'b' ... OK\n",
+            ))
+            .stderr("error: Path 'c' not found or user lacks permission\n");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+        dir.child("b.~tmp1~").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn commit_overwritten() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--transaction")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert(predicates::path::missing());
+        dst_file.assert("1");
+        dir.child("b.~tmp1~").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn rollback_with_journal() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a1"), "1");
+        let journal_file = dir.child("journal");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--transaction")
+            .arg("--journal=journal")
+            .write_stdin("<a1\n>b1\n<a2\n>b2")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'a2' not found or user lacks permission\n");
+
+        src_file.assert("1");
+        journal_file.assert(format!(
+            "<{}\n>{}\n<{}\n>{}\n",
+            absolute_path(&dir, "a1"),
+            absolute_path(&dir, "b1"),
+            absolute_path(&dir, "b1"),
+            absolute_path(&dir, "a1")
+        ));
+    }
+}
+
+mod journal {
+    use super::*;
+
+    #[test]
+    fn undo() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b/c/d");
+        let journal_file = dir.child("journal");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--journal=journal")
+            .write_stdin("<a\n>b/c/d")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert(predicates::path::missing());
+        dst_file.assert("1");
+        journal_file.assert(format!(
+            "+{}\n+{}\n<{}\n>{}\n",
+            absolute_path(&dir, "b"),
+            absolute_path(&dir, "b/c"),
+            absolute_path(&dir, "a"),
+            absolute_path(&dir, "b/c/d")
+        ));
+
+        mvb()
+            .arg("--undo")
+            .arg(journal_file.path())
+            .arg("--verbose")
+            .assert()
+            .success()
+            .stdout(format!(
+                "Moving '{}' to '{}' ... OK\nRemoving '{}' ... OK\nRemoving '{}' ... OK\n",
+                absolute_path(&dir, "b/c/d"),
+                absolute_path(&dir, "a"),
+                absolute_path(&dir, "b/c"),
+                absolute_path(&dir, "b")
+            ))
+            .stderr("");
+
+        src_file.assert("1");
+        dir.child("b").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn undo_keeps_nonempty_dir() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let journal_file = write(dir.child("journal"), "+b\n<a\n>b/c\n");
+        let other_file = write(dir.child("b/d"), "2");
+        write(dir.child("b/c"), "1");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--undo")
+            .arg(journal_file.path())
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        other_file.assert("2");
+        dir.child("b/c").assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn undo_failure() {
+        let dir = temp_dir();
+
+        write(dir.child("journal"), "<a\n>b\n");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--undo=journal")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'b' not found or user lacks permission\n");
+    }
+}
+
+mod progress {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+        write(dir.child("b/c"), "23");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--progress")
+            .write_stdin("<a\n>d\n<b\n>e")
+            .assert()
+            .success()
+            .stdout("Progress: 2/2 paths, 3 B / 3 B (100%), ETA 00:00\n")
+            .stderr("");
+
+        dir.child("d").assert("1");
+        dir.child("e/c").assert("23");
+    }
+}
+
+mod json_lines {
+    use super::*;
+
+    #[test]
+    fn rollback() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--json-lines")
+            .arg("--transaction")
+            .write_stdin("<a\n>b\n<c\n>d")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout(
+                predicate::str::is_match(concat!(
+                    r#"^\{"type":"transfer","src":"a","dst":"b","action":"move","status":"ok","error":null,"bytes":0,"duration_ms":\d+\}\n"#,
+                    r#"\{"type":"transfer","src":"c","dst":"d","action":"move","status":"failed","error":"Path 'c' not found or user lacks permission","bytes":0,"duration_ms":\d+\}\n"#,
+                    r#"\{"type":"summary","total":2,"ok":1,"failed":1,"skipped":0,"bytes":0,"duration_ms":\d+,"rolled_back":true\}\n$"#,
+                ))
+                .unwrap(),
+            )
+            .stderr("error: Path 'c' not found or user lacks permission\n");
+
+        src_file.assert("1");
+        dir.child("b").assert(predicates::path::missing());
+    }
+}
+
 #[test]
 fn help() {
     mvb()
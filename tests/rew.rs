@@ -148,6 +148,64 @@ mod input_terminator {
     }
 }
 
+mod input_normalization {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn pattern() {
+        rew()
+            .arg("--normalize-input=nfc")
+            .arg("{r:\u{e9}:e}")
+            .write_stdin("cafe\u{301}")
+            .assert()
+            .success()
+            .stdout("cafe\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn no_pattern() {
+        rew()
+            .arg("--normalize-input=nfc")
+            .write_stdin("cafe\u{301}")
+            .assert()
+            .success()
+            .stdout("caf\u{e9}\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn diff_keeps_original() {
+        rew()
+            .arg("--normalize-input=nfc")
+            .arg("--diff")
+            .write_stdin("cafe\u{301}")
+            .assert()
+            .success()
+            .stdout("<cafe\u{301}\n>caf\u{e9}\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn file_filters_use_original() {
+        let dir = TempDir::new().unwrap();
+        dir.child("cafe\u{301}").write_str("abc").unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("--normalize-input=nfc")
+            .arg("{}:{ms}:{q:f:t:z}")
+            .write_stdin("cafe\u{301}")
+            .assert()
+            .success()
+            .stdout("caf\u{e9}:3:caf\u{e9}\n")
+            .stderr("");
+    }
+}
+
 mod output_terminator {
     use super::*;
 
@@ -313,6 +371,56 @@ mod regex {
     }
 }
 
+mod glob {
+    use super::*;
+
+    #[test]
+    fn value() {
+        rew()
+            .arg("--glob=*_*.jpg")
+            .arg("{$2}_{$1}.jpg")
+            .write_stdin("a_b.jpg")
+            .assert()
+            .success()
+            .stdout("b_a.jpg\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn filename() {
+        rew()
+            .arg("--glob-filename=file_?")
+            .arg("{$1}")
+            .write_stdin("dir_1/file_2")
+            .assert()
+            .success()
+            .stdout("2\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn filter() {
+        rew()
+            .arg("{Gs:*_*:$2_$1}")
+            .write_stdin("a_b")
+            .assert()
+            .success()
+            .stdout("b_a\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn star_delimiter() {
+        rew()
+            .arg("{s*b*X}")
+            .write_stdin("abc")
+            .assert()
+            .success()
+            .stdout("aXc\n")
+            .stderr("");
+    }
+}
+
 mod errors {
     use super::*;
 
@@ -443,6 +551,530 @@ mod working_dir {
     }
 }
 
+mod metadata {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn size() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").write_str("abc").unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{f}_{ms}")
+            .write_stdin("a")
+            .assert()
+            .success()
+            .stdout("a_3\n");
+    }
+
+    #[test]
+    fn file_type() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").write_str("abc").unwrap();
+        dir.child("b").create_dir_all().unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{mt}_{f}")
+            .write_stdin("a\nb")
+            .assert()
+            .success()
+            .stdout("file_a\ndir_b\n");
+    }
+
+    #[test]
+    fn missing() {
+        let dir = TempDir::new().unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{ms}")
+            .write_stdin("a")
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::starts_with(
+                "error: 'File size in bytes' evaluation failed for value 'a': Cannot read file metadata: ",
+            ));
+    }
+}
+
+mod exif {
+    use std::io::Cursor;
+
+    use ::exif::experimental::Writer;
+    use ::exif::{Field, In, Tag, Value};
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn photo_name() {
+        let dir = TempDir::new().unwrap();
+        let model = Field {
+            tag: Tag::Model,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"Camera".to_vec()]),
+        };
+        let date_time = Field {
+            tag: Tag::DateTimeOriginal,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"2021:05:04 10:20:30".to_vec()]),
+        };
+
+        let mut writer = Writer::new();
+        let mut output = Cursor::new(Vec::new());
+        writer.push_field(&model);
+        writer.push_field(&date_time);
+        writer.write(&mut output, false).unwrap();
+        dir.child("a.tiff").write_binary(output.get_ref()).unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{xDateTimeOriginal|Ti/%%F %%T/%%Y%%m%%d}_{xModel}_{xLensModel|?unknown}")
+            .write_stdin("a.tiff")
+            .assert()
+            .success()
+            .stdout("20210504_Camera_unknown\n");
+    }
+
+    #[test]
+    fn no_exif() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").write_str("abc").unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{xModel}")
+            .write_stdin("a")
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::starts_with(
+                "error: 'Image EXIF tag 'Model'' evaluation failed for value 'a': Cannot read EXIF data: ",
+            ));
+    }
+}
+
+mod audio {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn id3v1() {
+        let dir = TempDir::new().unwrap();
+        let mut tag = [0; 128];
+        tag[..3].copy_from_slice(b"TAG");
+        tag[3..7].copy_from_slice(b"Song");
+        tag[33..39].copy_from_slice(b"Artist");
+        tag[63..68].copy_from_slice(b"Album");
+        tag[126] = 3; // Track number
+        dir.child("a.mp3").write_binary(&tag).unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{ga}/{gb}/{gn|<2:0} {gt}.{e}")
+            .write_stdin("a.mp3")
+            .assert()
+            .success()
+            .stdout("Artist/Album/03 Song.mp3\n");
+    }
+
+    #[test]
+    fn flac() {
+        let dir = TempDir::new().unwrap();
+        let comment = b"ARTIST=Artist";
+        let mut data = b"fLaC".to_vec();
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 34]); // STREAMINFO
+        data.extend_from_slice(&[0x10, 0x00, 0x10, 0x00]); // Block sizes
+        data.extend_from_slice(&[0; 6]); // Frame sizes
+        data.extend_from_slice(&[0x0A, 0xC4, 0x42, 0xF0]); // 44.1 kHz, 2 channels, 16 bits
+        data.extend_from_slice(&[0; 20]); // Sample count and MD5
+        data.extend_from_slice(&[0x84, 0x00, 0x00, 25]); // Last block, VORBIS_COMMENT
+        data.extend_from_slice(&[0, 0, 0, 0, 1, 0, 0, 0, comment.len() as u8, 0, 0, 0]);
+        data.extend_from_slice(comment);
+        data.extend_from_slice(&[0xFF, 0xF8, 0xC9, 0x18, 0x00, 0xC2]); // Frame header with CRC
+        dir.child("a.flac").write_binary(&data).unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{ga}/{f}")
+            .write_stdin("a.flac")
+            .assert()
+            .success()
+            .stdout("Artist/a.flac\n");
+    }
+
+    #[test]
+    fn unsupported() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").write_str("abc").unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{ga}")
+            .write_stdin("a")
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::starts_with(
+                "error: 'Audio artist' evaluation failed for value 'a': Cannot read audio tags: ",
+            ));
+    }
+}
+
+mod hash {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn input() {
+        rew()
+            .arg("{hm}")
+            .write_stdin("abc")
+            .assert()
+            .success()
+            .stdout("900150983cd24fb0d6963f7d28e17f72\n");
+    }
+
+    #[test]
+    fn file_content() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a.txt").write_str("abc").unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{HS8}.{e}")
+            .write_stdin("a.txt")
+            .assert()
+            .success()
+            .stdout("ba7816bf.txt\n");
+    }
+
+    #[test]
+    fn missing_file() {
+        let dir = TempDir::new().unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{Hc}")
+            .write_stdin("a")
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::starts_with(
+                "error: 'CRC32 hash of file content' evaluation failed for value 'a': Cannot read file: ",
+            ));
+    }
+}
+
+mod case {
+    use super::*;
+
+    #[test]
+    fn styles() {
+        rew()
+            .arg("{b|~k}.{e} {b|~c}")
+            .write_stdin("HTTPServer config.RS\nmy photo 2021.JPG")
+            .assert()
+            .success()
+            .stdout("http-server-config.RS httpServerConfig\nmy-photo-2021.JPG myPhoto2021\n");
+    }
+}
+
+mod sanitize {
+    use super::*;
+
+    #[test]
+    fn windows() {
+        rew()
+            .arg("{!w}")
+            .write_stdin("Report: Q1/Q2?.pdf\nbackup/con.txt\nnotes. ")
+            .assert()
+            .success()
+            .stdout("Report_ Q1/Q2_.pdf\nbackup/con_.txt\nnotes\n");
+    }
+}
+
+mod date {
+    use super::*;
+
+    #[test]
+    fn input() {
+        rew()
+            .arg("{TI:%%d.%%m.%%Y:%%Y-%%m-%%d}")
+            .write_stdin("04.05.2021")
+            .assert()
+            .success()
+            .stdout("2021-05-04\n");
+    }
+
+    #[test]
+    fn invalid_format() {
+        rew()
+            .arg("{Tn:%%Q}")
+            .assert()
+            .failure()
+            .code(3)
+            .stderr(predicate::str::contains(
+                "https://docs.rs/chrono/0.4/chrono/format/strftime/",
+            ));
+    }
+}
+
+mod arithmetic {
+    use super::*;
+
+    #[test]
+    fn counter() {
+        rew()
+            .arg("{&2:_|o+C}")
+            .write_stdin("IMG_0041\nIMG_0041")
+            .assert()
+            .success()
+            .stdout("0042\n0043\n");
+    }
+
+    #[test]
+    fn not_number() {
+        rew()
+            .arg("{o+1}")
+            .write_stdin("abc")
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::starts_with(
+                "error: 'Add 1' evaluation failed for value 'abc': Input is not a number\n",
+            ));
+    }
+}
+
+mod number_format {
+    use super::*;
+
+    #[test]
+    fn counter() {
+        rew()
+            .arg("{c|kR}-{C|k03}")
+            .write_stdin("a\nb")
+            .assert()
+            .success()
+            .stdout("I-001\nII-002\n");
+    }
+}
+
+mod conditional {
+    use super::*;
+
+    #[test]
+    fn branches() {
+        rew()
+            .arg("{q:<10:k02:o*10%|k,}")
+            .write_stdin("7\n120")
+            .assert()
+            .success()
+            .stdout("07\n1,200\n");
+    }
+}
+
+mod lookup {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn found() {
+        let dir = TempDir::new().unwrap();
+        dir.child("skus.csv")
+            .write_str("SKU-1,Red Shirt\nSKU-2,\"Jeans, Blue\"\n")
+            .unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{b|l:skus.csv}.{e}")
+            .write_stdin("SKU-1.jpg\nSKU-2.png")
+            .assert()
+            .success()
+            .stdout("Red Shirt.jpg\nJeans, Blue.png\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn default() {
+        let dir = TempDir::new().unwrap();
+        dir.child("skus.json")
+            .write_str(r#"{"SKU-1": "Red Shirt"}"#)
+            .unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{l:skus.json:unknown}")
+            .write_stdin("SKU-1\nSKU-2")
+            .assert()
+            .success()
+            .stdout("Red Shirt\nunknown\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn working_dir() {
+        let dir = TempDir::new().unwrap();
+        dir.child("skus.csv")
+            .write_str("SKU-1,Red Shirt\n")
+            .unwrap();
+
+        rew()
+            .arg("--working-directory")
+            .arg(dir.path())
+            .arg("{l:skus.csv}")
+            .write_stdin("SKU-1")
+            .assert()
+            .success()
+            .stdout("Red Shirt\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn missing_key() {
+        let dir = TempDir::new().unwrap();
+        dir.child("skus.tsv")
+            .write_str("SKU-1\tRed Shirt\n")
+            .unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{l:skus.tsv}")
+            .write_stdin("SKU-2")
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::starts_with(
+                "error: 'Lookup in table 'skus.tsv'' evaluation failed for value 'SKU-2': Key 'SKU-2' not found in lookup table",
+            ));
+    }
+
+    #[test]
+    fn missing_table() {
+        let dir = TempDir::new().unwrap();
+
+        rew()
+            .current_dir(dir.path())
+            .arg("{l:skus.csv}")
+            .assert()
+            .failure()
+            .code(3)
+            .stderr(predicate::str::starts_with(
+                "error: Invalid pattern: Cannot load lookup table: ",
+            ));
+    }
+
+    #[test]
+    fn nested_expr() {
+        rew()
+            .arg("{l:{d}.csv}")
+            .assert()
+            .failure()
+            .code(3)
+            .stderr(predicate::str::starts_with(
+                "error: Invalid pattern: Lookup table cannot contain nested expressions\n",
+            ));
+    }
+}
+
+mod nested {
+    use super::*;
+
+    #[test]
+    fn expression() {
+        rew()
+            .arg("{f|r:_:-{d|^}-}")
+            .write_stdin("a/b_c.txt")
+            .assert()
+            .success()
+            .stdout("b-A-c.txt\n");
+    }
+
+    #[test]
+    fn value_with_delimiter() {
+        rew()
+            .arg("{r:{f}:X}")
+            .write_stdin("a:b")
+            .assert()
+            .success()
+            .stdout("X\n");
+    }
+
+    #[test]
+    fn value_with_regex() {
+        rew()
+            .arg("{s:{f}:X}")
+            .write_stdin("a(b")
+            .assert()
+            .success()
+            .stdout("X\n");
+    }
+
+    #[test]
+    fn value_with_glob() {
+        rew()
+            .arg("{G@:{f}:X:Y}")
+            .write_stdin("[ab]")
+            .assert()
+            .success()
+            .stdout("X\n");
+    }
+
+    #[test]
+    fn expanded_filter_invalid() {
+        rew()
+            .arg("{r{d}}")
+            .write_stdin("abc")
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::starts_with(
+                "error: 'Filter with a nested expression' evaluation failed for value 'abc': Invalid filter after expansion:",
+            ));
+    }
+}
+
+mod variable {
+    use super::*;
+
+    #[test]
+    fn reference() {
+        rew()
+            .arg("{b|v|S:[^a-z]:_|as name}/{$name}.{e}")
+            .write_stdin("My File.txt")
+            .assert()
+            .success()
+            .stdout("my_file/my_file.txt\n");
+    }
+
+    #[test]
+    fn undefined() {
+        rew()
+            .arg("{$name}")
+            .write_stdin("abc")
+            .assert()
+            .failure()
+            .code(3)
+            .stderr(predicate::str::starts_with(
+                "error: Invalid pattern: Undefined variable 'name'\n",
+            ));
+    }
+}
+
 mod quote {
     use super::*;
 
@@ -31,3 +31,9 @@ pub fn write<F: FileWriteStr>(file: F, data: &str) -> F {
     file.write_str(data).unwrap();
     file
 }
+
+#[allow(dead_code)]
+pub fn absolute_path(dir: &TempDir, path: &str) -> String {
+    let dir = dir.path().canonicalize().unwrap();
+    dir.join(path).to_string_lossy().into()
+}